@@ -1,8 +1,10 @@
 mod covariance;
 mod jacobi;
 pub mod matrix3x3;
+mod pivoted;
 mod rref;
 
 pub use covariance::*;
 pub use jacobi::*;
+pub use pivoted::*;
 pub use rref::*;
@@ -0,0 +1,106 @@
+use crate::BasicFloat;
+
+/// Compute `Reduced row echelon form` by gauss-jordan elimination with partial pivoting.
+///
+/// Rows could be arrays or `Vec`. A column is skipped when its largest candidate of pivot
+/// is not larger than `tolerance`.
+/// Return columns of pivots, numbers of them is the rank.
+pub fn pivoted_rref<T, R>(matrix: &mut [R], tolerance: T) -> Vec<usize>
+where
+    T: BasicFloat,
+    R: AsRef<[T]> + AsMut<[T]>,
+{
+    let nb_rows = matrix.len();
+    let nb_cols = matrix.first().map_or(0, |row| row.as_ref().len());
+    let mut pivots = Vec::with_capacity(nb_rows);
+    for col in 0..nb_cols {
+        let row = pivots.len();
+        if row == nb_rows {
+            break;
+        }
+        let best = (row + 1..nb_rows).fold(row, |best, r| {
+            if matrix[r].as_ref()[col].abs() > matrix[best].as_ref()[col].abs() {
+                r
+            } else {
+                best
+            }
+        });
+        let lead = matrix[best].as_ref()[col];
+        if lead.abs() <= tolerance || lead.is_nan() {
+            continue;
+        }
+        matrix.swap(row, best);
+        matrix[row].as_mut().iter_mut().for_each(|v| *v /= lead);
+
+        let pivot = matrix[row].as_ref().to_vec();
+        matrix
+            .iter_mut()
+            .enumerate()
+            .filter(|&(r, _)| r != row)
+            .for_each(|(_, other)| {
+                let other = other.as_mut();
+                let factor = other[col];
+                other
+                    .iter_mut()
+                    .zip(&pivot)
+                    .for_each(|(v, &p)| *v -= factor * p);
+            });
+        pivots.push(col);
+    }
+    pivots
+}
+
+/// Solve `a * x = b` of square `a` by [`pivoted_rref`].
+///
+/// `None` if `a` is singular by `tolerance`, or `x` is not finite.
+pub fn pivoted_solve<T, R>(a: &[R], b: &[T], tolerance: T) -> Option<Vec<T>>
+where
+    T: BasicFloat,
+    R: AsRef<[T]>,
+{
+    let n = b.len();
+    let mut augmented = a
+        .iter()
+        .zip(b)
+        .map(|(row, &v)| {
+            let mut row = row.as_ref().to_vec();
+            row.push(v);
+            row
+        })
+        .collect::<Vec<_>>();
+    let pivots = pivoted_rref(&mut augmented, tolerance);
+    // Pivots are ascending, so all of them are in `a` when the last is.
+    if pivots.len() < n || pivots.last().is_some_and(|&c| c >= n) {
+        return None;
+    }
+    let x = augmented.iter().map(|row| row[n]).collect::<Vec<_>>();
+    x.iter().all(|v| v.is_finite()).then_some(x)
+}
+
+#[cfg(test)]
+mod test_pivoted {
+    use super::*;
+
+    #[test]
+    fn solve() {
+        // Zero on diagonal needs pivoting.
+        let a = [[0f64, 2., 1.], [1., 1., 0.], [2., 0., 3.]];
+        let b = [7f64, 3., 11.];
+        let x = pivoted_solve(&a, &b, 1e-12).unwrap();
+        [1f64, 2., 3.]
+            .iter()
+            .zip(&x)
+            .for_each(|(e, v)| assert!((e - v).abs() < 1e-12));
+
+        let singular = vec![vec![1f32, 2.], vec![2., 4.]];
+        assert!(pivoted_solve(&singular, &[1., 2.], 1e-6).is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let mut matrix = [[1f64, 2., 3.], [2., 4., 6.], [1., 0., 1.]];
+        let pivots = pivoted_rref(&mut matrix, 1e-12);
+        assert_eq!(pivots, vec![0, 1]);
+        assert_eq!(matrix[2], [0., 0., 0.]);
+    }
+}
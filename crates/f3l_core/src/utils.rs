@@ -3,9 +3,11 @@ mod edge_linker;
 mod model;
 mod ops;
 mod project;
+mod random;
 
 pub use bound::*;
 pub use edge_linker::*;
 pub use model::*;
 pub use ops::*;
 pub use project::*;
+pub use random::*;
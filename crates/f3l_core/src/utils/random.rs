@@ -0,0 +1,15 @@
+use rand_core::SeedableRng;
+
+/// Generator of random algorithms, like samplers and `SAC`.
+///
+/// `ChaCha8Rng` is portable, same seed gives same numbers on any platform and version,
+/// unlike `StdRng` of `rand`, which may change between versions.
+pub type SeededRng = rand_chacha::ChaCha8Rng;
+
+/// Generator of `seed`, or from entropy if `None`.
+pub fn seeded_rng(seed: Option<u64>) -> SeededRng {
+    match seed {
+        Some(seed) => SeededRng::seed_from_u64(seed),
+        None => SeededRng::from_entropy(),
+    }
+}
@@ -24,6 +24,8 @@ use f3l_search_tree::*;
 ///
 /// // Use Radius Search
 /// // let mut estimator = NormalEstimation::new(SearchBy::Radius(0.08f32));
+/// // Use KNN inside radius
+/// // let mut estimator = NormalEstimation::new(SearchBy::Hybrid { k: 10, radius: 0.08f32 });
 /// // Use KNN Search
 /// let mut estimator = NormalEstimation::new(SearchBy::Count(10));
 /// if !estimator.compute(&vertices) {
@@ -40,8 +42,8 @@ where
     P: Into<[T; 3]> + Clone + Copy + Index<usize, Output = T>,
     [T; 3]: Into<P>,
 {
-    /// Use Radius or KNN to search neighbors.
-    pub method: SearchBy,
+    /// Use Radius, KNN or Hybrid to search neighbors.
+    pub method: SearchBy<T>,
     /// Use more rigorous methods or not. Default: true.
     /// - true  : use fast method.
     /// - false : rigorous method.
@@ -59,7 +61,7 @@ where
     P: Into<[T; 3]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
     [T; 3]: Into<P>,
 {
-    pub fn new(method: SearchBy) -> Self {
+    pub fn new(method: SearchBy<T>) -> Self {
         Self {
             method,
             fast: true,
@@ -102,6 +104,12 @@ where
                         .map(|&(p, _)| p)
                         .collect(),
                     SearchBy::Radius(r) => self.tree.search_radius(&data[i], r),
+                    SearchBy::Hybrid { k, radius } => self
+                        .tree
+                        .search_hybrid(&data[i], k, radius)
+                        .iter()
+                        .map(|&(p, _)| p)
+                        .collect(),
                 };
 
                 if cloud.len() == 1 {
@@ -0,0 +1,181 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::serde::{self, Deserialize, Serialize};
+use f3l_core::BasicFloat;
+use std::fmt::Debug;
+
+/// Voxel grid with a fixed size hash table, like `ApproximateVoxelGrid` of PCL.
+///
+/// Points are hashed by integer coordinate `floor(p / leaf)` into `histogram_size` cells.
+/// When a point falls in a cell occupied by another voxel, the old one is flushed to output.
+/// Memory is constant and data is visited once, suitable for streaming,
+/// but a voxel may be output more than once if it is evicted by collision.
+/// Output order only depends on order of data.
+///
+/// # Examples
+/// ```rust
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+///
+/// let mut filter = ApproximateVoxelGrid::with_data(&[0.05; 3]);
+/// let out = filter.filter_instance(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ApproximateVoxelGrid<T: BasicFloat, const D: usize> {
+    pub leaf: Vec<T>,
+    pub histogram_size: usize,
+    /// `(first point id, centroid)` of flushed voxels.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    voxels: Vec<(usize, Vec<T>)>,
+}
+
+/// A cell of hash table, accumulating points of one voxel.
+#[derive(Debug, Clone)]
+struct ApproximateCell<T: BasicFloat, const D: usize> {
+    key: [i64; D],
+    first: usize,
+    count: usize,
+    sum: [T; D],
+}
+
+impl<T: BasicFloat, const D: usize> Default for ApproximateVoxelGrid<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BasicFloat, const D: usize> ApproximateVoxelGrid<T, D> {
+    pub fn new() -> Self {
+        Self {
+            leaf: vec![T::zero(); D],
+            histogram_size: 512,
+            voxels: vec![],
+        }
+    }
+
+    pub fn with_data<P: Into<[T; D]> + Copy>(leaf: &P) -> Self {
+        let leaf: [T; D] = (*leaf).into();
+        Self {
+            leaf: leaf.iter().copied().collect(),
+            histogram_size: 512,
+            voxels: vec![],
+        }
+    }
+
+    pub fn set_leaf<P: Into<[T; D]> + Copy>(&mut self, leaf: &P) {
+        let leaf: [T; D] = (*leaf).into();
+        self.leaf = leaf.iter().copied().collect();
+    }
+
+    /// Number of cells of hash table. Default: 512.
+    pub fn set_histogram_size(&mut self, histogram_size: usize) {
+        self.histogram_size = histogram_size;
+    }
+
+    #[inline]
+    fn hash(&self, key: &[i64; D]) -> usize {
+        const PRIMES: [u64; 3] = [73856093, 19349669, 83492791];
+        let h = key.iter().enumerate().fold(0u64, |acc, (i, &k)| {
+            acc ^ (k as u64).wrapping_mul(PRIMES[i % 3])
+        });
+        (h % self.histogram_size as u64) as usize
+    }
+
+    fn flush(&mut self, cell: ApproximateCell<T, D>) {
+        let factor = T::one() / T::from(cell.count).unwrap();
+        let centroid = cell.sum.iter().map(|&v| v * factor).collect();
+        self.voxels.push((cell.first, centroid));
+    }
+}
+
+impl<T: BasicFloat, const D: usize> F3lFilterInverse for ApproximateVoxelGrid<T, D> {
+    fn set_negative(&mut self, _negative: bool) {}
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for ApproximateVoxelGrid<T, D>
+where
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Debug,
+    [T; D]: Into<P>,
+{
+    /// Get first `point id` of each flushed voxel.
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.voxels.iter().map(|(i, _)| *i).collect()
+    }
+
+    /// Get `mean point` of each flushed voxel.
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.voxels
+            .iter()
+            .map(|(_, centroid)| {
+                let mut p = [T::zero(); D];
+                (0..D).for_each(|i| p[i] = centroid[i]);
+                p.into()
+            })
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.voxels.clear();
+        if data.is_empty()
+            || self.histogram_size == 0
+            || self.leaf.len() != D
+            || self.leaf.iter().any(|&l| l <= T::zero())
+        {
+            return false;
+        }
+        let inverse = self.leaf.iter().map(|&l| T::one() / l).collect::<Vec<_>>();
+
+        let mut cells: Vec<Option<ApproximateCell<T, D>>> = vec![None; self.histogram_size];
+        data.iter().enumerate().for_each(|(i, p)| {
+            let p: [T; D] = (*p).into();
+            let mut key = [0i64; D];
+            for d in 0..D {
+                key[d] = match (p[d] * inverse[d]).floor().to_i64() {
+                    Some(k) => k,
+                    None => return,
+                };
+            }
+            let h = self.hash(&key);
+            match &mut cells[h] {
+                Some(cell) if cell.key == key => {
+                    cell.count += 1;
+                    (0..D).for_each(|d| cell.sum[d] += p[d]);
+                }
+                slot => {
+                    if let Some(old) = slot.replace(ApproximateCell {
+                        key,
+                        first: i,
+                        count: 1,
+                        sum: p,
+                    }) {
+                        self.flush(old);
+                    }
+                }
+            }
+        });
+        cells
+            .into_iter()
+            .flatten()
+            .for_each(|cell| self.flush(cell));
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = ApproximateVoxelGrid::with_data(&[0.05f32; 3]);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+    let text = r#"{"leaf":[0.05,0.05,0.05],"histogram_size":512}"#;
+    let model_de: ApproximateVoxelGrid<f32, 3> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.leaf, model_de.leaf);
+    assert_eq!(model.histogram_size, model_de.histogram_size);
+}
@@ -0,0 +1,154 @@
+use std::ops::Index;
+
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_search_tree::{KdTree, TreeSearch};
+
+/// Edge-preserving smoothing, which displaces points along their normals.
+///
+/// Ref: Fleishman et al., Bilateral Mesh Denoising.
+///
+/// For neighbors `q` in `2 * sigma_s` of point `p` with normal `n`,
+/// offset `h = n · (q - p)` is averaged with weight `w_s * w_r`:
+/// * `w_s = exp(-|q - p|^2 / 2 sigma_s^2)`
+/// * `w_r = exp(-r^2 / 2 sigma_r^2)`, `r` is `h`, or difference of intensities if set.
+///
+/// Then `p + n * mean(h)`. Neighbors across a sharp edge have large `r`,
+/// so they hardly move the point. Points without normal are not moved.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut estimator = NormalEstimation::new(SearchBy::Count(10));
+/// estimator.compute(&vertices);
+///
+/// let mut filter = BilateralFilter::new(0.01f32, 0.005);
+/// filter.set_normals(&estimator.normals());
+/// let smoothed = filter.smooth(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct BilateralFilter<T: BasicFloat> {
+    /// Spatial sigma, neighbors are searched in `2 * sigma_s`.
+    pub sigma_s: T,
+    /// Range sigma of normal offset, or intensity.
+    pub sigma_r: T,
+    /// Times to apply. Normals are not re-estimated between iterations.
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<Option<[T; 3]>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    intensities: Vec<T>,
+}
+
+impl<T: BasicFloat> BilateralFilter<T> {
+    pub fn new(sigma_s: T, sigma_r: T) -> Self {
+        Self {
+            sigma_s,
+            sigma_r,
+            iterations: 1,
+            normals: vec![],
+            intensities: vec![],
+        }
+    }
+
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations;
+    }
+
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+
+    /// Use intensities as range instead of normal offsets. Empty to clear.
+    pub fn set_intensities(&mut self, intensities: &[T]) {
+        self.intensities = intensities.to_vec();
+    }
+
+    /// Return smoothed data, or empty if normals or intensities do not match data.
+    pub fn smooth<P>(&self, data: &[P]) -> Vec<P>
+    where
+        P: Into<[T; 3]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+        [T; 3]: Into<P>,
+    {
+        if data.is_empty()
+            || self.normals.len() != data.len()
+            || !(self.intensities.is_empty() || self.intensities.len() == data.len())
+        {
+            return vec![];
+        }
+        let two = T::from(2).unwrap();
+        let sqr_s = two * self.sigma_s.powi(2);
+        let sqr_r = two * self.sigma_r.powi(2);
+        let radius = two * self.sigma_s;
+
+        let mut current = data.to_vec();
+        (0..self.iterations).for_each(|_| {
+            let mut tree = KdTree::<T, P>::new(3);
+            tree.set_data(&current);
+            tree.build();
+
+            let smoothed = (0..current.len())
+                .into_par_iter()
+                .map(|i| {
+                    let p: [T; 3] = current[i].into();
+                    let n = match self.normals[i] {
+                        Some(n) => n,
+                        None => return current[i],
+                    };
+                    let (sum, weight) = tree
+                        .search_radius_ids(&current[i], radius)
+                        .into_iter()
+                        .fold((T::zero(), T::zero()), |(sum, weight), j| {
+                            let q: [T; 3] = current[j].into();
+                            let d = [q[0] - p[0], q[1] - p[1], q[2] - p[2]];
+                            let h = d[0] * n[0] + d[1] * n[1] + d[2] * n[2];
+                            let r = if self.intensities.is_empty() {
+                                h
+                            } else {
+                                self.intensities[j] - self.intensities[i]
+                            };
+                            let w = (-(d[0] * d[0] + d[1] * d[1] + d[2] * d[2]) / sqr_s
+                                - r * r / sqr_r)
+                                .exp();
+                            (sum + w * h, weight + w)
+                        });
+                    if weight <= T::zero() {
+                        return current[i];
+                    }
+                    let offset = sum / weight;
+                    [
+                        p[0] + offset * n[0],
+                        p[1] + offset * n[1],
+                        p[2] + offset * n[2],
+                    ]
+                    .into()
+                })
+                .collect::<Vec<_>>();
+            current = smoothed;
+        });
+        current
+    }
+}
+
+#[test]
+fn serde() {
+    let model = BilateralFilter::new(0.01f32, 0.005);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "sigma_s":0.01,
+        "sigma_r":0.005,
+        "iterations":1
+    }"#;
+    let model_de: BilateralFilter<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.sigma_s, model_de.sigma_s);
+    assert_eq!(model.sigma_r, model_de.sigma_r);
+    assert_eq!(model.iterations, model_de.iterations);
+}
@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Cloth Simulation Filter, ground extraction for steep and urban terrain.
+///
+/// Ref: Zhang et al., An Easy-to-Use Airborne LiDAR Data Filtering Method Based on Cloth Simulation.
+///
+/// 1. Invert elevation (`Z`) of data, so ground becomes the top of cloud.
+/// 2. Grid of particles by `cloth_resolution` on `XY` is dropped from above by gravity.
+///    Each particle stops at the highest inverted point nearest to it, empty ones
+///    take the value of nearest filled particle.
+/// 3. Springs between 4-neighbors pull particles `rigidness` times per step,
+///    which keeps the cloth from falling into objects.
+/// 4. Points within `class_threshold` to the settled cloth are ground.
+///
+/// Springs are solved by Jacobi iterations, so result is deterministic and parallel.
+/// `filter` returns ground points, or non-ground if `negative`.
+/// Points of non-finite coordinates are in neither, and result is empty if `cloth_resolution` is not positive.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/aerial_lidar.ply");
+/// let mut filter = ClothSimulationFilter::new(0.5f32);
+/// filter.set_rigidness(3);
+/// let ground = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ClothSimulationFilter<T: BasicFloat> {
+    pub negative: bool,
+    /// Distance between particles.
+    pub cloth_resolution: T,
+    /// Times to satisfy springs per step, 1 for steep and 3 for flat terrain. Default: 3.
+    pub rigidness: usize,
+    /// Default: 0.65.
+    pub time_step: T,
+    /// Max iterations of simulation. Default: 500.
+    pub iterations: usize,
+    /// Distance to cloth to be ground. Default: 0.5.
+    pub class_threshold: T,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    /// Ground or not of each point, `None` if skipped.
+    ground: Vec<Option<bool>>,
+}
+
+/// Gravity acceleration of cloth.
+const GRAVITY: f64 = 0.2;
+/// Damping of velocity.
+const DAMPING: f64 = 0.01;
+/// Stop simulation if max movement of particles is less than it.
+const STABLE: f64 = 0.005;
+
+impl<T: BasicFloat> ClothSimulationFilter<T> {
+    pub fn new(cloth_resolution: T) -> Self {
+        Self {
+            negative: false,
+            cloth_resolution,
+            rigidness: 3,
+            time_step: T::from(0.65).unwrap(),
+            iterations: 500,
+            class_threshold: T::from(0.5).unwrap(),
+            ground: vec![],
+        }
+    }
+
+    pub fn set_rigidness(&mut self, rigidness: usize) {
+        self.rigidness = rigidness;
+    }
+
+    pub fn set_time_step(&mut self, time_step: T) {
+        self.time_step = time_step;
+    }
+
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations;
+    }
+
+    pub fn set_class_threshold(&mut self, class_threshold: T) {
+        self.class_threshold = class_threshold;
+    }
+
+    /// Fill empty cells by value of nearest filled cell, breadth first.
+    fn fill_empty(heights: &mut [Option<T>], cols: usize) {
+        let rows = heights.len() / cols;
+        let mut queue = (0..heights.len())
+            .filter(|&i| heights[i].is_some())
+            .collect::<VecDeque<_>>();
+        while let Some(i) = queue.pop_front() {
+            let (r, c) = (i / cols, i % cols);
+            let neighbors = [
+                (r > 0).then(|| i - cols),
+                (r + 1 < rows).then(|| i + cols),
+                (c > 0).then(|| i - 1),
+                (c + 1 < cols).then(|| i + 1),
+            ];
+            neighbors.into_iter().flatten().for_each(|j| {
+                if heights[j].is_none() {
+                    heights[j] = heights[i];
+                    queue.push_back(j);
+                }
+            });
+        }
+    }
+
+    /// Simulate cloth on `limits` of inverted elevation, return heights of particles.
+    fn simulate(&self, limits: &[T], cols: usize, start: T) -> Vec<T> {
+        let rows = limits.len() / cols;
+        let mut heights = vec![start; limits.len()];
+        let mut previous = heights.clone();
+        let mut movable = vec![true; limits.len()];
+        let one = T::one();
+        let half = T::from(0.5).unwrap();
+        let damping = one - T::from(DAMPING).unwrap();
+        let displacement = T::from(GRAVITY).unwrap() * self.time_step * self.time_step;
+        let stable = T::from(STABLE).unwrap();
+
+        for _ in 0..self.iterations {
+            // Gravity, verlet integration.
+            let moved = heights
+                .par_iter()
+                .zip(&previous)
+                .zip(&movable)
+                .map(|((&h, &p), &m)| {
+                    if m {
+                        h + (h - p) * damping - displacement
+                    } else {
+                        h
+                    }
+                })
+                .collect::<Vec<_>>();
+            previous = std::mem::replace(&mut heights, moved);
+
+            // Collision.
+            heights
+                .iter_mut()
+                .zip(&mut movable)
+                .zip(limits)
+                .for_each(|((h, m), &limit)| {
+                    if *m && *h <= limit {
+                        *h = limit;
+                        *m = false;
+                    }
+                });
+
+            // Springs.
+            (0..self.rigidness).for_each(|_| {
+                heights = (0..heights.len())
+                    .into_par_iter()
+                    .map(|i| {
+                        if !movable[i] {
+                            return heights[i];
+                        }
+                        let (r, c) = (i / cols, i % cols);
+                        let neighbors = [
+                            (r > 0).then(|| i - cols),
+                            (r + 1 < rows).then(|| i + cols),
+                            (c > 0).then(|| i - 1),
+                            (c + 1 < cols).then(|| i + 1),
+                        ];
+                        let (sum, count) = neighbors.into_iter().flatten().fold(
+                            (T::zero(), T::zero()),
+                            |(sum, count), j| {
+                                // Fixed neighbor pulls with full strength.
+                                let factor = if movable[j] { half } else { one };
+                                (sum + (heights[j] - heights[i]) * factor, count + one)
+                            },
+                        );
+                        if count > T::zero() {
+                            heights[i] + sum / count
+                        } else {
+                            heights[i]
+                        }
+                    })
+                    .collect();
+            });
+
+            let max_move = heights
+                .iter()
+                .zip(&previous)
+                .zip(&movable)
+                .filter(|(_, &m)| m)
+                .fold(T::zero(), |acc, ((&h, &p), _)| acc.max((h - p).abs()));
+            if max_move < stable || movable.iter().all(|&m| !m) {
+                break;
+            }
+        }
+        heights
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for ClothSimulationFilter<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for ClothSimulationFilter<T>
+where
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.ground
+            .iter()
+            .enumerate()
+            .filter(|&(_, &g)| g == Some(!self.negative))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.ground
+            .iter()
+            .enumerate()
+            .filter(|&(_, &g)| g == Some(!self.negative))
+            .map(|(i, _)| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.ground.clear();
+        if data.is_empty() || self.cloth_resolution.is_nan() || self.cloth_resolution <= T::zero() {
+            return false;
+        }
+        // Inverted.
+        let points = data
+            .iter()
+            .map(|&p| {
+                let p: [T; 3] = p.into();
+                [p[0], p[1], -p[2]]
+            })
+            .collect::<Vec<_>>();
+        let finite = |p: &[T; 3]| p.iter().all(|v| v.is_finite());
+        let (mut min, mut max) = ([T::max_value(); 3], [T::min_value(); 3]);
+        points.iter().filter(|p| finite(p)).for_each(|p| {
+            (0..3).for_each(|i| {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            });
+        });
+        if min[0] > max[0] {
+            return false;
+        }
+
+        // Particles at `min + k * resolution`, points go to the nearest one.
+        let res = self.cloth_resolution;
+        let half = T::from(0.5).unwrap();
+        let cell_of = |v: T, i: usize| ((v - min[i]) / res + half).floor().to_usize();
+        let (cols, rows) = match (cell_of(max[0], 0), cell_of(max[1], 1)) {
+            (Some(x), Some(y)) => (x + 1, y + 1),
+            _ => return false,
+        };
+        if cols.checked_mul(rows).is_none() {
+            return false;
+        }
+        let cells = points
+            .iter()
+            .map(|p| {
+                if !finite(p) {
+                    return None;
+                }
+                Some(cell_of(p[1], 1)? * cols + cell_of(p[0], 0)?)
+            })
+            .collect::<Vec<_>>();
+
+        let mut limits: Vec<Option<T>> = vec![None; rows * cols];
+        points.iter().zip(&cells).for_each(|(p, &c)| {
+            if let Some(c) = c {
+                limits[c] = Some(limits[c].map_or(p[2], |v| v.max(p[2])));
+            }
+        });
+        Self::fill_empty(&mut limits, cols);
+        let limits = limits.into_iter().map(|v| v.unwrap()).collect::<Vec<_>>();
+
+        let start = max[2] + res;
+        let cloth = self.simulate(&limits, cols, start);
+
+        // Bilinear height of cloth at point.
+        let height_at = |p: &[T; 3]| {
+            let x = ((p[0] - min[0]) / res).max(T::zero());
+            let y = ((p[1] - min[1]) / res).max(T::zero());
+            let c0 = x.floor().to_usize().map_or(cols - 1, |c| c.min(cols - 1));
+            let r0 = y.floor().to_usize().map_or(rows - 1, |r| r.min(rows - 1));
+            let (c1, r1) = ((c0 + 1).min(cols - 1), (r0 + 1).min(rows - 1));
+            let (fx, fy) = (x - T::from(c0).unwrap(), y - T::from(r0).unwrap());
+            let (fx, fy) = (fx.min(T::one()), fy.min(T::one()));
+            let top = cloth[r0 * cols + c0] * (T::one() - fx) + cloth[r0 * cols + c1] * fx;
+            let bottom = cloth[r1 * cols + c0] * (T::one() - fx) + cloth[r1 * cols + c1] * fx;
+            top * (T::one() - fy) + bottom * fy
+        };
+        self.ground = points
+            .par_iter()
+            .zip(&cells)
+            .map(|(p, c)| c.map(|_| (height_at(p) - p[2]).abs() <= self.class_threshold))
+            .collect();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = ClothSimulationFilter::new(0.5f32);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "cloth_resolution":0.5,
+        "rigidness":3,
+        "time_step":0.65,
+        "iterations":500,
+        "class_threshold":0.5
+    }"#;
+    let model_de: ClothSimulationFilter<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.cloth_resolution, model_de.cloth_resolution);
+    assert_eq!(model.rigidness, model_de.rigidness);
+    assert_eq!(model.time_step, model_de.time_step);
+    assert_eq!(model.iterations, model_de.iterations);
+    assert_eq!(model.class_threshold, model_de.class_threshold);
+}
@@ -1,3 +1,6 @@
+mod condition;
+pub use condition::{Comparison, Condition, Quantity};
+
 use std::ops::{Bound, Range};
 
 use crate::F3lFilterInverse;
@@ -26,11 +29,34 @@ type DirectionRange<T> = Vec<(usize, Range<Bound<T>>)>;
 /// let out = filter.filter_instance(&vertices);
 /// ```
 ///
+/// A [`Condition`] tree could be set for nested `And`, `Or`, `Not` of comparisons
+/// on coordinates, attributes and distances. Then a point is inlier if it is in all
+/// `bound` and satisfies `condition`, and `negative` gives the complement.
+///
+/// ```
+/// let mut filter = ConditionRemoval::with_condition(Condition::or(vec![
+///     Condition::compare(Quantity::Attribute(0), Comparison::Greater(0.8)),
+///     Condition::compare(
+///         Quantity::DistanceToPlane(plane.coefficients),
+///         Comparison::Range(Bound::Included(-0.01)..Bound::Included(0.01)),
+///     ),
+/// ]));
+/// filter.set_attributes(&intensities);
+/// let out = filter.filter_instance(&vertices);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
 pub struct ConditionRemoval<T: BasicFloat> {
     pub negative: bool,
     pub bound: DirectionRange<T>,
+    pub condition: Option<Condition<T>>,
+    /// Attributes of points, `nb_channels` per point.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    attributes: Vec<T>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    nb_channels: usize,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     inliers: Vec<usize>,
@@ -41,6 +67,9 @@ impl<T: BasicFloat> Default for ConditionRemoval<T> {
         Self {
             negative: false,
             bound: Default::default(),
+            condition: None,
+            attributes: vec![],
+            nb_channels: 0,
             inliers: Default::default(),
         }
     }
@@ -50,15 +79,39 @@ impl<T: BasicFloat> ConditionRemoval<T> {
     // pub fn with_data(data: &'a Vec<P>, bound: &'a Vec<(usize, Bound<T>, Bound<T>)>) -> Self {
     pub fn with_data(bound: &DirectionRange<T>) -> Self {
         Self {
-            negative: false,
             bound: bound.clone(),
-            inliers: Default::default(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_condition(condition: Condition<T>) -> Self {
+        Self {
+            condition: Some(condition),
+            ..Default::default()
         }
     }
 
     pub fn set_parameter(&mut self, bound: &DirectionRange<T>) {
         self.bound = bound.clone();
     }
+
+    pub fn set_condition(&mut self, condition: Option<Condition<T>>) {
+        self.condition = condition;
+    }
+
+    /// Attributes of points in the same order of data, like intensity, label or normal.
+    /// Compared by [`Quantity::Attribute`] of channel.
+    pub fn set_attributes<const N: usize>(&mut self, attributes: &[[T; N]]) {
+        self.attributes = attributes.iter().flatten().copied().collect();
+        self.nb_channels = N;
+    }
+
+    #[inline]
+    fn in_bound(&self, p: &[T]) -> bool {
+        self.bound
+            .iter()
+            .all(|(dim, bound)| Comparison::Range(bound.clone()).test(p[*dim]))
+    }
 }
 
 impl<T: BasicFloat> F3lFilterInverse for ConditionRemoval<T> {
@@ -89,6 +142,31 @@ where
 
     fn apply_filter(&mut self, data: &'a [P]) -> bool {
         use f3l_core::rayon::prelude::*;
+        if let Some(condition) = &self.condition {
+            if self.nb_channels > 0 && self.attributes.len() != data.len() * self.nb_channels {
+                return false;
+            }
+            self.inliers = data
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, &p)| {
+                    let p: [T; D] = p.into();
+                    let attributes = if self.nb_channels > 0 {
+                        &self.attributes[i * self.nb_channels..(i + 1) * self.nb_channels]
+                    } else {
+                        &[]
+                    };
+                    let ok = self.in_bound(&p) && condition.evaluate(&p, attributes);
+                    if ok != self.negative {
+                        Some(i)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            return true;
+        }
+
         self.inliers = data
             .par_iter()
             .enumerate()
@@ -133,11 +211,7 @@ fn serde() {
         (1, Bound::Included(0.)..Bound::Included(0.8)),
         (2, Bound::Included(-1.4)..Bound::Included(-1.3)),
     ];
-    let model = ConditionRemoval {
-        negative: false,
-        inliers: vec![],
-        bound: range,
-    };
+    let model = ConditionRemoval::with_data(&range);
     let content = serde_json::to_string(&model).unwrap();
     println!("{}", content);
 
@@ -152,5 +226,28 @@ fn serde() {
     let model_de: ConditionRemoval<f32> = serde_json::from_str(text).unwrap();
     assert_eq!(model.negative, model_de.negative);
     assert_eq!(model.bound, model_de.bound);
+    assert_eq!(model.condition, model_de.condition);
     assert_eq!(model.inliers, model_de.inliers);
 }
+
+#[test]
+fn serde_condition() {
+    let model = ConditionRemoval::with_condition(Condition::and(vec![
+        Condition::compare(Quantity::Coordinate(2), Comparison::Less(0.5f32)),
+        !Condition::compare(Quantity::Attribute(0), Comparison::In(vec![1., 2.])),
+    ]));
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "bound":[],
+        "condition":{"And":[
+            {"Compare":{"quantity":{"Coordinate":2},"comparison":{"Less":0.5}}},
+            {"Not":{"Compare":{"quantity":{"Attribute":0},"comparison":{"In":[1.0,2.0]}}}}
+        ]}
+    }"#;
+    let model_de: ConditionRemoval<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.condition, model_de.condition);
+}
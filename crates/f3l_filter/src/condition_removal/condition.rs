@@ -0,0 +1,165 @@
+use std::ops::{Bound, Not, Range};
+
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Value of a point to be compared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum Quantity<T: BasicFloat> {
+    /// Coordinate of dimension.
+    Coordinate(usize),
+    /// Channel of attributes, see [`super::ConditionRemoval::set_attributes`].
+    Attribute(usize),
+    /// Euclidean distance to a point.
+    DistanceToPoint(Vec<T>),
+    /// Signed distance to plane `ax + by + cz + d = 0`, coefficients of `PlaneCoefficient`.
+    DistanceToPlane([T; 4]),
+}
+
+/// Comparison of a [`Quantity`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum Comparison<T: BasicFloat> {
+    Less(T),
+    LessEqual(T),
+    Greater(T),
+    GreaterEqual(T),
+    Equal(T),
+    NotEqual(T),
+    Range(Range<Bound<T>>),
+    /// Equal to one of values, like labels.
+    In(Vec<T>),
+}
+
+/// Boolean expression tree of comparisons.
+///
+/// # Examples
+/// ```
+/// // z < 0.5 && !(label in {1, 2})
+/// let condition = Condition::and(vec![
+///     Condition::compare(Quantity::Coordinate(2), Comparison::Less(0.5)),
+///     !Condition::compare(Quantity::Attribute(0), Comparison::In(vec![1., 2.])),
+/// ]);
+/// // Same as JSON:
+/// let text = r#"{"And":[
+///     {"Compare":{"quantity":{"Coordinate":2},"comparison":{"Less":0.5}}},
+///     {"Not":{"Compare":{"quantity":{"Attribute":0},"comparison":{"In":[1.0,2.0]}}}}
+/// ]}"#;
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum Condition<T: BasicFloat> {
+    Compare {
+        quantity: Quantity<T>,
+        comparison: Comparison<T>,
+    },
+    /// True if all are true, or empty.
+    And(Vec<Condition<T>>),
+    /// True if any is true.
+    Or(Vec<Condition<T>>),
+    Not(Box<Condition<T>>),
+}
+
+impl<T: BasicFloat> Comparison<T> {
+    pub fn test(&self, v: T) -> bool {
+        match self {
+            Comparison::Less(t) => v < *t,
+            Comparison::LessEqual(t) => v <= *t,
+            Comparison::Greater(t) => v > *t,
+            Comparison::GreaterEqual(t) => v >= *t,
+            Comparison::Equal(t) => v == *t,
+            Comparison::NotEqual(t) => v != *t,
+            Comparison::Range(range) => {
+                let b_start = match range.start {
+                    Bound::Included(t) => v >= t,
+                    Bound::Excluded(t) => v > t,
+                    Bound::Unbounded => true,
+                };
+                let b_end = match range.end {
+                    Bound::Included(t) => v <= t,
+                    Bound::Excluded(t) => v < t,
+                    Bound::Unbounded => true,
+                };
+                b_start && b_end
+            }
+            Comparison::In(values) => values.contains(&v),
+        }
+    }
+}
+
+impl<T: BasicFloat> Quantity<T> {
+    /// `None` if dimension or channel is out of range.
+    pub fn value(&self, point: &[T], attributes: &[T]) -> Option<T> {
+        match self {
+            Quantity::Coordinate(dim) => point.get(*dim).copied(),
+            Quantity::Attribute(channel) => attributes.get(*channel).copied(),
+            Quantity::DistanceToPoint(target) => {
+                if target.len() > point.len() {
+                    return None;
+                }
+                Some(
+                    target
+                        .iter()
+                        .zip(point)
+                        .fold(T::zero(), |acc, (&t, &p)| acc + (p - t).powi(2))
+                        .sqrt(),
+                )
+            }
+            Quantity::DistanceToPlane(plane) => {
+                if point.len() < 3 {
+                    return None;
+                }
+                let norm = (plane[0].powi(2) + plane[1].powi(2) + plane[2].powi(2)).sqrt();
+                Some(
+                    (point[0] * plane[0] + point[1] * plane[1] + point[2] * plane[2] + plane[3])
+                        / norm,
+                )
+            }
+        }
+    }
+}
+
+impl<T: BasicFloat> Condition<T> {
+    pub fn compare(quantity: Quantity<T>, comparison: Comparison<T>) -> Self {
+        Self::Compare {
+            quantity,
+            comparison,
+        }
+    }
+
+    pub fn and(conditions: Vec<Condition<T>>) -> Self {
+        Self::And(conditions)
+    }
+
+    pub fn or(conditions: Vec<Condition<T>>) -> Self {
+        Self::Or(conditions)
+    }
+
+    /// Evaluate with `point` and its `attributes`.
+    /// A comparison on missing value is false.
+    pub fn evaluate(&self, point: &[T], attributes: &[T]) -> bool {
+        match self {
+            Condition::Compare {
+                quantity,
+                comparison,
+            } => quantity
+                .value(point, attributes)
+                .map(|v| comparison.test(v))
+                .unwrap_or(false),
+            Condition::And(conditions) => conditions.iter().all(|c| c.evaluate(point, attributes)),
+            Condition::Or(conditions) => conditions.iter().any(|c| c.evaluate(point, attributes)),
+            Condition::Not(condition) => !condition.evaluate(point, attributes),
+        }
+    }
+}
+
+impl<T: BasicFloat> Not for Condition<T> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
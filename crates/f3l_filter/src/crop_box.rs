@@ -0,0 +1,188 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Keep points inside an oriented box.
+///
+/// Box is `min` ~ `max` in local frame, placed in world by orthonormal `axes` and `translation`.
+/// Local coordinate of point `p` is `axes[i] · (p - translation)`.
+/// Default axes are identity, so it is an axis-aligned box.
+///
+/// [`CropBox::from_obb`] builds from `center`, directions and half `length` of `OBB` in `f3l_features`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+///
+/// let obb = OBB::compute(&vertices);
+/// let mut filter = CropBox::from_obb(
+///     &obb.center,
+///     &[obb.primary, obb.secondary, obb.tertiary],
+///     &obb.length,
+/// );
+/// let out = filter.filter_instance(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct CropBox<T: BasicFloat, const D: usize> {
+    pub negative: bool,
+    pub min: Vec<T>,
+    pub max: Vec<T>,
+    /// Directions of local axes in world.
+    pub axes: Vec<Vec<T>>,
+    pub translation: Vec<T>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    inliers: Vec<usize>,
+}
+
+impl<T: BasicFloat, const D: usize> Default for CropBox<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BasicFloat, const D: usize> CropBox<T, D> {
+    pub fn new() -> Self {
+        Self {
+            negative: false,
+            min: vec![T::zero(); D],
+            max: vec![T::zero(); D],
+            axes: (0..D)
+                .map(|i| {
+                    let mut axis = vec![T::zero(); D];
+                    axis[i] = T::one();
+                    axis
+                })
+                .collect(),
+            translation: vec![T::zero(); D],
+            inliers: vec![],
+        }
+    }
+
+    pub fn with_data<P: Into<[T; D]> + Copy>(min: &P, max: &P) -> Self {
+        let mut filter = Self::new();
+        filter.set_min_max(min, max);
+        filter
+    }
+
+    /// Box of `center +- length` along `axes`, like `OBB`.
+    pub fn from_obb<P: Into<[T; D]> + Copy>(center: &P, axes: &[P; D], length: &P) -> Self {
+        let length: [T; D] = (*length).into();
+        let mut filter = Self::new();
+        filter.min = length.iter().map(|&l| -l.abs()).collect();
+        filter.max = length.iter().map(|&l| l.abs()).collect();
+        filter.set_axes(axes);
+        filter.set_translation(center);
+        filter
+    }
+
+    pub fn set_min_max<P: Into<[T; D]> + Copy>(&mut self, min: &P, max: &P) {
+        let min: [T; D] = (*min).into();
+        let max: [T; D] = (*max).into();
+        self.min = min.to_vec();
+        self.max = max.to_vec();
+    }
+
+    /// Axes should be orthonormal.
+    pub fn set_axes<P: Into<[T; D]> + Copy>(&mut self, axes: &[P; D]) {
+        self.axes = axes
+            .iter()
+            .map(|&axis| {
+                let axis: [T; D] = axis.into();
+                axis.to_vec()
+            })
+            .collect();
+    }
+
+    pub fn set_translation<P: Into<[T; D]> + Copy>(&mut self, translation: &P) {
+        let translation: [T; D] = (*translation).into();
+        self.translation = translation.to_vec();
+    }
+
+    #[inline]
+    fn contains(&self, p: &[T; D]) -> bool {
+        (0..D).all(|i| {
+            let v = (0..D).fold(T::zero(), |acc, j| {
+                acc + self.axes[i][j] * (p[j] - self.translation[j])
+            });
+            v >= self.min[i] && v <= self.max[i]
+        })
+    }
+}
+
+impl<T: BasicFloat, const D: usize> F3lFilterInverse for CropBox<T, D> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for CropBox<T, D>
+where
+    P: Into<[T; D]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.inliers.clone()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.inliers.iter().map(|&i| data[i]).collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.inliers.clear();
+        if self.min.len() != D
+            || self.max.len() != D
+            || self.translation.len() != D
+            || self.axes.len() != D
+            || self.axes.iter().any(|axis| axis.len() != D)
+        {
+            return false;
+        }
+
+        use f3l_core::rayon::prelude::*;
+        self.inliers = data
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, &p)| {
+                if self.contains(&p.into()) != self.negative {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = CropBox::<f32, 2>::with_data(&[-1f32, -1.], &[1f32, 1.]);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "min":[-1.0,-1.0],
+        "max":[1.0,1.0],
+        "axes":[[1.0,0.0],[0.0,1.0]],
+        "translation":[0.0,0.0]
+    }"#;
+    let model_de: CropBox<f32, 2> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.min, model_de.min);
+    assert_eq!(model.max, model_de.max);
+    assert_eq!(model.axes, model_de.axes);
+    assert_eq!(model.translation, model_de.translation);
+}
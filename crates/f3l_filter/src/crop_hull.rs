@@ -0,0 +1,265 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, Line,
+};
+
+/// Shape of [`CropHull`], stored by coordinates so it does not borrow data of hull.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum HullShape<T: BasicFloat> {
+    /// Edges of 2D polygons. Inside is tested by even-odd rule, so holes are allowed.
+    Polygon(Vec<[[T; 2]; 2]>),
+    /// Triangles of a closed 3D mesh. Inside is tested by winding number.
+    Mesh(Vec<[[T; 3]; 3]>),
+}
+
+impl<T: BasicFloat> Default for HullShape<T> {
+    fn default() -> Self {
+        Self::Polygon(vec![])
+    }
+}
+
+/// Keep points inside a polygon or a closed mesh.
+///
+/// * Polygon: Output of `ConvexHull2D` by [`CropHull::set_polygon`],
+///   contours of `ConcaveHull` by [`CropHull::set_contours`].
+///   Data is projected to `dims` before testing.
+/// * Mesh: Faces of `ConvexHull3D` by [`CropHull::set_mesh`]. Only first 3 dimensions are used.
+///
+/// Points outside bounding box of hull are rejected before polygon or mesh test.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+///
+/// let mut cvh = ConvexHull3D::with_data(&hull_points);
+/// cvh.compute();
+/// let mut filter = CropHull::new();
+/// if let ConvexHullId::D3(faces) = cvh.hulls {
+///     filter.set_mesh(&hull_points, &faces);
+/// }
+/// let out = filter.filter_instance(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct CropHull<T: BasicFloat> {
+    pub negative: bool,
+    /// Dimensions of data to test with polygon.
+    pub dims: [usize; 2],
+    pub hull: HullShape<T>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    inliers: Vec<usize>,
+}
+
+impl<T: BasicFloat> Default for CropHull<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BasicFloat> CropHull<T> {
+    pub fn new() -> Self {
+        Self {
+            negative: false,
+            dims: [0, 1],
+            hull: HullShape::default(),
+            inliers: vec![],
+        }
+    }
+
+    pub fn set_dims(&mut self, dims: [usize; 2]) {
+        self.dims = dims;
+    }
+
+    /// Polygon of ordered `ids` of `data`, closed from last to first.
+    pub fn set_polygon<P: Into<[T; 2]> + Copy>(&mut self, data: &[P], ids: &[usize]) {
+        let edges = (0..ids.len())
+            .map(|i| [data[ids[i]].into(), data[ids[(i + 1) % ids.len()]].into()])
+            .collect();
+        self.hull = HullShape::Polygon(edges);
+    }
+
+    /// Polygons of edges, like contours of `Delaunay2DShape`.
+    pub fn set_contours<P: Into<[T; 2]> + Copy>(&mut self, data: &[P], contours: &[Vec<Line>]) {
+        let edges = contours
+            .iter()
+            .flatten()
+            .map(|&(a, b)| [data[a].into(), data[b].into()])
+            .collect();
+        self.hull = HullShape::Polygon(edges);
+    }
+
+    /// Closed mesh of `faces`, like `FaceIdType` of `ConvexHull3D`.
+    pub fn set_mesh<P, F>(&mut self, data: &[P], faces: &[F])
+    where
+        P: Into<[T; 3]> + Copy,
+        F: Into<[usize; 3]> + Copy,
+    {
+        let triangles = faces
+            .iter()
+            .map(|&f| {
+                let [a, b, c]: [usize; 3] = f.into();
+                [data[a].into(), data[b].into(), data[c].into()]
+            })
+            .collect();
+        self.hull = HullShape::Mesh(triangles);
+    }
+
+    /// Even-odd rule by casting a ray to `+x`.
+    fn in_polygon(edges: &[[[T; 2]; 2]], p: &[T; 2]) -> bool {
+        edges.iter().fold(false, |inside, [a, b]| {
+            if (a[1] > p[1]) != (b[1] > p[1]) {
+                let x = a[0] + (p[1] - a[1]) * (b[0] - a[0]) / (b[1] - a[1]);
+                if p[0] < x {
+                    return !inside;
+                }
+            }
+            inside
+        })
+    }
+
+    /// Winding number by sum of solid angles of triangles,
+    /// `±1` inside and `0` outside, independent of orientation of faces.
+    ///
+    /// Ref: Van Oosterom and Strackee, The Solid Angle of a Plane Triangle.
+    fn in_mesh(triangles: &[[[T; 3]; 3]], p: &[T; 3]) -> bool {
+        let sub = |v: &[T; 3]| [v[0] - p[0], v[1] - p[1], v[2] - p[2]];
+        let dot = |a: &[T; 3], b: &[T; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let norm = |a: &[T; 3]| dot(a, a).sqrt();
+        let cross = |a: &[T; 3], b: &[T; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+
+        let omega = triangles.iter().fold(T::zero(), |acc, [a, b, c]| {
+            let (a, b, c) = (sub(a), sub(b), sub(c));
+            let (la, lb, lc) = (norm(&a), norm(&b), norm(&c));
+            let numerator = dot(&a, &cross(&b, &c));
+            let denominator = la * lb * lc + dot(&a, &b) * lc + dot(&a, &c) * lb + dot(&b, &c) * la;
+            acc + numerator.atan2(denominator)
+        });
+        // Sum of half solid angles is `±2π` inside.
+        omega.abs() > T::from(std::f64::consts::PI).unwrap()
+    }
+
+    /// Test with bounding box `min` ~ `max` of hull first.
+    fn contains<const D: usize>(&self, p: &[T; D], min: &[T], max: &[T]) -> bool {
+        let in_bound = |p: &[T]| (0..p.len()).all(|i| p[i] >= min[i] && p[i] <= max[i]);
+        match &self.hull {
+            HullShape::Polygon(edges) => {
+                let p = [p[self.dims[0]], p[self.dims[1]]];
+                in_bound(&p) && Self::in_polygon(edges, &p)
+            }
+            HullShape::Mesh(triangles) => {
+                let p = [p[0], p[1], p[2]];
+                in_bound(&p) && Self::in_mesh(triangles, &p)
+            }
+        }
+    }
+
+    /// Min and max of hull, for early rejection.
+    fn bound<const N: usize>(vertices: impl Iterator<Item = [T; N]>) -> ([T; N], [T; N]) {
+        vertices.fold(
+            ([T::max_value(); N], [T::min_value(); N]),
+            |(mut min, mut max), v| {
+                (0..N).for_each(|i| {
+                    min[i] = min[i].min(v[i]);
+                    max[i] = max[i].max(v[i]);
+                });
+                (min, max)
+            },
+        )
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for CropHull<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for CropHull<T>
+where
+    P: Into<[T; D]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.inliers.clone()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.inliers.iter().map(|&i| data[i]).collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.inliers.clear();
+
+        let (min, max) = match &self.hull {
+            HullShape::Polygon(edges) => {
+                if edges.is_empty() || self.dims.iter().any(|&d| d >= D) {
+                    return false;
+                }
+                let (min, max) = Self::bound(edges.iter().flatten().copied());
+                (min.to_vec(), max.to_vec())
+            }
+            HullShape::Mesh(triangles) => {
+                if triangles.is_empty() || D < 3 {
+                    return false;
+                }
+                let (min, max) = Self::bound(triangles.iter().flatten().copied());
+                (min.to_vec(), max.to_vec())
+            }
+        };
+
+        use f3l_core::rayon::prelude::*;
+        self.inliers = data
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, &p)| {
+                if self.contains(&p.into(), &min, &max) != self.negative {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let mut model = CropHull::<f32>::new();
+    model.set_polygon(&[[0f32, 0.], [1., 0.], [0., 1.]], &[0, 1, 2]);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "dims":[0,1],
+        "hull":{"Polygon":[
+            [[0.0,0.0],[1.0,0.0]],
+            [[1.0,0.0],[0.0,1.0]],
+            [[0.0,1.0],[0.0,0.0]]
+        ]}
+    }"#;
+    let model_de: CropHull<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.dims, model_de.dims);
+    match (model.hull, model_de.hull) {
+        (HullShape::Polygon(a), HullShape::Polygon(b)) => assert_eq!(a, b),
+        _ => panic!("Could not resolve to Polygon."),
+    }
+}
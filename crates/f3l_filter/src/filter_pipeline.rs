@@ -0,0 +1,248 @@
+use std::{fmt::Debug, ops::Index};
+
+use crate::{
+    ApproximateVoxelGrid, ConditionRemoval, CropBox, CropHull, F3lFilter, F3lFilterInverse,
+    FarthestPointSampling, PassThrough, PoissonDiskSampling, RadiusOutlierRemoval, RandomSampling,
+    StatisticalOutlierRemoval, VoxelGrid,
+};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// A boxed filter, which could be applied to data of any lifetime.
+pub type BoxedFilter<P, const D: usize> = Box<dyn for<'a> F3lFilter<'a, P, D>>;
+
+/// Serializable description of a stage of [`FilterPipeline`].
+///
+/// # Examples
+/// ```
+/// let text = r#"[
+///     {"PassThrough":{"negative":false,"dim":2,"bound":{"start":"Unbounded","end":{"Included":0.5}}}},
+///     {"VoxelGrid":{"leaf":[0.01,0.01,0.01]}},
+///     {"StatisticalOutlierRemoval":{"negative":false,"multiply":1.0,"k_neighbors":50}}
+/// ]"#;
+/// let stages: Vec<FilterStage<f32, 3>> = serde_json::from_str(text).unwrap();
+/// let mut pipeline = FilterPipeline::from_stages(stages);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum FilterStage<T: BasicFloat, const D: usize> {
+    PassThrough(PassThrough<T>),
+    ConditionRemoval(ConditionRemoval<T>),
+    CropBox(CropBox<T, D>),
+    CropHull(CropHull<T>),
+    VoxelGrid(VoxelGrid<T, D>),
+    ApproximateVoxelGrid(ApproximateVoxelGrid<T, D>),
+    RadiusOutlierRemoval(RadiusOutlierRemoval<T>),
+    StatisticalOutlierRemoval(StatisticalOutlierRemoval<T>),
+    FarthestPointSampling(FarthestPointSampling),
+    RandomSampling(RandomSampling),
+    PoissonDiskSampling(PoissonDiskSampling<T>),
+}
+
+impl<T: BasicFloat + 'static, const D: usize> FilterStage<T, D> {
+    pub fn boxed<P>(self) -> BoxedFilter<P, D>
+    where
+        P: Into<[T; D]> + Clone + Copy + Send + Sync + Debug + Index<usize, Output = T>,
+        [T; D]: Into<P>,
+    {
+        match self {
+            FilterStage::PassThrough(filter) => Box::new(filter),
+            FilterStage::ConditionRemoval(filter) => Box::new(filter),
+            FilterStage::CropBox(filter) => Box::new(filter),
+            FilterStage::CropHull(filter) => Box::new(filter),
+            FilterStage::VoxelGrid(filter) => Box::new(filter),
+            FilterStage::ApproximateVoxelGrid(filter) => Box::new(filter),
+            FilterStage::RadiusOutlierRemoval(filter) => Box::new(filter),
+            FilterStage::StatisticalOutlierRemoval(filter) => Box::new(filter),
+            FilterStage::FarthestPointSampling(filter) => Box::new(filter),
+            FilterStage::RandomSampling(filter) => Box::new(filter),
+            FilterStage::PoissonDiskSampling(filter) => Box::new(filter),
+        }
+    }
+}
+
+/// Chain filters, each stage is applied to the output of previous one.
+///
+/// Indices are mapped back to the input data after every stage,
+/// so `filter` returns indices of the original cloud, and `filter_instance`
+/// returns points of it. `VoxelGrid` stages keep the point nearest to mean of each voxel.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+///
+/// let mut pipeline = FilterPipeline::new();
+/// pipeline
+///     .push(PassThrough::with_data(Bound::Unbounded..Bound::Included(0.5), 2))
+///     .push(VoxelGrid::with_data(&[0.01; 3]))
+///     .push(StatisticalOutlierRemoval::new(1., 50));
+///
+/// let ids = pipeline.filter(&vertices);
+/// // Indices remained after each stage.
+/// let provenance = pipeline.provenance();
+/// ```
+pub struct FilterPipeline<P, const D: usize> {
+    pub negative: bool,
+    stages: Vec<BoxedFilter<P, D>>,
+    provenance: Vec<Vec<usize>>,
+    nb_data: usize,
+}
+
+impl<P, const D: usize> Default for FilterPipeline<P, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, const D: usize> FilterPipeline<P, D> {
+    pub fn new() -> Self {
+        Self {
+            negative: false,
+            stages: vec![],
+            provenance: vec![],
+            nb_data: 0,
+        }
+    }
+
+    /// Build pipeline from serializable stages.
+    pub fn from_stages<T, I>(stages: I) -> Self
+    where
+        T: BasicFloat + 'static,
+        I: IntoIterator<Item = FilterStage<T, D>>,
+        P: Into<[T; D]> + Clone + Copy + Send + Sync + Debug + Index<usize, Output = T>,
+        [T; D]: Into<P>,
+    {
+        let mut pipeline = Self::new();
+        stages.into_iter().for_each(|stage| {
+            pipeline.push_boxed(stage.boxed());
+        });
+        pipeline
+    }
+
+    pub fn push<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: for<'a> F3lFilter<'a, P, D> + 'static,
+    {
+        self.push_boxed(Box::new(filter))
+    }
+
+    pub fn push_boxed(&mut self, filter: BoxedFilter<P, D>) -> &mut Self {
+        self.stages.push(filter);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Indices of original data remained after each stage.
+    pub fn provenance(&self) -> &[Vec<usize>] {
+        &self.provenance
+    }
+
+    fn indices(&self) -> Vec<usize> {
+        let inliers = match self.provenance.last() {
+            Some(ids) => ids.clone(),
+            None => (0..self.nb_data).collect(),
+        };
+        if !self.negative {
+            return inliers;
+        }
+        let mut mask = vec![true; self.nb_data];
+        inliers.iter().for_each(|&i| mask[i] = false);
+        mask.iter()
+            .enumerate()
+            .filter(|&(_, &f)| f)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+impl<P, const D: usize> F3lFilterInverse for FilterPipeline<P, D> {
+    /// Inverse result of whole pipeline, not each stage.
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P: Copy, const D: usize> F3lFilter<'a, P, D> for FilterPipeline<P, D> {
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.indices()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.indices().iter().map(|&i| data[i]).collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.provenance.clear();
+        self.nb_data = data.len();
+
+        let mut ids = (0..data.len()).collect::<Vec<_>>();
+        for (i, stage) in self.stages.iter_mut().enumerate() {
+            let local = if i == 0 {
+                stage.filter(data)
+            } else {
+                let buffer = ids.iter().map(|&id| data[id]).collect::<Vec<_>>();
+                stage.filter(&buffer)
+            };
+            ids = local.into_iter().map(|id| ids[id]).collect();
+            self.provenance.push(ids.clone());
+        }
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    use std::ops::Bound;
+
+    let stages: Vec<FilterStage<f32, 3>> = vec![
+        FilterStage::PassThrough(PassThrough::with_data(
+            Bound::Unbounded..Bound::Included(0.5),
+            2,
+        )),
+        FilterStage::VoxelGrid(VoxelGrid::with_data(&[0.01; 3])),
+        FilterStage::RadiusOutlierRemoval(RadiusOutlierRemoval::new(0.03, 20)),
+    ];
+    let content = serde_json::to_string(&stages).unwrap();
+    println!("{}", content);
+
+    let text = r#"[
+        {"PassThrough":{"negative":false,"dim":2,"bound":{"start":"Unbounded","end":{"Included":0.5}}}},
+        {"VoxelGrid":{"leaf":[0.01,0.01,0.01]}},
+        {"RadiusOutlierRemoval":{"negative":false,"radius":0.03,"threshold":20}}
+    ]"#;
+    let stages_de: Vec<FilterStage<f32, 3>> = serde_json::from_str(text).unwrap();
+    assert_eq!(stages.len(), stages_de.len());
+    match (&stages[0], &stages_de[0]) {
+        (FilterStage::PassThrough(a), FilterStage::PassThrough(b)) => {
+            assert_eq!(a.dim, b.dim);
+            assert_eq!(a.bound, b.bound);
+        }
+        _ => panic!("Stage 0 should be PassThrough"),
+    }
+    match (&stages[1], &stages_de[1]) {
+        (FilterStage::VoxelGrid(a), FilterStage::VoxelGrid(b)) => assert_eq!(a.leaf, b.leaf),
+        _ => panic!("Stage 1 should be VoxelGrid"),
+    }
+    match (&stages[2], &stages_de[2]) {
+        (FilterStage::RadiusOutlierRemoval(a), FilterStage::RadiusOutlierRemoval(b)) => {
+            assert_eq!(a.radius, b.radius);
+            assert_eq!(a.threshold, b.threshold);
+        }
+        _ => panic!("Stage 2 should be RadiusOutlierRemoval"),
+    }
+}
@@ -0,0 +1,162 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_surface::{Convex, ConvexHull3D, ConvexHullId};
+
+/// Hidden Point Removal, keep points visible from `viewpoint` without normals or surface.
+///
+/// Ref: Katz et al., Direct Visibility of Point Sets.
+///
+/// 1. Points relative to `viewpoint` are spherical flipped by sphere of radius `R`,
+///    `p' = p + 2 * (R - |p|) * p / |p|`, where `R = max(|p|) * 10^gamma`.
+/// 2. Compute [`ConvexHull3D`] of flipped points and `viewpoint`.
+/// 3. Points on the hull are visible.
+///
+/// Larger `gamma` keeps more points, include some hidden ones.
+/// Hull is computed in `f64`, flipped points are far larger than data.
+/// `filter` returns visible points, or hidden if `negative`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = HiddenPointRemoval::new([0f32, 0., 2.]);
+/// filter.set_gamma(2.);
+/// let visible = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct HiddenPointRemoval<T: BasicFloat> {
+    pub negative: bool,
+    pub viewpoint: [T; 3],
+    /// Exponent of flipping radius. Default: 2.
+    pub gamma: T,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    visible: Vec<bool>,
+}
+
+impl<T: BasicFloat> HiddenPointRemoval<T> {
+    pub fn new(viewpoint: [T; 3]) -> Self {
+        Self {
+            negative: false,
+            viewpoint,
+            gamma: T::from(2).unwrap(),
+            visible: vec![],
+        }
+    }
+
+    pub fn set_viewpoint(&mut self, viewpoint: [T; 3]) {
+        self.viewpoint = viewpoint;
+    }
+
+    pub fn set_gamma(&mut self, gamma: T) {
+        self.gamma = gamma;
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for HiddenPointRemoval<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for HiddenPointRemoval<T>
+where
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.visible
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != self.negative)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.visible
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != self.negative)
+            .map(|(i, _)| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.visible.clear();
+        if data.is_empty() {
+            return false;
+        }
+        let viewpoint = self.viewpoint.map(|v| v.to_f64().unwrap());
+        let points = data
+            .iter()
+            .map(|&p| {
+                let p: [T; 3] = p.into();
+                [0, 1, 2].map(|i| p[i].to_f64().unwrap() - viewpoint[i])
+            })
+            .collect::<Vec<_>>();
+        let norms = points
+            .iter()
+            .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+            .collect::<Vec<_>>();
+        let max = norms.iter().fold(0f64, |acc, &n| acc.max(n));
+        if max <= 0f64 {
+            return false;
+        }
+        let radius = max * 10f64.powf(self.gamma.to_f64().unwrap());
+
+        // Viewpoint is the last one. Points at viewpoint stay, and are hidden.
+        let mut flipped = points
+            .iter()
+            .zip(&norms)
+            .map(|(p, &n)| {
+                if n <= 0f64 {
+                    return [0f64; 3];
+                }
+                let scale = 1f64 + 2f64 * (radius - n) / n;
+                [p[0] * scale, p[1] * scale, p[2] * scale]
+            })
+            .collect::<Vec<_>>();
+        flipped.push([0f64; 3]);
+
+        let mut hull = ConvexHull3D::with_data(&flipped);
+        hull.compute();
+
+        self.visible = vec![false; data.len()];
+        if let ConvexHullId::D3(faces) = hull.hulls {
+            faces
+                .iter()
+                .flat_map(|f| f.point)
+                .filter(|&i| i < data.len() && norms[i] > 0f64)
+                .for_each(|i| self.visible[i] = true);
+        }
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = HiddenPointRemoval::new([0f32, 0., 2.]);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "viewpoint":[0.0,0.0,2.0],
+        "gamma":2.0
+    }"#;
+    let model_de: HiddenPointRemoval<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.viewpoint, model_de.viewpoint);
+    assert_eq!(model.gamma, model_de.gamma);
+}
@@ -1,8 +1,20 @@
+mod approximate_voxel_grid;
+mod bilateral_filter;
+mod cloth_simulation_filter;
 mod condition_removal;
+mod crop_box;
+mod crop_hull;
+mod filter_pipeline;
+mod hidden_point_removal;
+mod median_filter;
 mod pass_through;
+mod progressive_morphological_filter;
 mod radius_outlier_removal;
+mod sampling;
+mod shadow_points;
 mod statistical_outlier_removal;
 mod voxel_grid;
+mod z_buffer_occlusion;
 
 pub trait F3lFilterInverse {
     /// if true, get outlier else inlier
@@ -20,8 +32,23 @@ pub trait F3lFilter<'a, P, const D: usize>: F3lFilterInverse {
     fn apply_filter(&mut self, data: &'a [P]) -> bool;
 }
 
-pub use condition_removal::ConditionRemoval;
+pub use approximate_voxel_grid::ApproximateVoxelGrid;
+pub use bilateral_filter::BilateralFilter;
+pub use cloth_simulation_filter::ClothSimulationFilter;
+pub use condition_removal::{Comparison, Condition, ConditionRemoval, Quantity};
+pub use crop_box::CropBox;
+pub use crop_hull::{CropHull, HullShape};
+pub use filter_pipeline::{BoxedFilter, FilterPipeline, FilterStage};
+pub use hidden_point_removal::HiddenPointRemoval;
+pub use median_filter::MedianFilter;
 pub use pass_through::PassThrough;
+pub use progressive_morphological_filter::ProgressiveMorphologicalFilter;
 pub use radius_outlier_removal::RadiusOutlierRemoval;
+pub use sampling::{
+    CovarianceSampling, FarthestPointSampling, NormalSpaceSampling, PoissonDiskSampling,
+    RandomSampling,
+};
+pub use shadow_points::ShadowPoints;
 pub use statistical_outlier_removal::StatisticalOutlierRemoval;
-pub use voxel_grid::VoxelGrid;
+pub use voxel_grid::{AttributeReduction, VoxelGrid, VoxelReduction};
+pub use z_buffer_occlusion::ZBufferOcclusion;
@@ -0,0 +1,107 @@
+use std::ops::Index;
+
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_search_tree::{KdTree, SearchBy, TreeSearch};
+
+/// Replace each point by median of its neighbors, dimension-wise.
+///
+/// Median is robust to outliers and keeps steps of surfaces better than mean.
+/// If `max_displacement` is set, movement of each point is clamped to it.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = MedianFilter::new(SearchBy::Count(9));
+/// filter.set_max_displacement(Some(0.005f32));
+/// let smoothed = filter.smooth(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct MedianFilter<T: BasicFloat> {
+    /// Use Radius, KNN or Hybrid to search neighbors.
+    pub method: SearchBy<T>,
+    pub max_displacement: Option<T>,
+}
+
+impl<T: BasicFloat> MedianFilter<T> {
+    pub fn new(method: SearchBy<T>) -> Self {
+        Self {
+            method,
+            max_displacement: None,
+        }
+    }
+
+    pub fn set_max_displacement(&mut self, max_displacement: Option<T>) {
+        self.max_displacement = max_displacement;
+    }
+
+    pub fn smooth<P, const D: usize>(&self, data: &[P]) -> Vec<P>
+    where
+        P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+        [T; D]: Into<P>,
+    {
+        if data.is_empty() {
+            return vec![];
+        }
+        let mut tree = KdTree::<T, P>::new(D);
+        tree.set_data(data);
+        tree.build();
+
+        data.par_iter()
+            .map(|p| {
+                let neighbors = match self.method {
+                    SearchBy::Count(k) => tree.search_knn_ids(p, k),
+                    SearchBy::Radius(r) => tree.search_radius_ids(p, r),
+                    SearchBy::Hybrid { k, radius } => tree.search_hybrid_ids(p, k, radius),
+                };
+                if neighbors.is_empty() {
+                    return *p;
+                }
+                let p: [T; D] = (*p).into();
+                let mut median = [T::zero(); D];
+                let mut values = Vec::with_capacity(neighbors.len());
+                (0..D).for_each(|d| {
+                    values.clear();
+                    values.extend(neighbors.iter().map(|&j| data[j][d]));
+                    values.sort_by(|a, b| a.to_f64().unwrap().total_cmp(&b.to_f64().unwrap()));
+                    let mid = values.len() / 2;
+                    median[d] = if values.len() % 2 == 0 {
+                        (values[mid - 1] + values[mid]) / T::from(2).unwrap()
+                    } else {
+                        values[mid]
+                    };
+                });
+
+                if let Some(max) = self.max_displacement {
+                    let len = (0..D)
+                        .fold(T::zero(), |acc, d| acc + (median[d] - p[d]).powi(2))
+                        .sqrt();
+                    if len > max {
+                        (0..D).for_each(|d| median[d] = p[d] + (median[d] - p[d]) * max / len);
+                    }
+                }
+                median.into()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn serde() {
+    let mut model = MedianFilter::new(SearchBy::Count(9));
+    model.set_max_displacement(Some(0.005f32));
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "method":{"Count":9},
+        "max_displacement":0.005
+    }"#;
+    let model_de: MedianFilter<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.method, model_de.method);
+    assert_eq!(model.max_displacement, model_de.max_displacement);
+}
@@ -0,0 +1,298 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Progressive Morphological Filter, separating ground from non-ground of aerial LiDAR.
+///
+/// Ref: Zhang et al., A Progressive Morphological Filter for Removing Nonground Measurements
+/// From Airborne LIDAR Data.
+///
+/// 1. Rasterize minimum elevation (`Z`) of points into grid of `cell_size` on `XY`.
+/// 2. Open the grid (erosion then dilation) by windows of increasing size,
+///    `2 * b^k + 1` cells (`k` from 0) if `exponential`, else `2 * k * b + 1` (`k` from 1).
+/// 3. Elevation threshold of each window is `slope * (w_k - w_{k-1}) * cell_size + initial_distance`,
+///    limited by `max_distance`, and `initial_distance` for the first window.
+/// 4. Points higher than opened surface over threshold are non-ground, and removed from next step.
+///
+/// `filter` returns ground points, or non-ground if `negative`.
+/// Points of non-finite coordinates are in neither, and result is empty if `cell_size` is not positive.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/aerial_lidar.ply");
+/// let mut filter = ProgressiveMorphologicalFilter::new(1f32);
+/// filter.set_max_window_size(20);
+/// filter.set_slope(1.0);
+/// let ground = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ProgressiveMorphologicalFilter<T: BasicFloat> {
+    pub negative: bool,
+    pub cell_size: T,
+    /// Max window size in cells. Default: 33.
+    pub max_window_size: usize,
+    /// Base of window size. Default: 2.
+    pub base: usize,
+    /// Exponential or linear growing windows. Default: true.
+    pub exponential: bool,
+    /// Slope of terrain. Default: 0.7.
+    pub slope: T,
+    /// Default: 0.15.
+    pub initial_distance: T,
+    /// Default: 10.
+    pub max_distance: T,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    /// Ground or not of each point, `None` if skipped.
+    ground: Vec<Option<bool>>,
+}
+
+impl<T: BasicFloat> ProgressiveMorphologicalFilter<T> {
+    pub fn new(cell_size: T) -> Self {
+        Self {
+            negative: false,
+            cell_size,
+            max_window_size: 33,
+            base: 2,
+            exponential: true,
+            slope: T::from(0.7).unwrap(),
+            initial_distance: T::from(0.15).unwrap(),
+            max_distance: T::from(10).unwrap(),
+            ground: vec![],
+        }
+    }
+
+    pub fn set_max_window_size(&mut self, max_window_size: usize) {
+        self.max_window_size = max_window_size;
+    }
+
+    pub fn set_base(&mut self, base: usize) {
+        self.base = base;
+    }
+
+    pub fn set_exponential(&mut self, exponential: bool) {
+        self.exponential = exponential;
+    }
+
+    pub fn set_slope(&mut self, slope: T) {
+        self.slope = slope;
+    }
+
+    pub fn set_initial_distance(&mut self, initial_distance: T) {
+        self.initial_distance = initial_distance;
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: T) {
+        self.max_distance = max_distance;
+    }
+
+    /// Window sizes in cells, odd and not larger than `max_window_size`.
+    fn window_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![];
+        let mut k = if self.exponential { 0u32 } else { 1 };
+        loop {
+            let half = if self.exponential {
+                match self.base.checked_pow(k) {
+                    Some(v) => v,
+                    None => break,
+                }
+            } else {
+                k as usize * self.base
+            };
+            let size = 2 * half + 1;
+            if size > self.max_window_size || sizes.last() == Some(&size) {
+                break;
+            }
+            sizes.push(size);
+            k += 1;
+        }
+        sizes
+    }
+
+    /// Minimum (`erode`) or maximum of `size x size` window, separated by rows and columns.
+    /// Empty cells (`NaN`) are ignored.
+    fn morphology(grid: &[T], cols: usize, size: usize, erode: bool) -> Vec<T> {
+        let rows = grid.len() / cols;
+        let half = size / 2;
+        let pick = |a: T, b: T| {
+            if a.is_nan() {
+                b
+            } else if b.is_nan() {
+                a
+            } else if erode {
+                a.min(b)
+            } else {
+                a.max(b)
+            }
+        };
+        let along_x = (0..grid.len())
+            .into_par_iter()
+            .map(|i| {
+                let (r, c) = (i / cols, i % cols);
+                (c.saturating_sub(half)..(c + half + 1).min(cols))
+                    .fold(T::nan(), |acc, cc| pick(acc, grid[r * cols + cc]))
+            })
+            .collect::<Vec<_>>();
+        (0..grid.len())
+            .into_par_iter()
+            .map(|i| {
+                let (r, c) = (i / cols, i % cols);
+                (r.saturating_sub(half)..(r + half + 1).min(rows))
+                    .fold(T::nan(), |acc, rr| pick(acc, along_x[rr * cols + c]))
+            })
+            .collect()
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for ProgressiveMorphologicalFilter<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for ProgressiveMorphologicalFilter<T>
+where
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.ground
+            .iter()
+            .enumerate()
+            .filter(|&(_, &g)| g == Some(!self.negative))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.ground
+            .iter()
+            .enumerate()
+            .filter(|&(_, &g)| g == Some(!self.negative))
+            .map(|(i, _)| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.ground.clear();
+        if data.is_empty() || self.cell_size.is_nan() || self.cell_size <= T::zero() {
+            return false;
+        }
+        let points = data.iter().map(|&p| p.into()).collect::<Vec<[T; 3]>>();
+        let finite = |p: &[T; 3]| p.iter().all(|v| v.is_finite());
+        let (mut min, mut max) = ([T::max_value(); 2], [T::min_value(); 2]);
+        points.iter().filter(|p| finite(p)).for_each(|p| {
+            (0..2).for_each(|i| {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            });
+        });
+        if min[0] > max[0] {
+            return false;
+        }
+        let cell_of = |v: T, i: usize| ((v - min[i]) / self.cell_size).floor().to_usize();
+        let (cols, rows) = match (cell_of(max[0], 0), cell_of(max[1], 1)) {
+            (Some(x), Some(y)) => (x + 1, y + 1),
+            _ => return false,
+        };
+        if cols.checked_mul(rows).is_none() {
+            return false;
+        }
+        let cells = points
+            .iter()
+            .map(|p| {
+                if !finite(p) {
+                    return None;
+                }
+                Some(cell_of(p[1], 1)? * cols + cell_of(p[0], 0)?)
+            })
+            .collect::<Vec<_>>();
+
+        self.ground = cells.iter().map(|c| c.map(|_| true)).collect();
+        let mut previous = None;
+        for size in self.window_sizes() {
+            // Minimum elevation of remained ground points.
+            let mut grid = vec![T::nan(); rows * cols];
+            points
+                .iter()
+                .zip(&cells)
+                .zip(&self.ground)
+                .filter(|(_, &g)| g == Some(true))
+                .filter_map(|((p, &c), _)| Some((p, c?)))
+                .for_each(|(p, c)| {
+                    if grid[c].is_nan() || p[2] < grid[c] {
+                        grid[c] = p[2];
+                    }
+                });
+
+            let eroded = Self::morphology(&grid, cols, size, true);
+            let opened = Self::morphology(&eroded, cols, size, false);
+
+            let threshold = match previous {
+                Some(previous) => (self.slope * T::from(size - previous).unwrap() * self.cell_size
+                    + self.initial_distance)
+                    .min(self.max_distance),
+                None => self.initial_distance,
+            };
+            previous = Some(size);
+
+            self.ground
+                .par_iter_mut()
+                .zip(&points)
+                .zip(&cells)
+                .for_each(|((g, p), &c)| {
+                    if let (Some(true), Some(c)) = (*g, c) {
+                        if !opened[c].is_nan() && p[2] - opened[c] > threshold {
+                            *g = Some(false);
+                        }
+                    }
+                });
+        }
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = ProgressiveMorphologicalFilter::new(1f32);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "cell_size":1.0,
+        "max_window_size":33,
+        "base":2,
+        "exponential":true,
+        "slope":0.7,
+        "initial_distance":0.15,
+        "max_distance":10.0
+    }"#;
+    let model_de: ProgressiveMorphologicalFilter<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.cell_size, model_de.cell_size);
+    assert_eq!(model.max_window_size, model_de.max_window_size);
+    assert_eq!(model.base, model_de.base);
+    assert_eq!(model.exponential, model_de.exponential);
+    assert_eq!(model.slope, model_de.slope);
+}
+
+#[test]
+fn window_sizes() {
+    let mut model = ProgressiveMorphologicalFilter::new(1f32);
+    assert_eq!(model.window_sizes(), [3, 5, 9, 17, 33]);
+    model.set_exponential(false);
+    model.set_max_window_size(12);
+    assert_eq!(model.window_sizes(), [5, 9]);
+}
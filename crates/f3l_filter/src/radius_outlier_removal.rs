@@ -21,33 +21,21 @@ use crate::{F3lFilter, F3lFilterInverse};
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct RadiusOutlierRemoval<'a, P, T: BasicFloat, const D: usize>
-where
-    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
-    [T; D]: Into<P>,
-{
+pub struct RadiusOutlierRemoval<T: BasicFloat> {
     pub negative: bool,
     pub radius: T,
     pub threshold: usize,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
-    tree: KdTree<'a, T, P>,
-    #[serde(skip_serializing)]
-    #[serde(skip_deserializing)]
     inlier: Vec<bool>,
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> RadiusOutlierRemoval<'a, P, T, D>
-where
-    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
-    [T; D]: Into<P>,
-{
+impl<T: BasicFloat> RadiusOutlierRemoval<T> {
     pub fn new(radius: T, threshold: usize) -> Self {
         Self {
             negative: false,
             radius,
             threshold,
-            tree: KdTree::<T, P>::new(D),
             inlier: vec![],
         }
     }
@@ -58,17 +46,13 @@ where
     }
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> F3lFilterInverse for RadiusOutlierRemoval<'a, P, T, D>
-where
-    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
-    [T; D]: Into<P>,
-{
+impl<T: BasicFloat> F3lFilterInverse for RadiusOutlierRemoval<T> {
     fn set_negative(&mut self, negative: bool) {
         self.negative = negative;
     }
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for RadiusOutlierRemoval<'a, P, T, D>
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for RadiusOutlierRemoval<T>
 where
     P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
     [T; D]: Into<P>,
@@ -99,33 +83,30 @@ where
         if data.is_empty() {
             return false;
         }
-        // Check Tree dimension correct, cause skip deserialize would be 0.
-        if self.tree.dim != D {
-            self.tree = KdTree::<T, P>::new(D);
-        }
-        self.tree.set_data(data);
-
-        self.tree.build();
+        // Build tree per call, so the filter is not bound to lifetime of data.
+        let mut tree = KdTree::<T, P>::new(D);
+        tree.set_data(data);
+        tree.build();
         let capacity = data.len() / 10;
         let capacity = if capacity > 10 { capacity } else { 10 };
 
-        let r = (self.radius.to_f32().unwrap()).powi(2);
+        let r = self.radius.powi(2);
         let by = if self.radius == T::zero() {
-            SearchBy::Radius(1.0)
+            SearchBy::Radius(T::one())
         } else {
             SearchBy::Radius(r)
         };
 
         let th = self.threshold;
 
-        self.inlier.resize(data.len(), false);
+        self.inlier = vec![false; data.len()];
         let inlier = data
             .par_iter()
             .enumerate()
             .map(|(i, p)| {
                 let mut result =
                     TreeRadiusResult::with_capacity(r, capacity).set_to_maximum_size(th);
-                self.tree.search(*p, by, &mut result);
+                tree.search(*p, by, &mut result);
                 (i, result.data.len() >= th)
             })
             .collect::<Vec<_>>();
@@ -140,7 +121,7 @@ where
 
 #[test]
 fn serde() {
-    let model = RadiusOutlierRemoval::<[f32; 3], f32, 3>::new(0.03f32, 20_usize);
+    let model = RadiusOutlierRemoval::<f32>::new(0.03f32, 20_usize);
     let content = serde_json::to_string(&model).unwrap();
     println!("{}", content);
 
@@ -149,7 +130,7 @@ fn serde() {
         "radius":0.03,
         "threshold":20
     }"#;
-    let model_de: RadiusOutlierRemoval<[f32; 3], f32, 3> = serde_json::from_str(text).unwrap();
+    let model_de: RadiusOutlierRemoval<f32> = serde_json::from_str(text).unwrap();
     assert_eq!(model.negative, model_de.negative);
     assert_eq!(model.radius, model_de.radius);
     assert_eq!(model.threshold, model_de.threshold);
@@ -0,0 +1,25 @@
+mod covariance_sampling;
+mod farthest_point_sampling;
+mod normal_space_sampling;
+mod poisson_disk_sampling;
+mod random_sampling;
+
+pub use covariance_sampling::CovarianceSampling;
+pub use farthest_point_sampling::FarthestPointSampling;
+pub use normal_space_sampling::NormalSpaceSampling;
+pub use poisson_disk_sampling::PoissonDiskSampling;
+pub use random_sampling::RandomSampling;
+
+/// `samples`, or sorted indices not sampled if `negative`.
+fn sampled_indices(samples: &[usize], nb_data: usize, negative: bool) -> Vec<usize> {
+    if !negative {
+        return samples.to_vec();
+    }
+    let mut mask = vec![true; nb_data];
+    samples.iter().for_each(|&i| mask[i] = false);
+    mask.iter()
+        .enumerate()
+        .filter(|&(_, &f)| f)
+        .map(|(i, _)| i)
+        .collect()
+}
@@ -0,0 +1,214 @@
+use super::sampled_indices;
+use crate::{F3lFilter, F3lFilterInverse};
+use f3l_core::{
+    jacobi_eigen_square_n,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Covariance sampling for stable point-to-plane ICP.
+///
+/// Ref: Gelfand et al., Geometrically Stable Sampling for the ICP Algorithm.
+///
+/// Each point gives a constraint `[p x n, n]` of rotation and translation.
+/// Eigenvectors of covariance of constraints are directions of transformation,
+/// points are picked greedily for the direction constrained least so far,
+/// so sliding directions of the cloud are kept. Points without normal are never sampled.
+/// Samples are sorted.
+///
+/// Normals are indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut estimator = NormalEstimation::new(SearchBy::Count(10));
+/// estimator.compute(&vertices);
+///
+/// let mut filter = CovarianceSampling::new(1000);
+/// filter.set_normals(&estimator.normals());
+/// let ids = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct CovarianceSampling<T: BasicFloat> {
+    pub negative: bool,
+    pub nb_samples: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<Option<[T; 3]>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    samples: Vec<usize>,
+}
+
+impl<T: BasicFloat> CovarianceSampling<T> {
+    pub fn new(nb_samples: usize) -> Self {
+        Self {
+            negative: false,
+            nb_samples,
+            normals: vec![],
+            samples: vec![],
+        }
+    }
+
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+
+    /// Constraints `[p x n, n]` of points with normal, `p` is centered and scaled
+    /// by mean distance to centroid, so rotation and translation are comparable.
+    fn constraints<P: Into<[T; 3]> + Copy>(&self, data: &[P]) -> Vec<(usize, [T; 6])> {
+        let candidates = self
+            .normals
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.map(|n| (i, data[i].into(), n)))
+            .collect::<Vec<(usize, [T; 3], [T; 3])>>();
+        if candidates.is_empty() {
+            return vec![];
+        }
+        let factor = T::one() / T::from(candidates.len()).unwrap();
+        let mut centroid = [T::zero(); 3];
+        candidates.iter().for_each(|(_, p, _)| {
+            (0..3).for_each(|i| centroid[i] += p[i] * factor);
+        });
+        let scale = candidates.iter().fold(T::zero(), |acc, (_, p, _)| {
+            acc + (0..3)
+                .fold(T::zero(), |acc, i| acc + (p[i] - centroid[i]).powi(2))
+                .sqrt()
+                * factor
+        });
+        let scale = if scale > T::zero() { scale } else { T::one() };
+
+        candidates
+            .into_iter()
+            .map(|(i, p, n)| {
+                let p = [
+                    (p[0] - centroid[0]) / scale,
+                    (p[1] - centroid[1]) / scale,
+                    (p[2] - centroid[2]) / scale,
+                ];
+                let r = [
+                    p[1] * n[2] - p[2] * n[1],
+                    p[2] * n[0] - p[0] * n[2],
+                    p[0] * n[1] - p[1] * n[0],
+                ];
+                (i, [r[0], r[1], r[2], n[0], n[1], n[2]])
+            })
+            .collect()
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for CovarianceSampling<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for CovarianceSampling<T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+            .iter()
+            .map(|&i| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.samples.clear();
+        if data.is_empty() || self.normals.len() != data.len() {
+            return false;
+        }
+        let constraints = self.constraints(data);
+        let nb_samples = self.nb_samples.min(constraints.len());
+        if nb_samples == 0 {
+            return true;
+        }
+
+        let mut cov = [[T::zero(); 6]; 6];
+        constraints.iter().for_each(|(_, v)| {
+            (0..6).for_each(|r| (0..6).for_each(|c| cov[r][c] += v[r] * v[c]));
+        });
+        let eigen = jacobi_eigen_square_n(cov);
+
+        let dot = |v: &[T; 6], e: &[T; 6]| (0..6).fold(T::zero(), |acc, i| acc + v[i] * e[i]);
+        // Candidates per eigenvector, sorted by contribution descending.
+        let lists = eigen
+            .iter()
+            .map(|e| {
+                let mut list = (0..constraints.len())
+                    .map(|k| (k, dot(&constraints[k].1, &e.eigenvector).abs()))
+                    .collect::<Vec<_>>();
+                list.sort_by(|a, b| b.1.to_f64().unwrap().total_cmp(&a.1.to_f64().unwrap()));
+                list
+            })
+            .collect::<Vec<_>>();
+
+        // Directions no point could constrain are skipped, or they would take all samples.
+        let max_eigenvalue = eigen.iter().fold(T::zero(), |acc, e| acc.max(e.eigenvalue));
+        let threshold = max_eigenvalue * T::epsilon().sqrt();
+        let directions = (0..6)
+            .filter(|&j| eigen[j].eigenvalue > threshold)
+            .collect::<Vec<_>>();
+
+        let mut torques = [T::zero(); 6];
+        let mut cursors = [0usize; 6];
+        let mut selected = vec![false; constraints.len()];
+        while self.samples.len() < nb_samples {
+            // Least constrained direction, which still has candidates.
+            let j = directions
+                .iter()
+                .copied()
+                .filter(|&j| cursors[j] < lists[j].len())
+                .min_by(|&a, &b| {
+                    torques[a]
+                        .to_f64()
+                        .unwrap()
+                        .total_cmp(&torques[b].to_f64().unwrap())
+                });
+            let j = match j {
+                Some(j) => j,
+                None => break,
+            };
+            let k = lists[j][cursors[j]].0;
+            cursors[j] += 1;
+            if selected[k] {
+                continue;
+            }
+            selected[k] = true;
+            self.samples.push(constraints[k].0);
+            (0..6).for_each(|jj| {
+                torques[jj] += dot(&constraints[k].1, &eigen[jj].eigenvector).powi(2);
+            });
+        }
+        self.samples.sort();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = CovarianceSampling::<f32>::new(100);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "nb_samples":100
+    }"#;
+    let model_de: CovarianceSampling<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.nb_samples, model_de.nb_samples);
+}
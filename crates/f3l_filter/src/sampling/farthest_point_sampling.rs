@@ -0,0 +1,140 @@
+use std::ops::Index;
+
+use super::sampled_indices;
+use crate::{F3lFilter, F3lFilterInverse};
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Farthest Point Sampling.
+///
+/// Start from `start`, then repeatedly pick the point farthest to sampled points.
+/// Distance to nearest sample of each point is updated incrementally,
+/// so cost is `O(nb_samples * nb_data)`.
+/// Samples are in picking order, so first `k` of them are also a `k` sampling.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = FarthestPointSampling::new(1000);
+/// let ids = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct FarthestPointSampling {
+    pub negative: bool,
+    pub nb_samples: usize,
+    /// Index of first sample.
+    pub start: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    samples: Vec<usize>,
+}
+
+impl FarthestPointSampling {
+    pub fn new(nb_samples: usize) -> Self {
+        Self {
+            negative: false,
+            nb_samples,
+            start: 0,
+            samples: vec![],
+        }
+    }
+
+    pub fn set_start(&mut self, start: usize) {
+        self.start = start;
+    }
+}
+
+impl F3lFilterInverse for FarthestPointSampling {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for FarthestPointSampling
+where
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+            .iter()
+            .map(|&i| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.samples.clear();
+        if data.is_empty() || self.start >= data.len() {
+            return false;
+        }
+        let nb_samples = self.nb_samples.min(data.len());
+        if nb_samples == 0 {
+            return true;
+        }
+
+        // Sampled points are marked `-inf`, never picked again even with duplicated points.
+        let mut distances = vec![T::infinity(); data.len()];
+        let mut last = self.start;
+        self.samples.push(last);
+        distances[last] = T::neg_infinity();
+        while self.samples.len() < nb_samples {
+            let target: [T; D] = data[last].into();
+            let (farthest, _) = distances
+                .par_iter_mut()
+                .enumerate()
+                .map(|(i, d)| {
+                    let p: [T; D] = data[i].into();
+                    let dist = (0..D).fold(T::zero(), |acc, j| acc + (p[j] - target[j]).powi(2));
+                    if dist < *d {
+                        *d = dist;
+                    }
+                    (i, *d)
+                })
+                .reduce(
+                    || (usize::MAX, T::neg_infinity()),
+                    |a, b| {
+                        // Smaller index first on ties, to be deterministic.
+                        if b.1 > a.1 || (b.1 == a.1 && b.0 < a.0) {
+                            b
+                        } else {
+                            a
+                        }
+                    },
+                );
+            last = farthest;
+            distances[last] = T::neg_infinity();
+            self.samples.push(last);
+        }
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = FarthestPointSampling::new(100);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "nb_samples":100,
+        "start":0
+    }"#;
+    let model_de: FarthestPointSampling = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.nb_samples, model_de.nb_samples);
+    assert_eq!(model.start, model_de.start);
+}
@@ -0,0 +1,159 @@
+use super::sampled_indices;
+use crate::{F3lFilter, F3lFilterInverse};
+use f3l_core::{
+    seeded_rng,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use rand::seq::SliceRandom;
+
+/// Normal-space sampling.
+///
+/// Normals are put into `bins^3` buckets by each component in `[-1, 1]`,
+/// then points are picked randomly from each bucket in turn,
+/// so directions of normals are distributed as uniform as possible.
+/// Points without normal are never sampled. Samples are sorted.
+///
+/// Normals are indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut estimator = NormalEstimation::new(SearchBy::Count(10));
+/// estimator.compute(&vertices);
+///
+/// let mut filter = NormalSpaceSampling::new(1000, 4);
+/// filter.set_normals(&estimator.normals());
+/// let ids = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct NormalSpaceSampling<T: BasicFloat> {
+    pub negative: bool,
+    pub nb_samples: usize,
+    /// Number of buckets per component.
+    pub bins: usize,
+    pub seed: Option<u64>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<Option<[T; 3]>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    samples: Vec<usize>,
+}
+
+impl<T: BasicFloat> NormalSpaceSampling<T> {
+    pub fn new(nb_samples: usize, bins: usize) -> Self {
+        Self {
+            negative: false,
+            nb_samples,
+            bins,
+            seed: None,
+            normals: vec![],
+            samples: vec![],
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+
+    #[inline]
+    fn bucket(&self, normal: &[T; 3]) -> usize {
+        let bins = T::from(self.bins).unwrap();
+        let two = T::from(2).unwrap();
+        normal.iter().rev().fold(0, |acc, &v| {
+            let b = ((v + T::one()) / two * bins)
+                .floor()
+                .to_usize()
+                .unwrap_or(0)
+                .min(self.bins - 1);
+            acc * self.bins + b
+        })
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for NormalSpaceSampling<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for NormalSpaceSampling<T>
+where
+    P: Into<[T; D]> + Clone + Copy,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+            .iter()
+            .map(|&i| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.samples.clear();
+        if data.is_empty() || self.bins == 0 || self.normals.len() != data.len() {
+            return false;
+        }
+
+        let mut buckets = vec![vec![]; self.bins.pow(3)];
+        self.normals.iter().enumerate().for_each(|(i, n)| {
+            if let Some(n) = n {
+                buckets[self.bucket(n)].push(i);
+            }
+        });
+        let mut rng = seeded_rng(self.seed);
+        buckets
+            .iter_mut()
+            .for_each(|bucket| bucket.shuffle(&mut rng));
+
+        let nb_normals = buckets.iter().map(|b| b.len()).sum::<usize>();
+        let nb_samples = self.nb_samples.min(nb_normals);
+        let mut round = 0;
+        while self.samples.len() < nb_samples {
+            for bucket in &buckets {
+                if self.samples.len() >= nb_samples {
+                    break;
+                }
+                if let Some(&i) = bucket.get(round) {
+                    self.samples.push(i);
+                }
+            }
+            round += 1;
+        }
+        self.samples.sort();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = NormalSpaceSampling::<f32>::new(100, 4);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "nb_samples":100,
+        "bins":4,
+        "seed":null
+    }"#;
+    let model_de: NormalSpaceSampling<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.nb_samples, model_de.nb_samples);
+    assert_eq!(model.bins, model_de.bins);
+}
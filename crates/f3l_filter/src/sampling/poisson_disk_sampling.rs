@@ -0,0 +1,123 @@
+use std::ops::Index;
+
+use super::sampled_indices;
+use crate::{F3lFilter, F3lFilterInverse};
+use f3l_core::{
+    seeded_rng,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_search_tree::{KdTree, TreeSearch};
+use rand::seq::SliceRandom;
+
+/// Poisson-disk sampling by dart throwing on data.
+///
+/// Visit points in random order, a point is sampled if no sampled point is inside `radius`.
+/// Neighbors of each sample are found by a [`KdTree`] radius search.
+/// Number of samples depends on `radius`, distance of any two samples is larger than `radius`.
+/// Samples are sorted.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = PoissonDiskSampling::new(0.01f32);
+/// filter.set_seed(Some(42));
+/// let ids = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct PoissonDiskSampling<T: BasicFloat> {
+    pub negative: bool,
+    pub radius: T,
+    pub seed: Option<u64>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    samples: Vec<usize>,
+}
+
+impl<T: BasicFloat> PoissonDiskSampling<T> {
+    pub fn new(radius: T) -> Self {
+        Self {
+            negative: false,
+            radius,
+            seed: None,
+            samples: vec![],
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for PoissonDiskSampling<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for PoissonDiskSampling<T>
+where
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; D]: Into<P>,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+            .iter()
+            .map(|&i| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.samples.clear();
+        if data.is_empty() || self.radius <= T::zero() {
+            return false;
+        }
+        let mut tree = KdTree::<T, P>::new(D);
+        tree.set_data(data);
+        tree.build();
+
+        let mut order = (0..data.len()).collect::<Vec<_>>();
+        order.shuffle(&mut seeded_rng(self.seed));
+
+        let mut blocked = vec![false; data.len()];
+        order.into_iter().for_each(|i| {
+            if blocked[i] {
+                return;
+            }
+            self.samples.push(i);
+            tree.search_radius_ids(&data[i], self.radius)
+                .into_iter()
+                .for_each(|j| blocked[j] = true);
+        });
+        self.samples.sort();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = PoissonDiskSampling::new(0.5f32);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "radius":0.5,
+        "seed":null
+    }"#;
+    let model_de: PoissonDiskSampling<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.radius, model_de.radius);
+    assert_eq!(model.seed, model_de.seed);
+}
@@ -0,0 +1,105 @@
+use std::ops::Index;
+
+use super::sampled_indices;
+use crate::{F3lFilter, F3lFilterInverse};
+use f3l_core::{
+    seeded_rng,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Uniform random sampling without replacement.
+///
+/// Same `seed` gives same samples, random every call if `seed` is `None`.
+/// Samples are sorted.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = RandomSampling::new(1000);
+/// filter.set_seed(Some(42));
+/// let ids = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct RandomSampling {
+    pub negative: bool,
+    pub nb_samples: usize,
+    pub seed: Option<u64>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    samples: Vec<usize>,
+}
+
+impl RandomSampling {
+    pub fn new(nb_samples: usize) -> Self {
+        Self {
+            negative: false,
+            nb_samples,
+            seed: None,
+            samples: vec![],
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+}
+
+impl F3lFilterInverse for RandomSampling {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for RandomSampling
+where
+    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        sampled_indices(&self.samples, data.len(), self.negative)
+            .iter()
+            .map(|&i| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.samples.clear();
+        if data.is_empty() {
+            return false;
+        }
+        let mut rng = seeded_rng(self.seed);
+        let amount = self.nb_samples.min(data.len());
+        self.samples = rand::seq::index::sample(&mut rng, data.len(), amount).into_vec();
+        self.samples.sort();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let mut model = RandomSampling::new(100);
+    model.set_seed(Some(42));
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "nb_samples":100,
+        "seed":42
+    }"#;
+    let model_de: RandomSampling = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.nb_samples, model_de.nb_samples);
+    assert_eq!(model.seed, model_de.seed);
+}
@@ -0,0 +1,151 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Remove shadow (veil) points along depth discontinuities of depth and ToF sensors, like `ShadowPoints` of PCL.
+///
+/// Shadow points lie on the ray between foreground and background edges,
+/// so their normals are nearly perpendicular to the view ray.
+/// Point `p` with normal `n` is kept if `|n · (p - origin)| / |p - origin| >= threshold`.
+///
+/// Normals are indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+/// Points without normal, or at `origin` are shadow.
+/// `filter` returns kept points, or shadow if `negative`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut estimator = NormalEstimation::new(SearchBy::Count(10));
+/// estimator.compute(&vertices);
+///
+/// let mut filter = ShadowPoints::new(0.1f32);
+/// filter.set_normals(&estimator.normals());
+/// let out = filter.filter_instance(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ShadowPoints<T: BasicFloat> {
+    pub negative: bool,
+    /// Minimum `|cos|` between normal and view ray, in `[0, 1]`.
+    pub threshold: T,
+    /// Sensor origin. Default: origin.
+    pub origin: [T; 3],
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<Option<[T; 3]>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    kept: Vec<bool>,
+}
+
+impl<T: BasicFloat> ShadowPoints<T> {
+    pub fn new(threshold: T) -> Self {
+        Self {
+            negative: false,
+            threshold,
+            origin: [T::zero(); 3],
+            normals: vec![],
+            kept: vec![],
+        }
+    }
+
+    pub fn set_threshold(&mut self, threshold: T) {
+        self.threshold = threshold;
+    }
+
+    pub fn set_origin(&mut self, origin: [T; 3]) {
+        self.origin = origin;
+    }
+
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for ShadowPoints<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for ShadowPoints<T>
+where
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.kept
+            .iter()
+            .enumerate()
+            .filter(|&(_, &k)| k != self.negative)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.kept
+            .iter()
+            .enumerate()
+            .filter(|&(_, &k)| k != self.negative)
+            .map(|(i, _)| data[i])
+            .collect()
+    }
+
+    /// Return false if data is empty or normals do not match data.
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.kept.clear();
+        if data.is_empty() || self.normals.len() != data.len() {
+            return false;
+        }
+        self.kept = data
+            .par_iter()
+            .zip(&self.normals)
+            .map(|(&p, n)| {
+                let n = match n {
+                    Some(n) => n,
+                    None => return false,
+                };
+                let p: [T; 3] = p.into();
+                let d = [
+                    p[0] - self.origin[0],
+                    p[1] - self.origin[1],
+                    p[2] - self.origin[2],
+                ];
+                let len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+                let n_len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+                if len <= T::zero() || n_len <= T::zero() {
+                    return false;
+                }
+                (n[0] * d[0] + n[1] * d[1] + n[2] * d[2]).abs() / (len * n_len) >= self.threshold
+            })
+            .collect();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = ShadowPoints::new(0.1f32);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "threshold":0.1,
+        "origin":[0.0,0.0,0.0]
+    }"#;
+    let model_de: ShadowPoints<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.threshold, model_de.threshold);
+    assert_eq!(model.origin, model_de.origin);
+}
@@ -21,32 +21,21 @@ use f3l_search_tree::{KdTree, TreeSearch};
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct StatisticalOutlierRemoval<'a, P, T: BasicFloat, const D: usize>
-where
-    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
-{
+pub struct StatisticalOutlierRemoval<T: BasicFloat> {
     pub negative: bool,
     pub multiply: T,
     pub k_neighbors: usize,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
-    tree: KdTree<'a, T, P>,
-    #[serde(skip_serializing)]
-    #[serde(skip_deserializing)]
     inlier: Vec<bool>,
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> StatisticalOutlierRemoval<'a, P, T, D>
-where
-    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
-    [T; D]: Into<P>,
-{
+impl<T: BasicFloat> StatisticalOutlierRemoval<T> {
     pub fn new(multiply: T, k_neighbors: usize) -> Self {
         Self {
             negative: false,
             multiply,
             k_neighbors,
-            tree: KdTree::<T, P>::new(D),
             inlier: vec![],
         }
     }
@@ -57,19 +46,13 @@ where
     }
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> F3lFilterInverse
-    for StatisticalOutlierRemoval<'a, P, T, D>
-where
-    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
-    [T; D]: Into<P>,
-{
+impl<T: BasicFloat> F3lFilterInverse for StatisticalOutlierRemoval<T> {
     fn set_negative(&mut self, negative: bool) {
         self.negative = negative;
     }
 }
 
-impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D>
-    for StatisticalOutlierRemoval<'a, P, T, D>
+impl<'a, P, T: BasicFloat, const D: usize> F3lFilter<'a, P, D> for StatisticalOutlierRemoval<T>
 where
     P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
     [T; D]: Into<P>,
@@ -100,13 +83,10 @@ where
         if data.is_empty() {
             return false;
         }
-        // Check Tree dimension correct, cause skip deserialize would be 0.
-        if self.tree.dim != D {
-            self.tree = KdTree::<T, P>::new(D);
-        }
-        self.tree.set_data(data);
-
-        self.tree.build();
+        // Build tree per call, so the filter is not bound to lifetime of data.
+        let mut tree = KdTree::<T, P>::new(D);
+        tree.set_data(data);
+        tree.build();
 
         use std::sync::{Arc, Mutex};
         let nb_valid = Arc::new(Mutex::new(0usize));
@@ -116,7 +96,7 @@ where
             .par_iter()
             .enumerate()
             .map(|(i, v)| {
-                let out = self.tree.search_knn(v, self.k_neighbors);
+                let out = tree.search_knn(v, self.k_neighbors);
                 if out.is_empty() {
                     return (i, T::zero());
                 }
@@ -125,8 +105,8 @@ where
                     *lock += 1usize;
                 }
 
-                let sum = out.iter().map(|(_, o)| (*o) * (*o)).sum::<f32>();
-                (i, T::from(sum).unwrap())
+                let sum = out.iter().fold(T::zero(), |acc, &(_, o)| acc + o * o);
+                (i, sum)
             })
             .collect::<Vec<_>>();
         let nb_valid = *(nb_valid.lock().unwrap());
@@ -156,7 +136,7 @@ where
 
 #[test]
 fn serde() {
-    let model = StatisticalOutlierRemoval::<[f32; 3], f32, 3>::new(2f32, 20_usize);
+    let model = StatisticalOutlierRemoval::<f32>::new(2f32, 20_usize);
     let content = serde_json::to_string(&model).unwrap();
     println!("{}", content);
 
@@ -165,7 +145,7 @@ fn serde() {
         "multiply":2.0,
         "k_neighbors":20
     }"#;
-    let model_de: StatisticalOutlierRemoval<[f32; 3], f32, 3> = serde_json::from_str(text).unwrap();
+    let model_de: StatisticalOutlierRemoval<f32> = serde_json::from_str(text).unwrap();
     assert_eq!(model.negative, model_de.negative);
     assert_eq!(model.multiply, model_de.multiply);
     assert_eq!(model.k_neighbors, model_de.k_neighbors);
@@ -3,7 +3,7 @@ use crate::F3lFilterInverse;
 use super::F3lFilter;
 use f3l_core::serde::{self, Deserialize, Serialize};
 use f3l_core::{get_minmax, BasicFloat};
-use std::{collections::HashMap, fmt::Debug};
+use std::{cmp::Ordering, collections::HashMap, fmt::Debug};
 
 /// Build a `Dimension-wise` grid, compute mean of points per grid.
 /// 
@@ -22,7 +22,6 @@ use std::{collections::HashMap, fmt::Debug};
 pub struct VoxelGridParameter<T: BasicFloat, const D: usize> {
     bound: Vec<(T, T)>,
     inverse_div: Vec<T>,
-    nb_dim: Vec<usize>,
 }
 
 impl<T: BasicFloat, const D: usize> VoxelGridParameter<T, D> {
@@ -30,21 +29,54 @@ impl<T: BasicFloat, const D: usize> VoxelGridParameter<T, D> {
         Self {
             bound: Vec::with_capacity(D),
             inverse_div: Vec::with_capacity(D),
-            nb_dim: Vec::with_capacity(D),
         }
     }
 }
 
+/// Reduction of points in a voxel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum VoxelReduction {
+    /// Mean of points.
+    #[default]
+    Centroid,
+    /// Original point nearest to mean.
+    NearestToCentroid,
+    /// Original point with the smallest index.
+    First,
+    /// Median per dimension.
+    Median,
+}
+
+/// Reduction of attributes (normals, colors, intensities...) in a voxel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum AttributeReduction {
+    /// Mean per component.
+    Mean,
+    /// Most frequent value, for labels or colors. Ties choose the smallest one.
+    Mode,
+    /// Mean, then normalize to unit length. For normals.
+    Normal,
+    /// Value of the point returned by `filter`.
+    Representative,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
 pub struct VoxelGrid<T: BasicFloat, const D: usize> {
     pub leaf: Vec<T>,
+    #[serde(default)]
+    pub reduction: VoxelReduction,
     #[serde(skip_serializing)]
-    #[serde(skip_deserializing)]
+    #[serde(skip_deserializing, default = "VoxelGridParameter::new")]
     pub parameter: VoxelGridParameter<T, D>,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
-    voxel_map: HashMap<usize, Vec<usize>>,
+    voxel_map: HashMap<[usize; D], Vec<usize>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    keys: Vec<[usize; D]>,
 }
 
 impl<T: BasicFloat, const D: usize> Default for VoxelGrid<T, D> {
@@ -57,8 +89,10 @@ impl<T: BasicFloat, const D: usize> VoxelGrid<T, D> {
     pub fn new() -> Self {
         Self {
             leaf: vec![T::zero(); D],
+            reduction: VoxelReduction::Centroid,
             parameter: VoxelGridParameter::new(),
             voxel_map: HashMap::new(),
+            keys: vec![],
         }
     }
 
@@ -66,8 +100,10 @@ impl<T: BasicFloat, const D: usize> VoxelGrid<T, D> {
         let leaf: [T; D] = (*leaf).into();
         Self {
             leaf: leaf.iter().copied().collect(),
+            reduction: VoxelReduction::Centroid,
             parameter: VoxelGridParameter::<T, D>::new(),
             voxel_map: HashMap::new(),
+            keys: vec![],
         }
     }
 
@@ -80,6 +116,133 @@ impl<T: BasicFloat, const D: usize> VoxelGrid<T, D> {
         (0..D).for_each(|i| self.leaf[i] = leaf[i]);
     }
 
+    pub fn set_reduction(&mut self, reduction: VoxelReduction) {
+        self.reduction = reduction;
+    }
+
+    /// `(key, member indices)` of voxels of last filtering, sorted by key.
+    /// Key is the integer coordinate of voxel from lower bound of data.
+    /// Same order as outputs of `filter`, `filter_instance` and `aggregate`.
+    pub fn voxels(&self) -> impl Iterator<Item = ([usize; D], &[usize])> {
+        self.keys
+            .iter()
+            .map(|key| (*key, self.voxel_map[key].as_slice()))
+    }
+
+    /// Reduce `attributes` of points per voxel of last filtering.
+    ///
+    /// `data` is the data of last filtering, used to pick the point of `Representative`.
+    /// `attributes` are indexed the same as `data`, empty if lengths differ.
+    pub fn aggregate<P, const N: usize>(
+        &self,
+        data: &[P],
+        attributes: &[[T; N]],
+        reduction: AttributeReduction,
+    ) -> Vec<[T; N]>
+    where
+        P: Into<[T; D]> + Copy,
+    {
+        if attributes.len() != data.len() {
+            return vec![];
+        }
+        self.voxels()
+            .map(|(_, pts)| match reduction {
+                AttributeReduction::Mean => Self::mean(pts.iter().map(|&i| attributes[i])),
+                AttributeReduction::Mode => Self::mode(pts.iter().map(|&i| attributes[i])),
+                AttributeReduction::Normal => {
+                    let mut normal = Self::mean(pts.iter().map(|&i| attributes[i]));
+                    let norm = normal.iter().fold(T::zero(), |acc, v| acc + *v * *v).sqrt();
+                    if norm > T::zero() {
+                        normal.iter_mut().for_each(|v| *v /= norm);
+                    }
+                    normal
+                }
+                AttributeReduction::Representative => attributes[self.representative(data, pts)],
+            })
+            .collect()
+    }
+
+    /// Index of original point standing for the voxel.
+    fn representative<P: Into<[T; D]> + Copy>(&self, data: &[P], pts: &[usize]) -> usize {
+        let target = match self.reduction {
+            VoxelReduction::First => return *pts.iter().min().unwrap(),
+            VoxelReduction::Centroid | VoxelReduction::NearestToCentroid => {
+                Self::mean(pts.iter().map(|&i| data[i].into()))
+            }
+            VoxelReduction::Median => Self::median(pts.iter().map(|&i| data[i].into())),
+        };
+        let distance = |id: usize| {
+            let p: [T; D] = data[id].into();
+            (0..D).fold(T::zero(), |acc, i| acc + (p[i] - target[i]).powi(2))
+        };
+        pts.iter()
+            .copied()
+            .min_by(|&a, &b| total_cmp(&distance(a), &distance(b)))
+            .unwrap()
+    }
+
+    /// Reduced point of the voxel.
+    fn reduce<P: Into<[T; D]> + Copy>(&self, data: &[P], pts: &[usize]) -> [T; D] {
+        match self.reduction {
+            VoxelReduction::Centroid => Self::mean(pts.iter().map(|&i| data[i].into())),
+            VoxelReduction::Median => Self::median(pts.iter().map(|&i| data[i].into())),
+            VoxelReduction::NearestToCentroid | VoxelReduction::First => {
+                data[self.representative(data, pts)].into()
+            }
+        }
+    }
+
+    fn mean<const N: usize>(values: impl ExactSizeIterator<Item = [T; N]>) -> [T; N] {
+        let factor = T::one() / T::from(values.len()).unwrap();
+        let mut sum = [T::zero(); N];
+        values.for_each(|v| {
+            (0..N).for_each(|i| {
+                sum[i] += v[i] * factor;
+            });
+        });
+        sum
+    }
+
+    fn median<const N: usize>(values: impl Iterator<Item = [T; N]>) -> [T; N] {
+        let values = values.collect::<Vec<_>>();
+        let mid = values.len() / 2;
+        let mut median = [T::zero(); N];
+        (0..N).for_each(|i| {
+            let mut column = values.iter().map(|v| v[i]).collect::<Vec<_>>();
+            column.sort_by(total_cmp);
+            median[i] = if column.len() % 2 == 0 {
+                (column[mid - 1] + column[mid]) / T::from(2).unwrap()
+            } else {
+                column[mid]
+            };
+        });
+        median
+    }
+
+    fn mode<const N: usize>(values: impl Iterator<Item = [T; N]>) -> [T; N] {
+        let mut values = values.collect::<Vec<_>>();
+        values.sort_by(|a, b| {
+            a.iter()
+                .zip(b.iter())
+                .map(|(a, b)| total_cmp(a, b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        let mut best = (values[0], 0usize);
+        let mut run = (values[0], 0usize);
+        values.iter().for_each(|v| {
+            if *v == run.0 {
+                run.1 += 1;
+            } else {
+                run = (*v, 1);
+            }
+            if run.1 > best.1 {
+                best = run;
+            }
+        });
+        best.0
+    }
+
     fn compute_bound<P: Into<[T; D]> + Copy>(&mut self, data: &[P])
     where
         [T; D]: Into<P>,
@@ -108,25 +271,12 @@ impl<T: BasicFloat, const D: usize> VoxelGrid<T, D> {
             inverse[i] = T::one() / self.leaf[i];
         });
 
-        let mut dims = vec![0usize; D];
-        let mut count: usize = 0;
-        // check number of voxels not overflow
-        for (i, v) in box_range.iter().enumerate() {
-            match v.to_usize() {
-                Some(v) => {
-                    // add one to avoid value too small
-                    dims[i] = v + 1;
-                    if let Some(vv) = count.checked_mul(v + 1) {
-                        count = vv;
-                    } else {
-                        return false;
-                    }
-                }
-                None => return false,
-            }
+        // Voxels are hashed by integer coordinate, so only coordinate per dimension
+        // has to fit in `usize`, not number of voxels of whole extent.
+        if box_range.iter().any(|v| v.to_usize().is_none()) {
+            return false;
         }
         self.parameter.inverse_div = inverse;
-        self.parameter.nb_dim = dims;
 
         true
     }
@@ -141,79 +291,68 @@ where
     P: Into<[T; D]> + Clone + Copy + Send + Sync + Debug,
     [T; D]: Into<P>,
 {
-    /// Get not empty `ids` of `Voxel Grid` not `point id`
+    /// Get `point id` standing for each not empty grid, by `reduction`.
+    /// For `Centroid` and `Median`, the point nearest to them.
     fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
         if !self.apply_filter(data) {
             return vec![];
         }
 
-        let keys = self.voxel_map.keys();
-        keys.into_iter().copied().collect()
+        self.voxels()
+            .map(|(_, pts)| self.representative(data, pts))
+            .collect()
     }
 
-    /// Get `mean point` of not empty grids
+    /// Get reduced point of not empty grids, by `reduction`.
     fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
         if !self.apply_filter(data) {
             return vec![];
         }
 
-        let maps = &self.voxel_map;
-        maps.iter()
-            .map(|(_, pts)| {
-                let nb = T::from(pts.len()).unwrap();
-                let factor = T::one() / nb;
-                let mut sum = [T::zero(); D];
-                pts.iter().for_each(|p| {
-                    let p: [T; D] = data[*p].into();
-                    (0..D).for_each(|i| {
-                        sum[i] += p[i] * factor;
-                    });
-                });
-                sum.into()
-            })
+        self.voxels()
+            .map(|(_, pts)| self.reduce(data, pts).into())
             .collect()
     }
 
     fn apply_filter(&mut self, data: &'a [P]) -> bool {
-        if !self.leaf_check(data) {
+        self.voxel_map.clear();
+        self.keys.clear();
+        self.parameter.bound.clear();
+        if data.is_empty() || !self.leaf_check(data) {
             return false;
         }
 
         let VoxelGridParameter {
             bound,
             inverse_div: inv_div,
-            nb_dim,
         } = &self.parameter;
 
         let min = bound.iter().map(|(a, _)| *a).collect::<Vec<_>>();
 
-        let mut inc = [1usize; D];
-        (0..D).for_each(|i| {
-            if i == 0 {
-                return;
-            }
-
-            inc[i] = nb_dim[i - 1] * inc[i - 1];
-        });
-
         data.iter().enumerate().for_each(|(i, p)| {
             let p: [T; D] = (*p).into();
-            let mut dim = 0usize;
-            (0..D).for_each(|i| {
-                let v = (p[i] - min[i]) * inv_div[i];
-                let d = match v.to_usize() {
-                    Some(d) => d,
+            let mut key = [0usize; D];
+            for d in 0..D {
+                let v = (p[d] - min[d]) * inv_div[d];
+                key[d] = match v.to_usize() {
+                    Some(k) => k,
                     None => return,
                 };
-                dim += d * inc[i];
-            });
-            let vec = self.voxel_map.entry(dim).or_default();
+            }
+            let vec = self.voxel_map.entry(key).or_default();
             vec.push(i);
         });
+        self.keys = self.voxel_map.keys().copied().collect();
+        self.keys.sort();
         true
     }
 }
 
+/// Order of `total_cmp`, so `NaN` does not panic.
+fn total_cmp<T: BasicFloat>(a: &T, b: &T) -> Ordering {
+    a.to_f64().unwrap().total_cmp(&b.to_f64().unwrap())
+}
+
 #[test]
 fn serde() {
     let model = VoxelGrid::with_data(&[0.05f32; 3]);
@@ -222,4 +361,9 @@ fn serde() {
     let text = r#"{"leaf":[0.05,0.05,0.05]}"#;
     let model_de: VoxelGrid<f32, 3> = serde_json::from_str(text).unwrap();
     assert_eq!(model.leaf, model_de.leaf);
+    assert_eq!(model.reduction, model_de.reduction);
+
+    let text = r#"{"leaf":[0.05,0.05,0.05],"reduction":"Median"}"#;
+    let model_de: VoxelGrid<f32, 3> = serde_json::from_str(text).unwrap();
+    assert_eq!(model_de.reduction, VoxelReduction::Median);
 }
@@ -0,0 +1,199 @@
+use crate::F3lFilterInverse;
+
+use super::F3lFilter;
+use f3l_core::rayon::prelude::*;
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+/// Keep points visible from a pinhole camera, by a depth buffer.
+///
+/// Camera is placed in world by orthonormal `axes` (`X` right, `Y` down, `Z` forward)
+/// and `translation`, like [`crate::CropBox`]. Camera coordinate of point `p` is
+/// `axes[i] · (p - translation)`, then projected to pixel
+/// `(fx * x / z + cx, fy * y / z + cy)` of `width x height` image.
+///
+/// 1. Nearest depth of each pixel is kept, a point also writes pixels in `splat` around it,
+///    so sparse points of front surface could cover the back one.
+/// 2. Points in image and not deeper than the pixel over `tolerance` are visible.
+///
+/// Points behind camera or out of image are not visible.
+/// `filter` returns visible points, or occluded if `negative`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let mut filter = ZBufferOcclusion::new([525f32, 525.], [319.5, 239.5], [640, 480]);
+/// filter.set_pose([[1., 0., 0.], [0., -1., 0.], [0., 0., -1.]], [0., 0., 2.]);
+/// filter.set_tolerance(0.01);
+/// let visible = filter.filter(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ZBufferOcclusion<T: BasicFloat> {
+    pub negative: bool,
+    /// Focal length `fx`, `fy` in pixels.
+    pub focal: [T; 2],
+    /// Principal point `cx`, `cy` in pixels.
+    pub principal: [T; 2],
+    /// Width and height of image.
+    pub resolution: [usize; 2],
+    /// Directions of camera axes in world. Default: identity.
+    pub axes: [[T; 3]; 3],
+    /// Position of camera in world. Default: origin.
+    pub translation: [T; 3],
+    /// Depth allowed behind the nearest one of pixel. Default: 0.
+    pub tolerance: T,
+    /// Pixels written around each projection. Default: 0.
+    pub splat: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    visible: Vec<bool>,
+}
+
+impl<T: BasicFloat> ZBufferOcclusion<T> {
+    pub fn new(focal: [T; 2], principal: [T; 2], resolution: [usize; 2]) -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        Self {
+            negative: false,
+            focal,
+            principal,
+            resolution,
+            axes: [[one, zero, zero], [zero, one, zero], [zero, zero, one]],
+            translation: [zero; 3],
+            tolerance: zero,
+            splat: 0,
+            visible: vec![],
+        }
+    }
+
+    pub fn set_pose(&mut self, axes: [[T; 3]; 3], translation: [T; 3]) {
+        self.axes = axes;
+        self.translation = translation;
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: T) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn set_splat(&mut self, splat: usize) {
+        self.splat = splat;
+    }
+
+    /// Pixel and depth of point, `None` if behind camera or out of image.
+    fn project(&self, p: &[T; 3]) -> Option<(usize, usize, T)> {
+        let d = [
+            p[0] - self.translation[0],
+            p[1] - self.translation[1],
+            p[2] - self.translation[2],
+        ];
+        let [x, y, z] = self.axes.map(|a| a[0] * d[0] + a[1] * d[1] + a[2] * d[2]);
+        if z <= T::zero() {
+            return None;
+        }
+        let u = (self.focal[0] * x / z + self.principal[0]).floor();
+        let v = (self.focal[1] * y / z + self.principal[1]).floor();
+        if u < T::zero() || v < T::zero() {
+            return None;
+        }
+        let (u, v) = (u.to_usize()?, v.to_usize()?);
+        if u >= self.resolution[0] || v >= self.resolution[1] {
+            return None;
+        }
+        Some((u, v, z))
+    }
+}
+
+impl<T: BasicFloat> F3lFilterInverse for ZBufferOcclusion<T> {
+    fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+}
+
+impl<'a, P, T: BasicFloat> F3lFilter<'a, P, 3> for ZBufferOcclusion<T>
+where
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync,
+{
+    fn filter(&mut self, data: &'a [P]) -> Vec<usize> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.visible
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != self.negative)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn filter_instance(&mut self, data: &'a [P]) -> Vec<P> {
+        if !self.apply_filter(data) {
+            return vec![];
+        }
+        self.visible
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != self.negative)
+            .map(|(i, _)| data[i])
+            .collect()
+    }
+
+    fn apply_filter(&mut self, data: &'a [P]) -> bool {
+        self.visible.clear();
+        let [width, height] = self.resolution;
+        if data.is_empty() || width == 0 || height == 0 {
+            return false;
+        }
+        let projected = data
+            .par_iter()
+            .map(|&p| self.project(&p.into()))
+            .collect::<Vec<_>>();
+
+        let mut depth = vec![T::max_value(); width * height];
+        projected.iter().flatten().for_each(|&(u, v, z)| {
+            let rows = v.saturating_sub(self.splat)..(v + self.splat + 1).min(height);
+            rows.for_each(|r| {
+                (u.saturating_sub(self.splat)..(u + self.splat + 1).min(width)).for_each(|c| {
+                    let d = &mut depth[r * width + c];
+                    *d = d.min(z);
+                });
+            });
+        });
+
+        self.visible = projected
+            .par_iter()
+            .map(|p| match p {
+                Some((u, v, z)) => *z <= depth[v * width + u] + self.tolerance,
+                None => false,
+            })
+            .collect();
+        true
+    }
+}
+
+#[test]
+fn serde() {
+    let model = ZBufferOcclusion::new([525f32, 525.], [319.5, 239.5], [640, 480]);
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "negative":false,
+        "focal":[525.0,525.0],
+        "principal":[319.5,239.5],
+        "resolution":[640,480],
+        "axes":[[1.0,0.0,0.0],[0.0,1.0,0.0],[0.0,0.0,1.0]],
+        "translation":[0.0,0.0,0.0],
+        "tolerance":0.0,
+        "splat":0
+    }"#;
+    let model_de: ZBufferOcclusion<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.negative, model_de.negative);
+    assert_eq!(model.focal, model_de.focal);
+    assert_eq!(model.principal, model_de.principal);
+    assert_eq!(model.resolution, model_de.resolution);
+    assert_eq!(model.axes, model_de.axes);
+    assert_eq!(model.translation, model_de.translation);
+    assert_eq!(model.splat, model_de.splat);
+}
@@ -0,0 +1,61 @@
+use f3l_filter::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Noisy step of `z = 0` for `x < 0` and `z = 0.5` otherwise.
+fn noisy_step() -> Vec<[f32; 3]> {
+    let mut rng = StdRng::seed_from_u64(0);
+    (-10..10)
+        .flat_map(|x| (-10..10).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let z = if x < 0 { 0. } else { 0.5 };
+            [
+                x as f32 * 0.05,
+                y as f32 * 0.05,
+                z + rng.gen_range(-0.01f32..0.01),
+            ]
+        })
+        .collect()
+}
+
+fn error(cloud: &[[f32; 3]]) -> f32 {
+    cloud
+        .iter()
+        .map(|p| (p[2] - if p[0] < 0. { 0. } else { 0.5 }).abs())
+        .sum()
+}
+
+#[test]
+fn bilateral_keeps_edges() {
+    let data = noisy_step();
+    let normals = vec![Some([0f32, 0., 1.]); data.len()];
+    let mut filter = BilateralFilter::new(0.05f32, 0.02);
+    filter.set_normals(&normals);
+    filter.set_iterations(2);
+    let out = filter.smooth(&data);
+
+    assert_eq!(out.len(), data.len());
+    assert!(error(&out) < error(&data) * 0.7);
+    // Only moved along normals.
+    assert!(out
+        .iter()
+        .zip(&data)
+        .all(|(a, b)| a[0] == b[0] && a[1] == b[1]));
+}
+
+#[test]
+fn bilateral_intensity_range() {
+    let data = noisy_step();
+    let normals = vec![Some([0f32, 0., 1.]); data.len()];
+    let intensities = data
+        .iter()
+        .map(|p| if p[0] < 0. { 0f32 } else { 1. })
+        .collect::<Vec<_>>();
+    let mut filter = BilateralFilter::new(0.05f32, 0.1);
+    filter.set_normals(&normals);
+    filter.set_intensities(&intensities);
+    let out = filter.smooth(&data);
+    assert!(error(&out) < error(&data));
+
+    filter.set_intensities(&intensities[..10]);
+    assert!(filter.smooth(&data).is_empty());
+}
@@ -0,0 +1,70 @@
+use f3l_filter::*;
+
+/// Hill `z = 2 sin(x / 4)` with buildings of 8 x 8 and a tree.
+/// Return data and labels of ground.
+fn urban() -> (Vec<[f32; 3]>, Vec<bool>) {
+    (0..60)
+        .flat_map(|x| (0..60).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let (fx, fy) = (x as f32 * 0.5, y as f32 * 0.5);
+            let ground = 2. * (fx / 4.).sin();
+            let building = ((10..18).contains(&x) && (30..38).contains(&y))
+                || ((40..48).contains(&x) && (5..13).contains(&y));
+            let tree = x == 30 && y == 50;
+            let z = if building {
+                ground + 6.
+            } else if tree {
+                ground + 3.
+            } else {
+                ground
+            };
+            ([fx, fy, z], !(building || tree))
+        })
+        .unzip()
+}
+
+#[test]
+fn ground_and_non_ground() {
+    let (data, labels) = urban();
+    let mut filter = ClothSimulationFilter::new(0.5f32);
+    let ground = filter.filter(&data);
+    let expect = labels
+        .iter()
+        .enumerate()
+        .filter(|(_, &g)| g)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(ground, expect);
+
+    filter.set_negative(true);
+    assert_eq!(filter.filter(&data).len(), 2 * 8 * 8 + 1);
+}
+
+#[test]
+fn deterministic() {
+    let (data, _) = urban();
+    let mut filter = ClothSimulationFilter::new(1f32);
+    filter.set_rigidness(1);
+    let first = filter.filter(&data);
+    assert_eq!(first, filter.filter(&data));
+}
+
+#[test]
+fn invalid_input() {
+    let (mut data, _) = urban();
+    let mut filter = ClothSimulationFilter::new(0.5f32);
+    let ground = filter.filter(&data);
+
+    // Non-finite points are skipped.
+    let nb = data.len();
+    data.push([f32::NAN, 0., 0.]);
+    data.push([0., 0., f32::NEG_INFINITY]);
+    assert_eq!(filter.filter(&data), ground);
+    filter.set_negative(true);
+    assert!(filter.filter(&data).iter().all(|&i| i < nb));
+
+    for resolution in [0., -1., f32::NAN] {
+        filter.cloth_resolution = resolution;
+        assert!(filter.filter(&data).is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+//! Data shared by tests.
+use std::ops::RangeInclusive;
+
+/// Grid of `range` on each axis, scaled by `step` and shifted by `offset`.
+///
+/// First axis changes slowest.
+pub fn grid<const D: usize>(range: RangeInclusive<i32>, step: f32, offset: f32) -> Vec<[f32; D]> {
+    let values = range.map(|v| v as f32 * step + offset).collect::<Vec<_>>();
+    let n = values.len();
+    (0..n.pow(D as u32))
+        .map(|mut i| {
+            let mut p = [0f32; D];
+            (0..D).rev().for_each(|axis| {
+                p[axis] = values[i % n];
+                i /= n;
+            });
+            p
+        })
+        .collect()
+}
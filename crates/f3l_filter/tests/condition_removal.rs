@@ -1,3 +1,5 @@
+mod common;
+
 use f3l_filter::*;
 use std::ops::Bound;
 
@@ -62,3 +64,88 @@ mod filter {
         }
     }
 }
+
+mod condition {
+    use super::*;
+
+    #[test]
+    fn nested_tree() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        // (x < 3 || x >= 8) && !(y == 5)
+        let condition = Condition::and(vec![
+            Condition::or(vec![
+                Condition::compare(Quantity::Coordinate(0), Comparison::Less(3.)),
+                Condition::compare(Quantity::Coordinate(0), Comparison::GreaterEqual(8.)),
+            ]),
+            !Condition::compare(Quantity::Coordinate(1), Comparison::Equal(5.)),
+        ]);
+        let mut filter = ConditionRemoval::with_condition(condition);
+        let out = filter.filter(&data);
+        assert_eq!(out.len(), 5 * 9 * 10);
+
+        filter.set_negative(true);
+        let outlier = filter.filter(&data);
+        assert_eq!(out.len() + outlier.len(), data.len());
+    }
+
+    #[test]
+    fn attributes() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        // [intensity, label, normal z]
+        let attributes = data
+            .iter()
+            .map(|p| [p[0] * 0.1, p[1] % 3., if p[2] < 5. { 0. } else { 1. }])
+            .collect::<Vec<_>>();
+        let condition = Condition::and(vec![
+            Condition::compare(Quantity::Attribute(0), Comparison::Greater(0.45)),
+            Condition::compare(Quantity::Attribute(1), Comparison::In(vec![0., 2.])),
+            Condition::compare(Quantity::Attribute(2), Comparison::Less(0.2)),
+        ]);
+        let mut filter = ConditionRemoval::with_condition(condition);
+        filter.set_attributes(&attributes);
+        let out = filter.filter(&data);
+        // x in 5..10, y % 3 in {0, 2}, z < 5
+        assert_eq!(out.len(), 5 * 7 * 5);
+
+        // Attributes not matching data.
+        filter.set_attributes(&attributes[..10]);
+        assert!(filter.filter(&data).is_empty());
+    }
+
+    #[test]
+    fn distances_with_bound() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let condition = Condition::or(vec![
+            Condition::compare(
+                Quantity::DistanceToPoint(vec![0., 0., 0.]),
+                Comparison::LessEqual(1.),
+            ),
+            // Plane 2z - 18 = 0, which is z = 9.
+            Condition::compare(
+                Quantity::DistanceToPlane([0., 0., 2., -18.]),
+                Comparison::Range(Bound::Included(-0.5)..Bound::Included(0.5)),
+            ),
+        ]);
+        let mut filter = ConditionRemoval::with_condition(condition);
+        filter.set_parameter(&vec![(0, Bound::Unbounded..Bound::Excluded(5.))]);
+        let out = filter.filter(&data);
+        // Origin and its 3 neighbors, and z = 9 of x < 5.
+        assert_eq!(out.len(), 4 + 5 * 10);
+    }
+
+    #[test]
+    fn from_json() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let text = r#"{
+            "negative":false,
+            "bound":[],
+            "condition":{"Or":[
+                {"Compare":{"quantity":{"Coordinate":0},"comparison":{"Range":{"start":{"Included":2.0},"end":{"Excluded":4.0}}}}},
+                {"Not":{"Compare":{"quantity":{"Coordinate":2},"comparison":{"NotEqual":0.0}}}}
+            ]}
+        }"#;
+        let mut filter: ConditionRemoval<f32> = serde_json::from_str(text).unwrap();
+        let out = filter.filter(&data);
+        assert_eq!(out.len(), 2 * 100 + 8 * 10);
+    }
+}
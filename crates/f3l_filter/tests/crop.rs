@@ -0,0 +1,126 @@
+mod common;
+
+use f3l_filter::*;
+
+mod crop_box {
+    use super::*;
+
+    #[test]
+    fn axis_aligned() {
+        let data = crate::common::grid::<3>(-10..=10, 0.1, 0.);
+        let mut filter = CropBox::with_data(&[-0.25f32, -0.25, -0.25], &[0.25f32, 0.25, 0.25]);
+        let out = filter.filter(&data);
+        assert_eq!(out.len(), 5 * 5 * 5);
+        assert!(out.iter().all(|&i| data[i].iter().all(|v| v.abs() < 0.25)));
+
+        filter.set_negative(true);
+        let outlier = filter.filter(&data);
+        assert_eq!(out.len() + outlier.len(), data.len());
+    }
+
+    #[test]
+    fn rotated_and_translated() {
+        let data = crate::common::grid::<2>(-10..=10, 0.1, 0.);
+        let s = std::f32::consts::FRAC_1_SQRT_2;
+        // A diamond of half diagonal 0.45 centered at (0.2, 0.2).
+        let mut filter =
+            CropBox::from_obb(&[0.2f32, 0.2], &[[s, s], [-s, s]], &[0.45f32 * s, 0.45 * s]);
+        let out = filter.filter(&data);
+        let expect = data
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| (p[0] - 0.2).abs() + (p[1] - 0.2).abs() < 0.45)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(out, expect);
+    }
+}
+
+mod crop_hull {
+    use super::*;
+    use f3l_surface::{Convex, ConvexHull2D, ConvexHull3D, ConvexHullId};
+
+    #[test]
+    fn convex_polygon() {
+        let polygon = vec![[0.05f32, -0.5], [0.55, 0.], [0.05, 0.5], [-0.45, 0.]];
+        let mut cvh = ConvexHull2D::with_data(&polygon);
+        cvh.compute();
+
+        let data = crate::common::grid::<2>(-10..=10, 0.1, 0.);
+        let mut filter = CropHull::new();
+        filter.set_polygon(&polygon, &cvh.hulls);
+        let out = filter.filter(&data);
+        let expect = data
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| (p[0] - 0.05).abs() + p[1].abs() < 0.5)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn polygon_with_hole_on_dims() {
+        let vertices = vec![
+            [-0.55f32, -0.55],
+            [0.55, -0.55],
+            [0.55, 0.55],
+            [-0.55, 0.55],
+            [-0.25, -0.25],
+            [0.25, -0.25],
+            [0.25, 0.25],
+            [-0.25, 0.25],
+        ];
+        let contours = vec![
+            vec![(0, 1), (1, 2), (2, 3), (3, 0)],
+            vec![(4, 5), (5, 6), (6, 7), (7, 4)],
+        ];
+        let data = crate::common::grid::<3>(-10..=10, 0.1, 0.);
+        let mut filter = CropHull::new();
+        filter.set_contours(&vertices, &contours);
+        filter.set_dims([0, 2]);
+        let out = filter.filter(&data);
+        // 11 x 11 minus 5 x 5 in XZ, all of Y.
+        assert_eq!(out.len(), (11 * 11 - 5 * 5) * 21);
+        assert!(out.iter().all(|&i| {
+            let p = data[i];
+            p[0].abs().max(p[2].abs()) > 0.25
+        }));
+    }
+
+    #[test]
+    fn convex_mesh() {
+        let corners = (0..8)
+            .map(|i| {
+                [
+                    if i & 1 == 0 { -0.55f32 } else { 0.35 },
+                    if i & 2 == 0 { -0.35f32 } else { 0.55 },
+                    if i & 4 == 0 { -0.15f32 } else { 0.15 },
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut cvh = ConvexHull3D::with_data(&corners);
+        cvh.compute();
+        let faces = match cvh.hulls {
+            ConvexHullId::D3(faces) => faces,
+            _ => panic!("Could not resolve to D3 type."),
+        };
+
+        let data = crate::common::grid::<3>(-10..=10, 0.1, 0.);
+        let mut filter = CropHull::new();
+        filter.set_mesh(&corners, &faces);
+        let out = filter.filter(&data);
+        let expect = data
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| {
+                p[0] > -0.55 && p[0] < 0.35 && p[1] > -0.35 && p[1] < 0.55 && p[2].abs() < 0.15
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(out, expect);
+
+        filter.set_negative(true);
+        assert_eq!(filter.filter(&data).len(), data.len() - expect.len());
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use f3l_filter::*;
+use std::ops::Bound;
+
+mod filter {
+    use super::*;
+
+    #[test]
+    fn pipeline_provenance() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut pipeline = FilterPipeline::new();
+        pipeline
+            .push(PassThrough::with_data(
+                Bound::Included(0.)..Bound::Excluded(5.),
+                0,
+            ))
+            .push(PassThrough::with_data(
+                Bound::Included(0.)..Bound::Excluded(5.),
+                1,
+            ));
+        let out = pipeline.filter(&data);
+
+        assert_eq!(pipeline.provenance().len(), 2);
+        assert_eq!(pipeline.provenance()[0].len(), 500);
+        assert_eq!(out.len(), 250);
+        out.iter().for_each(|&i| {
+            assert!(data[i][0] < 5.);
+            assert!(data[i][1] < 5.);
+        });
+    }
+
+    #[test]
+    fn pipeline_same_as_chained() {
+        let mut data = crate::common::grid::<3>(0..=9, 1., 0.);
+        // Isolated points removed by radius outlier removal.
+        data.push([50., 50., 50.]);
+        data.push([-50., 0., 0.]);
+
+        let mut pass = PassThrough::with_data(Bound::Included(-60.)..Bound::Included(5.), 0);
+        let step = pass.filter_instance(&data);
+        let mut radius = RadiusOutlierRemoval::new(1.1f32, 3);
+        let expected = radius.filter_instance(&step);
+
+        let mut pipeline = FilterPipeline::new();
+        pipeline
+            .push(PassThrough::with_data(
+                Bound::Included(-60.)..Bound::Included(5.),
+                0,
+            ))
+            .push(RadiusOutlierRemoval::new(1.1f32, 3));
+        let out = pipeline.filter(&data);
+
+        assert_eq!(out.len(), expected.len());
+        assert!(!out.contains(&1000));
+        assert!(!out.contains(&1001));
+        out.iter()
+            .for_each(|&i| assert!(expected.contains(&data[i])));
+    }
+
+    #[test]
+    fn pipeline_voxel_grid_keeps_original_points() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut pipeline = FilterPipeline::new();
+        pipeline.push(VoxelGrid::with_data(&[2f32; 3]));
+        let out = pipeline.filter_instance(&data);
+
+        assert_eq!(out.len(), 125);
+        out.iter().for_each(|p| assert!(data.contains(p)));
+    }
+
+    #[test]
+    fn pipeline_negative() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut pipeline = FilterPipeline::new();
+        pipeline.push(PassThrough::with_data(
+            Bound::Included(0.)..Bound::Excluded(3.),
+            2,
+        ));
+        pipeline.set_negative(true);
+        let out = pipeline.filter(&data);
+
+        assert_eq!(out.len(), 700);
+        out.iter().for_each(|&i| assert!(data[i][2] >= 3.));
+    }
+
+    #[test]
+    fn pipeline_from_json() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let text = r#"[
+            {"PassThrough":{"negative":false,"dim":2,"bound":{"start":"Unbounded","end":{"Excluded":4.0}}}},
+            {"ConditionRemoval":{"negative":false,"bound":[[0,{"start":{"Included":2.0},"end":"Unbounded"}]]}},
+            {"StatisticalOutlierRemoval":{"negative":false,"multiply":3.0,"k_neighbors":6}}
+        ]"#;
+        let stages: Vec<FilterStage<f32, 3>> = serde_json::from_str(text).unwrap();
+        let mut pipeline = FilterPipeline::from_stages(stages);
+        assert_eq!(pipeline.len(), 3);
+
+        let out = pipeline.filter(&data);
+        assert_eq!(pipeline.provenance()[0].len(), 400);
+        assert_eq!(pipeline.provenance()[1].len(), 320);
+        out.iter().for_each(|&i| {
+            assert!(data[i][2] < 4.);
+            assert!(data[i][0] >= 2.);
+        });
+    }
+}
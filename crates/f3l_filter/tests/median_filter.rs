@@ -0,0 +1,34 @@
+use f3l_filter::*;
+use f3l_search_tree::SearchBy;
+
+fn plane_with_spikes() -> Vec<[f32; 3]> {
+    (0..20)
+        .flat_map(|x| (0..20).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let z = if x % 10 == 5 && y % 10 == 5 { 0.3 } else { 0. };
+            [x as f32 * 0.1, y as f32 * 0.1, z]
+        })
+        .collect()
+}
+
+#[test]
+fn median_removes_spikes() {
+    let data = plane_with_spikes();
+    let filter = MedianFilter::new(SearchBy::Count(9));
+    let out = filter.smooth(&data);
+    assert_eq!(out.len(), data.len());
+    assert!(out.iter().all(|p| p[2].abs() < 1e-6));
+}
+
+#[test]
+fn median_max_displacement() {
+    let data = plane_with_spikes();
+    let mut filter = MedianFilter::new(SearchBy::Count(9));
+    filter.set_max_displacement(Some(0.1f32));
+    let out = filter.smooth(&data);
+    assert!(out.iter().zip(&data).all(|(a, b)| {
+        let d = (0..3).map(|i| (a[i] - b[i]).powi(2)).sum::<f32>().sqrt();
+        d <= 0.1 + 1e-6
+    }));
+    assert!(out.iter().any(|p| (p[2] - 0.2).abs() < 1e-6));
+}
@@ -0,0 +1,79 @@
+use f3l_filter::*;
+
+/// Sloped terrain `z = 0.1 x` with a building of 8 x 8 and a tree.
+/// Return data and labels of ground.
+fn aerial() -> (Vec<[f32; 3]>, Vec<bool>) {
+    (0..60)
+        .flat_map(|x| (0..60).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let ground = x as f32 * 0.1;
+            let building = (20..28).contains(&x) && (30..38).contains(&y);
+            let tree = x == 45 && y == 10;
+            let z = if building {
+                ground + 6.
+            } else if tree {
+                ground + 3.
+            } else {
+                ground
+            };
+            ([x as f32 * 0.5, y as f32 * 0.5, z], !(building || tree))
+        })
+        .unzip()
+}
+
+#[test]
+fn ground_and_non_ground() {
+    let (data, labels) = aerial();
+    let mut filter = ProgressiveMorphologicalFilter::new(0.5f32);
+    filter.set_max_window_size(17);
+    filter.set_slope(0.5);
+
+    let ground = filter.filter(&data);
+    let expect = labels
+        .iter()
+        .enumerate()
+        .filter(|(_, &g)| g)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(ground, expect);
+
+    filter.set_negative(true);
+    let non_ground = filter.filter(&data);
+    assert_eq!(non_ground.len(), 8 * 8 + 1);
+    assert_eq!(filter.filter_instance(&data).len(), non_ground.len());
+}
+
+#[test]
+fn small_window_keeps_building() {
+    let (data, _) = aerial();
+    let mut filter = ProgressiveMorphologicalFilter::new(0.5f32);
+    // Windows of 3 and 5 cells, smaller than the building.
+    filter.set_max_window_size(5);
+    // Tolerance of slope of roof.
+    filter.set_initial_distance(0.5);
+    filter.set_negative(true);
+    let non_ground = filter.filter(&data);
+    assert_eq!(non_ground.len(), 1);
+}
+
+#[test]
+fn invalid_input() {
+    let (mut data, _) = aerial();
+    let mut filter = ProgressiveMorphologicalFilter::new(0.5f32);
+    filter.set_max_window_size(17);
+    filter.set_slope(0.5);
+    let ground = filter.filter(&data);
+
+    // Non-finite points are skipped.
+    let nb = data.len();
+    data.push([f32::NAN, 0., 0.]);
+    data.push([0., f32::INFINITY, 0.]);
+    assert_eq!(filter.filter(&data), ground);
+    filter.set_negative(true);
+    assert!(filter.filter(&data).iter().all(|&i| i < nb));
+
+    for cell_size in [0., -1., f32::NAN] {
+        filter.cell_size = cell_size;
+        assert!(filter.filter(&data).is_empty());
+    }
+}
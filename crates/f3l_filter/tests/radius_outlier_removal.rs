@@ -57,6 +57,17 @@ mod filter {
             out.into_iter().for_each(|v| count -= v[0]);
             assert_relative_eq!(count, 0f32);
         }
+
+        #[test]
+        fn radius_outlier_removal_reuse() {
+            let mut filter = RadiusOutlierRemoval::new(1.5f32, 2);
+            {
+                let data = vec![[1f32], [3.], [4.], [5.], [7.]];
+                assert_eq!(filter.filter(&data), vec![1, 2, 3]);
+            }
+            let data = vec![[0f32], [1.], [9.]];
+            assert_eq!(filter.filter(&data), vec![0, 1]);
+        }
     }
 
     mod dimension_3d {
@@ -0,0 +1,131 @@
+mod common;
+
+use f3l_filter::*;
+
+mod sampling {
+    use super::*;
+
+    fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+        (0..3)
+            .fold(0f32, |acc, i| acc + (a[i] - b[i]).powi(2))
+            .sqrt()
+    }
+
+    #[test]
+    fn farthest_point_sampling() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut filter = FarthestPointSampling::new(20);
+        let ids = filter.filter(&data);
+
+        assert_eq!(ids.len(), 20);
+        assert_eq!(data[ids[0]], [0., 0., 0.]);
+        assert_eq!(data[ids[1]], [9., 9., 9.]);
+        // Each sample is the farthest to samples before it.
+        (1..ids.len()).for_each(|n| {
+            let nearest = |p: &[f32; 3]| {
+                ids[..n]
+                    .iter()
+                    .map(|&i| distance(p, &data[i]))
+                    .fold(f32::MAX, f32::min)
+            };
+            let farthest = data.iter().map(nearest).fold(0f32, f32::max);
+            assert_eq!(nearest(&data[ids[n]]), farthest);
+        });
+    }
+
+    #[test]
+    fn farthest_point_sampling_duplicated() {
+        let data = vec![[0f32, 0., 0.]; 5];
+        let mut filter = FarthestPointSampling::new(5);
+        let mut ids = filter.filter(&data);
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn random_sampling_seed() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut filter = RandomSampling::new(100);
+        filter.set_seed(Some(7));
+        let a = filter.filter(&data);
+        let b = filter.filter(&data);
+
+        assert_eq!(a.len(), 100);
+        assert_eq!(a, b);
+        let mut dedup = a.clone();
+        dedup.dedup();
+        assert_eq!(dedup.len(), 100);
+
+        filter.set_negative(true);
+        let c = filter.filter(&data);
+        assert_eq!(c.len(), 900);
+        assert!(c.iter().all(|i| !a.contains(i)));
+    }
+
+    #[test]
+    fn poisson_disk_sampling() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let radius = 2.5f32;
+        let mut filter = PoissonDiskSampling::new(radius);
+        filter.set_seed(Some(3));
+        let ids = filter.filter(&data);
+
+        assert!(!ids.is_empty());
+        ids.iter().enumerate().for_each(|(n, &i)| {
+            ids.iter().skip(n + 1).for_each(|&j| {
+                assert!(distance(&data[i], &data[j]) > radius);
+            });
+        });
+        // Maximal: every point is covered by a sample.
+        data.iter().for_each(|p| {
+            assert!(ids.iter().any(|&i| distance(p, &data[i]) <= radius));
+        });
+        assert_eq!(ids, filter.filter(&data));
+    }
+
+    #[test]
+    fn normal_space_sampling() {
+        // 90 points on plane z = 0 with normal z, 10 points on plane x = 0 with normal x.
+        let mut data = (0..90)
+            .map(|i| [(i % 10) as f32 + 1., (i / 10) as f32, 0.])
+            .collect::<Vec<_>>();
+        let mut normals = vec![Some([0f32, 0., 1.]); 90];
+        (0..10).for_each(|i| {
+            data.push([0., i as f32, 1.]);
+            normals.push(Some([1., 0., 0.]));
+        });
+        normals[0] = None;
+
+        let mut filter = NormalSpaceSampling::new(20, 4);
+        filter.set_seed(Some(1));
+        filter.set_normals(&normals);
+        let ids = filter.filter(&data);
+
+        assert_eq!(ids.len(), 20);
+        assert!(!ids.contains(&0));
+        // Both buckets are sampled equally.
+        assert_eq!(ids.iter().filter(|&&i| i >= 90).count(), 10);
+    }
+
+    #[test]
+    fn covariance_sampling() {
+        // A large plane z = 0 and a small plane x = 0, the small one constrains
+        // translation along x, which should be kept.
+        let mut data = (0..400)
+            .map(|i| [(i % 20) as f32 + 1., (i / 20) as f32, 0.])
+            .collect::<Vec<_>>();
+        let mut normals = vec![Some([0f32, 0., 1.]); 400];
+        (0..5).for_each(|i| {
+            data.push([0., i as f32, 1.]);
+            normals.push(Some([1., 0., 0.]));
+        });
+
+        let mut filter = CovarianceSampling::new(20);
+        filter.set_normals(&normals);
+        let ids = filter.filter(&data);
+
+        assert_eq!(ids.len(), 20);
+        assert!(ids.iter().any(|&i| i >= 400));
+        assert!(ids.iter().any(|&i| i < 400));
+    }
+}
@@ -0,0 +1,53 @@
+use f3l_features::NormalEstimation;
+use f3l_filter::*;
+use f3l_search_tree::SearchBy;
+
+/// Sensor at origin looks at a square at `z = 1` before a wall at `z = 3`,
+/// veil points are interpolated between right edge of square and the wall.
+/// Return data and labels of veil.
+fn scene() -> (Vec<[f32; 3]>, Vec<bool>) {
+    let square = (0..21)
+        .flat_map(|x| (0..21).map(move |y| [x as f32 * 0.05 - 0.5, y as f32 * 0.05 - 0.5, 1.]))
+        .map(|p| (p, false));
+    let wall = (0..61)
+        .flat_map(|x| (0..61).map(move |y| [x as f32 * 0.05 - 1.5, y as f32 * 0.05 - 1.5, 3.]))
+        .map(|p| (p, false));
+    let veil = (0..21)
+        .flat_map(|y| (2..40).map(move |t| (y, t)))
+        .map(|(y, t)| {
+            let t = 1. + t as f32 * 0.05;
+            ([0.5 * t, (y as f32 * 0.05 - 0.5) * t, t], true)
+        });
+    square.chain(wall).chain(veil).unzip()
+}
+
+#[test]
+fn remove_veil() {
+    let (data, labels) = scene();
+    let mut estimator = NormalEstimation::new(SearchBy::Count(10));
+    // Fast eigen solver does not handle exact planes.
+    estimator.set_fast(false);
+    estimator.compute(&data);
+
+    let mut filter = ShadowPoints::new(0.1f32);
+    filter.set_normals(&estimator.normals());
+    let kept = filter.filter(&data);
+
+    let nb_veil = labels.iter().filter(|&&l| l).count();
+    let kept_veil = kept.iter().filter(|&&i| labels[i]).count();
+    let kept_other = kept.len() - kept_veil;
+    assert!((kept_veil as f32) < nb_veil as f32 * 0.1);
+    assert!(kept_other as f32 > (data.len() - nb_veil) as f32 * 0.95);
+
+    filter.set_negative(true);
+    let shadow = filter.filter(&data);
+    assert_eq!(kept.len() + shadow.len(), data.len());
+}
+
+#[test]
+fn normals_not_match() {
+    let (data, _) = scene();
+    let mut filter = ShadowPoints::new(0.1f32);
+    filter.set_normals(&[Some([0f32, 0., 1.])]);
+    assert!(filter.filter(&data).is_empty());
+}
@@ -0,0 +1,88 @@
+use f3l_filter::*;
+
+/// Sphere of radius 1 at origin.
+fn sphere() -> Vec<[f32; 3]> {
+    let (rings, segments) = (40, 80);
+    (1..rings)
+        .flat_map(|r| (0..segments).map(move |s| (r, s)))
+        .map(|(r, s)| {
+            let theta = std::f32::consts::PI * r as f32 / rings as f32;
+            let phi = 2. * std::f32::consts::PI * s as f32 / segments as f32;
+            [
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn hidden_point_removal_sphere() {
+    let data = sphere();
+    let mut filter = HiddenPointRemoval::new([0f32, 0., 5.]);
+    let visible = filter.filter(&data);
+
+    // Horizon of viewpoint at distance 5 is z = 1 / 5.
+    assert!(!visible.is_empty());
+    assert!(visible.iter().all(|&i| data[i][2] > 0.1));
+    let front = data.iter().filter(|p| p[2] > 0.3).count();
+    let kept = visible.iter().filter(|&&i| data[i][2] > 0.3).count();
+    assert!(kept as f32 > front as f32 * 0.95);
+
+    filter.set_negative(true);
+    let hidden = filter.filter(&data);
+    assert_eq!(visible.len() + hidden.len(), data.len());
+}
+
+#[test]
+fn hidden_point_removal_occluded_plane() {
+    // Small plane at z = 1 in front of a large plane at z = 0.
+    let front = (0..11)
+        .flat_map(|x| (0..11).map(move |y| [x as f32 * 0.1 - 0.5, y as f32 * 0.1 - 0.5, 1.]));
+    let back =
+        (0..41).flat_map(|x| (0..41).map(move |y| [x as f32 * 0.1 - 2., y as f32 * 0.1 - 2., 0.]));
+    let data = front.chain(back).collect::<Vec<_>>();
+
+    let mut filter = HiddenPointRemoval::new([0f32, 0., 3.]);
+    let visible = filter.filter(&data);
+    // Back points right behind the front plane are hidden.
+    assert!(visible
+        .iter()
+        .all(|&i| data[i][2] > 0.5 || data[i][0].abs() > 0.4 || data[i][1].abs() > 0.4));
+    assert!(visible.iter().any(|&i| data[i][2] < 0.5));
+}
+
+#[test]
+fn z_buffer_occlusion() {
+    // Camera at z = 3 looking down.
+    let front = (0..11)
+        .flat_map(|x| (0..11).map(move |y| [x as f32 * 0.1 - 0.5, y as f32 * 0.1 - 0.5, 1.]));
+    let back =
+        (0..41).flat_map(|x| (0..41).map(move |y| [x as f32 * 0.1 - 2., y as f32 * 0.1 - 2., 0.]));
+    let behind = [[0f32, 0., 4.]];
+    let data = front.chain(back).chain(behind).collect::<Vec<_>>();
+
+    let mut filter = ZBufferOcclusion::new([200f32, 200.], [100., 100.], [200, 200]);
+    filter.set_pose([[1., 0., 0.], [0., -1., 0.], [0., 0., -1.]], [0., 0., 3.]);
+    // Front points are 10 pixels apart.
+    filter.set_splat(5);
+    filter.set_tolerance(0.01);
+    let visible = filter.filter(&data);
+
+    assert!((0..121).all(|i| visible.contains(&i)));
+    assert!(!visible.contains(&(data.len() - 1)));
+    // Back points under the front plane are occluded.
+    assert!(visible
+        .iter()
+        .all(|&i| data[i][2] > 0.5 || data[i][0].abs() > 0.5 || data[i][1].abs() > 0.5));
+    // Corners of back plane are out of image, `3 * 100 / 2` pixels from the center.
+    assert!(!visible
+        .iter()
+        .any(|&i| data[i][0].abs() > 1.9 && data[i][1].abs() > 1.9));
+    assert!(visible.iter().any(|&i| data[i][2] < 0.5));
+
+    filter.set_negative(true);
+    let occluded = filter.filter(&data);
+    assert_eq!(visible.len() + occluded.len(), data.len());
+}
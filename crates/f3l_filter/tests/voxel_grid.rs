@@ -1,3 +1,6 @@
+mod common;
+
+use approx::assert_relative_eq;
 use f3l_filter::*;
 
 mod filter {
@@ -18,6 +21,17 @@ mod filter {
                 assert!(out.contains(&[d]));
             });
         }
+
+        #[test]
+        fn voxel_grid_1d_point_ids() {
+            let data = (0..9).map(|i| [i as f32]).collect::<Vec<_>>();
+            let mut filter = VoxelGrid::with_data(&[3f32]);
+            let mut out = filter.filter(&data);
+            out.sort();
+
+            // Point nearest to mean of each voxel, not the voxel index.
+            assert_eq!(out, vec![1, 4, 7]);
+        }
     }
 
     mod dimension_3d {
@@ -46,4 +60,203 @@ mod filter {
             assert_eq!(size, out.len());
         }
     }
+
+    mod sparse {
+        use super::*;
+
+        #[test]
+        fn voxel_grid_huge_extent() {
+            // Dense index of 1e8 ^ 3 voxels overflows `usize`.
+            let data = vec![
+                [0f64, 0., 0.],
+                [0.0005, 0.0005, 0.],
+                [1e5, 1e5, 1e5],
+                [1e5 + 0.0005, 1e5, 1e5],
+                [-1e5, 3e4, -2e4],
+            ];
+            let mut filter = VoxelGrid::with_data(&[0.001f64; 3]);
+            let out = filter.filter(&data);
+
+            assert_eq!(out.len(), 3);
+        }
+
+        #[test]
+        fn voxel_grid_deterministic_order() {
+            let data = (0..1000)
+                .map(|i| [((i * 7919) % 1000) as f32, ((i * 104729) % 997) as f32])
+                .collect::<Vec<_>>();
+            let mut filter = VoxelGrid::with_data(&[10f32, 10.]);
+            let first = filter.filter(&data);
+            let second = filter.filter(&data);
+            assert_eq!(first, second);
+
+            let keys = filter.voxels().map(|(key, _)| key).collect::<Vec<_>>();
+            let mut sorted = keys.clone();
+            sorted.sort();
+            assert_eq!(keys, sorted);
+        }
+    }
+
+    mod approximate {
+        use super::*;
+
+        #[test]
+        fn approximate_voxel_grid_without_collision() {
+            let data = crate::common::grid::<3>(0..=19, 1., 0.5);
+            let mut filter = ApproximateVoxelGrid::with_data(&[4f32; 3]);
+            filter.set_histogram_size(1 << 16);
+            let out = filter.filter_instance(&data);
+
+            let mut exact = VoxelGrid::with_data(&[4f32; 3]);
+            let mut expected = exact.filter_instance(&data);
+
+            let mut out = out;
+            let cmp = |a: &[f32; 3], b: &[f32; 3]| a.partial_cmp(b).unwrap();
+            out.sort_by(cmp);
+            expected.sort_by(cmp);
+            assert_eq!(out.len(), 125);
+            out.iter().zip(expected.iter()).for_each(|(a, b)| {
+                (0..3).for_each(|i| assert_relative_eq!(a[i], b[i]));
+            });
+        }
+
+        #[test]
+        fn approximate_voxel_grid_small_table() {
+            let data = crate::common::grid::<3>(0..=19, 1., 0.5);
+            let mut filter = ApproximateVoxelGrid::with_data(&[4f32; 3]);
+            filter.set_histogram_size(8);
+            let out = filter.filter(&data);
+            // Evicted voxels are output more than once.
+            assert!(out.len() >= 125);
+            assert!(out.len() <= data.len());
+            assert_eq!(out, filter.filter(&data));
+        }
+    }
+
+    mod reduction {
+        use super::*;
+
+        fn data() -> Vec<[f32; 2]> {
+            // Two voxels of leaf 2: x in [0, 2) and x in [2, 4).
+            vec![
+                [0., 0.],
+                [1.5, 0.],
+                [0.2, 0.],
+                [2.1, 0.],
+                [3.9, 0.],
+                [3.0, 0.],
+            ]
+        }
+
+        fn sorted(mut v: Vec<[f32; 2]>) -> Vec<[f32; 2]> {
+            v.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap());
+            v
+        }
+
+        #[test]
+        fn voxel_grid_reduction() {
+            let data = data();
+            let mut filter = VoxelGrid::with_data(&[2f32, 2.]);
+
+            filter.set_reduction(VoxelReduction::NearestToCentroid);
+            let out = sorted(filter.filter_instance(&data));
+            assert_eq!(out, vec![[0.2, 0.], [3.0, 0.]]);
+
+            filter.set_reduction(VoxelReduction::First);
+            let mut ids = filter.filter(&data);
+            ids.sort();
+            assert_eq!(ids, vec![0, 3]);
+
+            filter.set_reduction(VoxelReduction::Median);
+            let out = sorted(filter.filter_instance(&data));
+            assert_eq!(out, vec![[0.2, 0.], [3.0, 0.]]);
+        }
+
+        #[test]
+        fn voxel_grid_voxels() {
+            let data = data();
+            let mut filter = VoxelGrid::with_data(&[2f32, 2.]);
+            let out = filter.filter_instance(&data);
+
+            let voxels = filter.voxels().collect::<Vec<_>>();
+            assert_eq!(voxels.len(), out.len());
+            voxels
+                .iter()
+                .zip(out.iter())
+                .for_each(|((coordinate, pts), p)| {
+                    assert_eq!(coordinate[1], 0);
+                    assert_eq!(pts.len(), 3);
+                    pts.iter().for_each(|&i| {
+                        assert_eq!((data[i][0] / 2.) as usize, coordinate[0]);
+                        assert_eq!((p[0] / 2.) as usize, coordinate[0]);
+                    });
+                });
+        }
+
+        #[test]
+        fn voxel_grid_aggregate() {
+            let data = data();
+            let normals = vec![
+                [1f32, 1., 0.],
+                [1., 1., 0.],
+                [1., 1., 0.],
+                [0., 0., 2.],
+                [0., 0., 1.],
+                [0., 0., 3.],
+            ];
+            let colors = vec![
+                [1f32, 0., 0.],
+                [0., 1., 0.],
+                [0., 1., 0.],
+                [0., 0., 1.],
+                [1., 0., 0.],
+                [1., 0., 0.],
+            ];
+            let mut filter = VoxelGrid::with_data(&[2f32, 2.]);
+            filter.set_reduction(VoxelReduction::NearestToCentroid);
+            filter.filter(&data);
+
+            let first_voxel = filter
+                .voxels()
+                .map(|(_, pts)| pts[0] == 0)
+                .collect::<Vec<_>>();
+
+            let normals = filter.aggregate(&data, &normals, AttributeReduction::Normal);
+            let colors = filter.aggregate(&data, &colors, AttributeReduction::Mode);
+            let intensities = filter.aggregate(
+                &data,
+                &[[1f32], [2.], [3.], [4.], [5.], [6.]],
+                AttributeReduction::Mean,
+            );
+            let represent = filter.aggregate(
+                &data,
+                &[[1f32], [2.], [3.], [4.], [5.], [6.]],
+                AttributeReduction::Representative,
+            );
+
+            first_voxel.iter().enumerate().for_each(|(i, &first)| {
+                if first {
+                    let v = 1. / 2f32.sqrt();
+                    assert_relative_eq!(normals[i][0], v);
+                    assert_relative_eq!(normals[i][1], v);
+                    assert_eq!(colors[i], [0., 1., 0.]);
+                    assert_relative_eq!(intensities[i][0], 2.);
+                    assert_eq!(represent[i], [3.]);
+                } else {
+                    assert_relative_eq!(normals[i][2], 1.);
+                    assert_eq!(colors[i], [1., 0., 0.]);
+                    assert_relative_eq!(intensities[i][0], 5.);
+                    assert_eq!(represent[i], [6.]);
+                }
+            });
+
+            // Attributes not the same length of data.
+            assert!(filter
+                .aggregate(&data, &[[1f32]; 5], AttributeReduction::Mean)
+                .is_empty());
+            // `NaN` does not panic.
+            let nan = [[f32::NAN]; 6];
+            assert_eq!(filter.aggregate(&data, &nan, AttributeReduction::Mode).len(), 2);
+        }
+    }
 }
@@ -1,14 +1,19 @@
 mod kd_features;
 mod kd_leaf;
+mod kd_metric;
 pub use kd_features::KdFeature;
 pub use kd_leaf::KdLeaf;
+pub use kd_metric::*;
 
 #[cfg(all(feature = "pure", not(feature = "core")))]
 use crate::{
     serde::{self, Deserialize, Serialize},
     BasicFloat,
 };
-use crate::{SearchBy, TreeHeapElement, TreeKnnResult, TreeRadiusResult, TreeResult, TreeSearch};
+use crate::{
+    SearchBy, TreeHeapElement, TreeHybridResult, TreeKnnResult, TreeRadiusResult, TreeResult,
+    TreeSearch,
+};
 #[cfg(all(feature = "core", not(feature = "pure")))]
 use f3l_core::{
     rayon,
@@ -17,6 +22,41 @@ use f3l_core::{
 };
 use std::{cmp::Reverse, collections::BinaryHeap, ops::Index};
 
+/// Node and index of the last replaced bound on its path.
+type KdQueueNode<'a, T> = TreeHeapElement<(&'a Box<KdLeaf<T>>, Option<usize>), T>;
+
+/// Searching queue of nodes, ordered by lower bound of distance.
+///
+/// Lower bound sums bound of each axis once, bound of the same axis on a path is replaced.
+/// Each node keeps index of the last replaced bound on its path into `bounds`,
+/// which links to the previous one, instead of a vector of bounds per node.
+struct KdSearchQueue<'a, T: BasicFloat> {
+    heap: BinaryHeap<Reverse<KdQueueNode<'a, T>>>,
+    /// Axis, bound, and previous one on path.
+    bounds: Vec<(usize, T, Option<usize>)>,
+}
+
+impl<'a, T: BasicFloat> KdSearchQueue<'a, T> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: BinaryHeap::with_capacity(capacity),
+            bounds: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Bound of `axis` included in lower bound of `path`, `0` if none.
+    fn bound_of(&self, mut path: Option<usize>, axis: usize) -> T {
+        while let Some(i) = path {
+            let (a, bound, previous) = self.bounds[i];
+            if a == axis {
+                return bound;
+            }
+            path = previous;
+        }
+        T::zero()
+    }
+}
+
 /// KD-Tree Implement
 ///
 /// Use for any dimension of data.
@@ -28,6 +68,9 @@ use std::{cmp::Reverse, collections::BinaryHeap, ops::Index};
 /// * element type (like f32 or f64.. )
 /// * Dimension: usize
 ///
+/// Distance is [`Euclidean`] by default, use [`KdTree::with_metric`] to change.
+/// See [`KdMetric`].
+///
 /// # Examples
 /// ```
 /// use approx::assert_relative_eq;
@@ -46,16 +89,17 @@ use std::{cmp::Reverse, collections::BinaryHeap, ops::Index};
 /// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct KdTree<'a, T: BasicFloat, P>
+pub struct KdTree<'a, T: BasicFloat, P, M = Euclidean>
 where
     P: Index<usize, Output = T> + Clone + Copy,
 {
     pub dim: usize,
     pub ignores: Vec<usize>,
     pub enable_ignore: bool,
+    pub metric: M,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
-    pub root: Option<Box<KdLeaf>>,
+    pub root: Option<Box<KdLeaf<T>>>,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     pub data: Option<&'a [P]>,
@@ -66,25 +110,40 @@ where
     P: Index<usize, Output = T> + Clone + Copy + Send + Sync,
 {
     pub fn new(dim: usize) -> Self {
-        Self {
-            root: None,
-            dim,
-            data: None,
-            ignores: vec![],
-            enable_ignore: false,
-        }
+        Self::with_metric(dim, Euclidean)
     }
 
     pub fn with_data(dim: usize, data: &'a [P]) -> Self {
+        let mut tree = Self::new(dim);
+        tree.data = Some(data);
+        tree
+    }
+}
+
+impl<'a, T: BasicFloat, P, M: KdMetric<T>> KdTree<'a, T, P, M>
+where
+    P: Index<usize, Output = T> + Clone + Copy + Send + Sync,
+{
+    pub fn with_metric(dim: usize, metric: M) -> Self {
         Self {
             root: None,
             dim,
-            data: Some(data),
+            data: None,
             ignores: vec![],
             enable_ignore: false,
+            metric,
         }
     }
 
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    /// Change metric. Tree does not need to rebuild.
+    pub fn set_metric(&mut self, metric: M) {
+        self.metric = metric;
+    }
+
     pub fn clear(&mut self) {
         // self.data.clear();
         self.root = None;
@@ -102,8 +161,8 @@ where
         }
     }
 
-    fn build_recursive(&self, indices: &mut [usize], data: &[P]) -> Box<KdLeaf> {
-        let mut node = Box::<KdLeaf>::default();
+    fn build_recursive(&self, indices: &mut [usize], data: &[P]) -> Box<KdLeaf<T>> {
+        let mut node = Box::<KdLeaf<T>>::default();
         if indices.len() == 1 {
             node.feature = KdFeature::Leaf(indices[0]);
             return node;
@@ -122,9 +181,9 @@ where
         node
     }
 
-    fn mean_split(&self, indices: &mut [usize], data: &[P]) -> (KdFeature, usize) {
+    fn mean_split(&self, indices: &mut [usize], data: &[P]) -> (KdFeature<T>, usize) {
         // Compute mean value per dimension
-        let factor = T::from(1.0f32 / indices.len() as f32).unwrap();
+        let factor = T::one() / T::from(indices.len()).unwrap();
         let mut mean = vec![T::zero(); self.dim];
         indices.iter().for_each(|&i| {
             (0..self.dim).for_each(|j| {
@@ -164,10 +223,7 @@ where
             index = mid;
         }
 
-        (
-            KdFeature::Split((split_dim, split_val.to_f32().unwrap())),
-            index,
-        )
+        (KdFeature::Split((split_dim, split_val)), index)
     }
 
     fn plane_split(&self, indices: &mut [usize], split_dim: usize, split_val: T, data: &[P]) -> (usize, usize) {
@@ -207,9 +263,8 @@ where
         (lim1, left)
     }
 
-    pub fn search<R: TreeResult>(&self, data: P, by: SearchBy, result: &mut R) {
-        let mut search_queue =
-            BinaryHeap::with_capacity(30);
+    pub fn search<R: TreeResult<Distance = T>>(&self, data: P, by: SearchBy<T>, result: &mut R) {
+        let mut search_queue = KdSearchQueue::with_capacity(30);
 
         if self.root.is_none() {
             return;
@@ -220,25 +275,34 @@ where
                 root,
                 &data,
                 by,
-                if result.is_farthest() { f32::MAX } else { 0.0 },
+                (
+                    if result.is_farthest() {
+                        T::max_value()
+                    } else {
+                        T::zero()
+                    },
+                    None,
+                ),
                 &mut search_queue,
             );
 
-            while let Some(Reverse(node)) = search_queue.pop() {
-                self.search_(result, node.raw, &data, by, node.order, &mut search_queue)
+            while let Some(Reverse(node)) = search_queue.heap.pop() {
+                let (raw, path) = node.raw;
+                self.search_(result, raw, &data, by, (node.order, path), &mut search_queue)
             }
         };
     }
 
-    fn search_<R: TreeResult>(
+    fn search_<R: TreeResult<Distance = T>>(
         &self,
         result: &mut R,
-        node: &'a KdLeaf,
+        node: &'a KdLeaf<T>,
         data: &P,
-        by: SearchBy,
-        min_dist: f32,
+        by: SearchBy<T>,
+        // Lower bound of distance, and last replaced bound on path, see `KdSearchQueue`.
+        (min_dist, path): (T, Option<usize>),
         // queue: &mut BinaryHeap<SearchQueue<TreeHeapElement<&'a Box<KdLeaf>, f32>>>,
-        queue: &mut BinaryHeap<Reverse<TreeHeapElement<&'a Box<KdLeaf>, f32>>>,
+        queue: &mut KdSearchQueue<'a, T>,
     ) {
         let is_farthest = result.is_farthest();
         if match is_farthest {
@@ -254,18 +318,20 @@ where
         let far;
 
         let d: T;
+        let axis: usize;
         match node.feature {
             KdFeature::Leaf(leaf) => {
                 if self.enable_ignore && self.ignores.contains(&leaf) {
                     return;
                 }
-                let dist = distance(self.data.unwrap()[leaf], *p, self.dim);
-                result.add(leaf, dist.to_f32().unwrap());
+                let dist = self.metric.distance(&self.data.unwrap()[leaf], p, self.dim);
+                result.add(leaf, dist);
                 return;
             }
             KdFeature::Split((sp_dim, sp_val)) => {
-                d = p[sp_dim] - T::from(sp_val).unwrap();
-                if d < T::zero() {
+                axis = sp_dim;
+                d = self.metric.bound(p[sp_dim], sp_val, sp_dim);
+                if p[sp_dim] < sp_val {
                     near = &node.left;
                     far = &node.right;
                 } else {
@@ -287,41 +353,37 @@ where
                         true
                     } else {
                         match is_farthest {
-                            true => d * d > T::from(result.worst() + f32::EPSILON).unwrap(),
-                            false => d * d < T::from(result.worst() + f32::EPSILON).unwrap(),
+                            true => d > result.worst() + T::epsilon(),
+                            false => d < result.worst() + T::epsilon(),
                         }
                     }
                 }
-                SearchBy::Radius(r) => d * d <= T::from(r).unwrap(),
+                SearchBy::Radius(r) => d <= r,
+                SearchBy::Hybrid { .. } => d <= result.worst(),
             };
             if add_far {
+                // Bound of the same axis on the path is replaced, not summed.
+                let previous = queue.bound_of(path, axis);
+                queue.bounds.push((axis, d, path));
                 let node = TreeHeapElement {
-                    raw: far,
-                    order: min_dist + (d * d).to_f32().unwrap(),
+                    raw: (far, Some(queue.bounds.len() - 1)),
+                    order: self.metric.accumulate(min_dist, previous, d),
                 };
-                queue.push(Reverse(node));
+                queue.heap.push(Reverse(node));
             }
         }
 
         if let Some(near) = near {
-            self.search_(result, near, data, by, min_dist, queue);
+            self.search_(result, near, data, by, (min_dist, path), queue);
         }
     }
 }
 
-#[inline]
-fn distance<T: BasicFloat, P>(a: P, b: P, dim: usize) -> T
-where
-    P: Index<usize, Output = T> + Copy,
-{
-    (0..dim).fold(T::zero(), |acc, i| acc + (a[i] - b[i]).powi(2))
-}
-
-impl<'a, T: BasicFloat, P> TreeSearch<P> for KdTree<'a, T, P>
+impl<'a, T: BasicFloat, P, M: KdMetric<T>> TreeSearch<P, T> for KdTree<'a, T, P, M>
 where
     P: Send + Sync + Clone + Copy + Index<usize, Output = T>,
 {
-    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, f32)> {
+    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, T)> {
         if self.data.is_none() {
             return vec![];
         }
@@ -335,20 +397,35 @@ where
         result
             .result()
             .iter()
-            .map(|&(i, d)| (self.data.unwrap()[i], d.sqrt()))
-            .collect::<Vec<(P, f32)>>()
+            .map(|&(i, d)| (self.data.unwrap()[i], self.metric.to_distance(d)))
+            .collect::<Vec<(P, T)>>()
     }
 
-    fn search_radius(&self, point: &P, radius: f32) -> Vec<P> {
+    fn search_hybrid(&self, point: &P, k: usize, radius: T) -> Vec<(P, T)> {
         if self.data.is_none() {
             return vec![];
         }
-        let by = if radius == 0.0 {
+        let radius = self.metric.to_search_space(radius);
+        let by = SearchBy::Hybrid { k, radius };
+        let mut result = TreeHybridResult::new((k, radius));
+        self.search(*point, by, &mut result);
+        result
+            .result()
+            .iter()
+            .map(|&(i, d)| (self.data.unwrap()[i], self.metric.to_distance(d)))
+            .collect::<Vec<(P, T)>>()
+    }
+
+    fn search_radius(&self, point: &P, radius: T) -> Vec<P> {
+        if self.data.is_none() {
+            return vec![];
+        }
+        let by = if radius == T::zero() {
             SearchBy::Count(1)
         } else {
-            SearchBy::Radius(radius * radius)
+            SearchBy::Radius(self.metric.to_search_space(radius))
         };
-        let mut result = TreeRadiusResult::new(radius * radius);
+        let mut result = TreeRadiusResult::new(self.metric.to_search_space(radius));
         self.search(*point, by, &mut result);
         result.data.iter().map(|&i| self.data.unwrap()[i]).collect()
     }
@@ -364,13 +441,24 @@ where
         result.data.iter().map(|&(i, _)| i).collect()
     }
 
-    fn search_radius_ids(&self, point: &P, radius: f32) -> Vec<usize> {
-        let by = if radius == 0.0 {
+    fn search_hybrid_ids(&self, point: &P, k: usize, radius: T) -> Vec<usize> {
+        if self.data.is_none() {
+            return vec![];
+        }
+        let radius = self.metric.to_search_space(radius);
+        let by = SearchBy::Hybrid { k, radius };
+        let mut result = TreeHybridResult::new((k, radius));
+        self.search(*point, by, &mut result);
+        result.result().iter().map(|&(i, _)| i).collect()
+    }
+
+    fn search_radius_ids(&self, point: &P, radius: T) -> Vec<usize> {
+        let by = if radius == T::zero() {
             SearchBy::Count(1)
         } else {
-            SearchBy::Radius(radius * radius)
+            SearchBy::Radius(self.metric.to_search_space(radius))
         };
-        let mut result = TreeRadiusResult::new(radius * radius);
+        let mut result = TreeRadiusResult::new(self.metric.to_search_space(radius));
         self.search(*point, by, &mut result);
         result.data
     }
@@ -1,20 +1,26 @@
 #[cfg(all(feature = "pure", not(feature = "core")))]
-use crate::serde::{self, Deserialize, Serialize};
+use crate::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 #[cfg(all(feature = "core", not(feature = "pure")))]
-use f3l_core::serde::{self, Deserialize, Serialize};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 
 /// KD Tree node type, all data store in `leaf` node
 #[derive(Clone, Debug, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub enum KdFeature {
-    /// Tree node
-    Split((usize, f32)),
+pub enum KdFeature<T> {
+    /// Tree node, split dimension and value
+    Split((usize, T)),
     /// Leaf node
     Leaf(usize),
 }
 
-impl Default for KdFeature {
+impl<T: BasicFloat> Default for KdFeature<T> {
     fn default() -> Self {
-        Self::Split((0, 0.0))
+        Self::Split((0, T::zero()))
     }
 }
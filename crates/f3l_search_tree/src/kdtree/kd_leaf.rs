@@ -1,15 +1,31 @@
 #[cfg(all(feature = "pure", not(feature = "core")))]
-use crate::serde::{self, Deserialize, Serialize};
+use crate::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 #[cfg(all(feature = "core", not(feature = "pure")))]
-use f3l_core::serde::{self, Deserialize, Serialize};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 
 use super::KdFeature;
 
 /// All KD nodes
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct KdLeaf {
-    pub left: Option<Box<KdLeaf>>,
-    pub right: Option<Box<KdLeaf>>,
-    pub feature: KdFeature,
+pub struct KdLeaf<T: BasicFloat> {
+    pub left: Option<Box<KdLeaf<T>>>,
+    pub right: Option<Box<KdLeaf<T>>>,
+    pub feature: KdFeature<T>,
+}
+
+impl<T: BasicFloat> Default for KdLeaf<T> {
+    fn default() -> Self {
+        Self {
+            left: None,
+            right: None,
+            feature: KdFeature::default(),
+        }
+    }
 }
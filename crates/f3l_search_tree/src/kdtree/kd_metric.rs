@@ -0,0 +1,261 @@
+#[cfg(all(feature = "pure", not(feature = "core")))]
+use crate::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+#[cfg(all(feature = "core", not(feature = "pure")))]
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use std::ops::Index;
+
+/// Distance used by [`crate::KdTree`].
+///
+/// Distances are compared in a `searching space`, which could differ from the
+/// real distance (like squared Euclidean), to avoid `sqrt` in searching.
+/// Use [`KdMetric::to_distance`] and [`KdMetric::to_search_space`] to convert.
+pub trait KdMetric<T: BasicFloat>: Clone + Send + Sync {
+    /// Distance of first `dim` components in searching space.
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>;
+    /// Lower bound of distance from `query` to any point on the other side of
+    /// `split` at `axis`, in searching space.
+    fn bound(&self, query: T, split: T, axis: usize) -> T;
+    /// Lower bound of far side of current split, from lower bound of parent node.
+    /// `previous` is bound of the same `axis` already included in `lower`,
+    /// which is replaced by `bound` of current split.
+    #[inline]
+    fn accumulate(&self, lower: T, previous: T, bound: T) -> T {
+        lower - previous + bound
+    }
+    /// Searching space to real distance.
+    fn to_distance(&self, d: T) -> T;
+    /// Real distance to searching space.
+    fn to_search_space(&self, d: T) -> T;
+}
+
+/// Squared L2, default metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Euclidean;
+
+impl<T: BasicFloat> KdMetric<T> for Euclidean {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| acc + (a[i] - b[i]).powi(2))
+    }
+
+    #[inline]
+    fn bound(&self, query: T, split: T, _axis: usize) -> T {
+        (query - split).powi(2)
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        d.sqrt()
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d * d
+    }
+}
+
+/// L1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Manhattan;
+
+impl<T: BasicFloat> KdMetric<T> for Manhattan {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| acc + (a[i] - b[i]).abs())
+    }
+
+    #[inline]
+    fn bound(&self, query: T, split: T, _axis: usize) -> T {
+        (query - split).abs()
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        d
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d
+    }
+}
+
+/// L-infinity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Chebyshev;
+
+impl<T: BasicFloat> KdMetric<T> for Chebyshev {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| acc.max((a[i] - b[i]).abs()))
+    }
+
+    #[inline]
+    fn bound(&self, query: T, split: T, _axis: usize) -> T {
+        (query - split).abs()
+    }
+
+    #[inline]
+    fn accumulate(&self, lower: T, _previous: T, bound: T) -> T {
+        lower.max(bound)
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        d
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d
+    }
+}
+
+/// Chi-Squared, `sum((a - b)^2 / (a + b))`.
+///
+/// For non-negative data like histograms. Components with `a + b == 0` are skipped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ChiSquared;
+
+impl<T: BasicFloat> KdMetric<T> for ChiSquared {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| {
+            let sum = a[i] + b[i];
+            if sum > T::zero() {
+                acc + (a[i] - b[i]).powi(2) / sum
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// `(q - b)^2 / (q + b)` is monotonic as `b` moves away from `q`,
+    /// so the nearest value is `split`.
+    #[inline]
+    fn bound(&self, query: T, split: T, _axis: usize) -> T {
+        let sum = query + split;
+        if sum > T::zero() {
+            (query - split).powi(2) / sum
+        } else {
+            T::zero()
+        }
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        d
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d
+    }
+}
+
+/// Hellinger, `sqrt(sum((sqrt(a) - sqrt(b))^2) / 2)`.
+///
+/// For non-negative data like histograms. Searching space is `sum((sqrt(a) - sqrt(b))^2)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Hellinger;
+
+impl<T: BasicFloat> KdMetric<T> for Hellinger {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| {
+            acc + (a[i].max(T::zero()).sqrt() - b[i].max(T::zero()).sqrt()).powi(2)
+        })
+    }
+
+    #[inline]
+    fn bound(&self, query: T, split: T, _axis: usize) -> T {
+        (query.max(T::zero()).sqrt() - split.max(T::zero()).sqrt()).powi(2)
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        (d / T::from(2).unwrap()).sqrt()
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d * d * T::from(2).unwrap()
+    }
+}
+
+/// Squared L2 with weight per dimension, `sum(w * (a - b)^2)`.
+///
+/// Dimensions without weight use `1`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct WeightedEuclidean<T: BasicFloat> {
+    pub weights: Vec<T>,
+}
+
+impl<T: BasicFloat> WeightedEuclidean<T> {
+    pub fn new(weights: &[T]) -> Self {
+        Self {
+            weights: weights.to_vec(),
+        }
+    }
+
+    #[inline]
+    fn weight(&self, axis: usize) -> T {
+        self.weights.get(axis).copied().unwrap_or(T::one())
+    }
+}
+
+impl<T: BasicFloat> KdMetric<T> for WeightedEuclidean<T> {
+    #[inline]
+    fn distance<P>(&self, a: &P, b: &P, dim: usize) -> T
+    where
+        P: Index<usize, Output = T>,
+    {
+        (0..dim).fold(T::zero(), |acc, i| {
+            acc + self.weight(i) * (a[i] - b[i]).powi(2)
+        })
+    }
+
+    #[inline]
+    fn bound(&self, query: T, split: T, axis: usize) -> T {
+        self.weight(axis) * (query - split).powi(2)
+    }
+
+    #[inline]
+    fn to_distance(&self, d: T) -> T {
+        d.sqrt()
+    }
+
+    #[inline]
+    fn to_search_space(&self, d: T) -> T {
+        d * d
+    }
+}
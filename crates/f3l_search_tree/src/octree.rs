@@ -1,15 +1,23 @@
+mod oc_change_detector;
 mod oc_features;
+mod oc_key;
 mod oc_leaf;
+mod oc_voxel;
 
 #[cfg(all(feature = "pure", not(feature = "core")))]
 use crate::{get_minmax, BasicFloat};
 #[cfg(all(feature = "core", not(feature = "pure")))]
 use f3l_core::{get_minmax, serde, BasicFloat};
+pub use oc_change_detector::*;
 pub use oc_features::*;
+pub use oc_key::*;
 pub use oc_leaf::*;
 use std::{cmp::Reverse, collections::BinaryHeap, ops::Index};
 
-use crate::{SearchBy, TreeHeapElement, TreeKnnResult, TreeRadiusResult, TreeResult, TreeSearch};
+use crate::{
+    SearchBy, TreeHeapElement, TreeHybridResult, TreeKnnResult, TreeRadiusResult, TreeResult,
+    TreeSearch,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +27,8 @@ where
     P: Index<usize, Output = T> + Clone + Copy + Serialize,
 {
     pub bounds: Option<(P, P)>,
+    /// Use these bounds instead of computing from data. See [`OcTree::set_bounds`].
+    pub fixed_bounds: Option<(P, P)>,
     pub max_points: usize,
     pub depth: usize,
     pub ignores: Vec<usize>,
@@ -40,6 +50,7 @@ where
     pub fn new(max_points: usize, depth: usize) -> Self {
         Self {
             bounds: None,
+            fixed_bounds: None,
             data: None,
             max_points,
             depth,
@@ -52,6 +63,7 @@ where
     pub fn with_data(data: &'a [P], max_points: usize, depth: usize) -> Self {
         Self {
             bounds: None,
+            fixed_bounds: None,
             data: Some(data),
             max_points,
             depth,
@@ -73,8 +85,18 @@ where
         }
     }
 
+    /// Fix bounds of tree, points outside would be skipped.
+    ///
+    /// Trees with same bounds and depth have same voxels, see [`OcChangeDetector`].
+    pub fn set_bounds(&mut self, lower: P, upper: P) {
+        self.fixed_bounds = Some((lower, upper));
+    }
+
     fn compute_bounds(&mut self, data: &[P]) {
-        let bdx = get_minmax(data);
+        let bdx = match self.fixed_bounds {
+            Some(bdx) => bdx,
+            None => get_minmax(data),
+        };
         self.bounds = Some(bdx);
     }
 
@@ -92,6 +114,9 @@ where
             self.nodes.push(root);
 
             (0..data.len()).for_each(|i| {
+                if self.fixed_bounds.is_some() && !self.nodes[0].is_inside(data[i]) {
+                    return;
+                }
                 self.insert(i, 0, 0, data);
             });
         }
@@ -231,7 +256,7 @@ impl<'a, T: BasicFloat, P> OcTree<'a, T, P>
 where
     P: Into<[T; 3]> + Index<usize, Output = T> + Clone + Copy + Serialize,
 {
-    pub fn search<R: TreeResult>(&self, point: P, by: SearchBy, result: &mut R) {
+    pub fn search<R: TreeResult<Distance = T>>(&self, point: P, by: SearchBy<T>, result: &mut R) {
         let data = if let Some(data) = self.data {
             data
         } else {
@@ -240,20 +265,20 @@ where
         let mut search_queue =
             BinaryHeap::with_capacity(std::cmp::max(10, (data.len() as f32).sqrt() as usize));
 
-        self.search_(result, 0, &point, by, 0.0, &mut search_queue);
+        self.search_(result, 0, &point, by, T::zero(), &mut search_queue);
         while let Some(Reverse(node)) = search_queue.pop() {
             self.search_(result, node.raw, &point, by, node.order, &mut search_queue);
         }
     }
 
-    fn search_<R: TreeResult>(
+    fn search_<R: TreeResult<Distance = T>>(
         &self,
         result: &mut R,
         node: usize,
         data: &P,
-        by: SearchBy,
-        min_dist: f32,
-        queue: &mut BinaryHeap<Reverse<TreeHeapElement<usize, f32>>>,
+        by: SearchBy<T>,
+        min_dist: T,
+        queue: &mut BinaryHeap<Reverse<TreeHeapElement<usize, T>>>,
     ) {
         if result.worst() < min_dist {
             return;
@@ -291,39 +316,39 @@ where
                         true
                     } else {
                         match distance_type {
-                            OcDistance::Outside(d) => (d * d).to_f32().unwrap() < result.worst(),
+                            OcDistance::Outside(d) => d < result.worst(),
                             _ => true,
                         }
                     }
                 }
                 SearchBy::Radius(r) => match distance_type {
-                    OcDistance::Outside(d) => d.to_f32().unwrap() < r,
+                    OcDistance::Outside(d) => d < r,
+                    _ => true,
+                },
+                SearchBy::Hybrid { .. } => match distance_type {
+                    OcDistance::Outside(d) => d <= result.worst(),
                     _ => true,
                 },
             };
             if add_other {
+                // `OcLeaf::distance` is already squared.
                 let d = match distance_type {
-                    OcDistance::Outside(d) => d * d,
+                    OcDistance::Outside(d) => d,
                     OcDistance::Inside => T::zero(),
                 };
-                queue.push(Reverse(TreeHeapElement {
-                    raw: i,
-                    order: d.to_f32().unwrap(),
-                }));
+                queue.push(Reverse(TreeHeapElement { raw: i, order: d }));
             }
         });
 
         self.search_(result, first, data, by, min_dist, queue);
     }
 
-    fn distance_square(a: P, b: P) -> f32 {
-        (0..3).fold(0f32, |acc, i| {
-            acc + ((b[i] - a[i]).powi(2)).to_f32().unwrap()
-        })
+    fn distance_square(a: P, b: P) -> T {
+        (0..3).fold(T::zero(), |acc, i| acc + (b[i] - a[i]).powi(2))
     }
 }
 
-impl<'a, T: BasicFloat, P> TreeSearch<P> for OcTree<'a, T, P>
+impl<'a, T: BasicFloat, P> TreeSearch<P, T> for OcTree<'a, T, P>
 where
     P: Into<[T; 3]> + Index<usize, Output = T> + Clone + Copy + Serialize,
 {
@@ -341,11 +366,24 @@ where
         result.data.iter().map(|&(i, _)| i).collect()
     }
 
-    fn search_radius_ids(&self, point: &P, radius: f32) -> Vec<usize> {
+    fn search_hybrid_ids(&self, point: &P, k: usize, radius: T) -> Vec<usize> {
         if self.data.is_none() {
             return vec![];
         }
-        let by = if radius == 0.0 {
+        let by = SearchBy::Hybrid {
+            k,
+            radius: radius * radius,
+        };
+        let mut result = TreeHybridResult::new((k, radius * radius));
+        self.search(*point, by, &mut result);
+        result.result().iter().map(|&(i, _)| i).collect()
+    }
+
+    fn search_radius_ids(&self, point: &P, radius: T) -> Vec<usize> {
+        if self.data.is_none() {
+            return vec![];
+        }
+        let by = if radius == T::zero() {
             SearchBy::Count(1)
         } else {
             SearchBy::Radius(radius * radius)
@@ -355,7 +393,7 @@ where
         result.data
     }
 
-    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, f32)> {
+    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, T)> {
         if self.data.is_none() {
             return vec![];
         }
@@ -370,14 +408,31 @@ where
             .result()
             .iter()
             .map(|&(i, d)| (self.data.unwrap()[i], d.sqrt()))
-            .collect::<Vec<(P, f32)>>()
+            .collect::<Vec<(P, T)>>()
+    }
+
+    fn search_hybrid(&self, point: &P, k: usize, radius: T) -> Vec<(P, T)> {
+        if self.data.is_none() {
+            return vec![];
+        }
+        let by = SearchBy::Hybrid {
+            k,
+            radius: radius * radius,
+        };
+        let mut result = TreeHybridResult::new((k, radius * radius));
+        self.search(*point, by, &mut result);
+        result
+            .result()
+            .iter()
+            .map(|&(i, d)| (self.data.unwrap()[i], d.sqrt()))
+            .collect::<Vec<(P, T)>>()
     }
 
-    fn search_radius(&self, point: &P, radius: f32) -> Vec<P> {
+    fn search_radius(&self, point: &P, radius: T) -> Vec<P> {
         if self.data.is_none() {
             return vec![];
         }
-        let by = if radius == 0.0 {
+        let by = if radius == T::zero() {
             SearchBy::Count(1)
         } else {
             SearchBy::Radius(radius * radius)
@@ -0,0 +1,196 @@
+#[cfg(all(feature = "pure", not(feature = "core")))]
+use crate::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+#[cfg(all(feature = "core", not(feature = "pure")))]
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use std::ops::Index;
+
+use crate::{OcFeature, OcKey, OcTree, OcVoxel};
+
+/// Detect changes between two scans, like `OctreePointCloudChangeDetector` of PCL.
+///
+/// Double buffered: each [`OcChangeDetector::set_input`] moves the current tree to
+/// previous buffer, then builds a new one with the same bounds and depth.
+/// Voxels are compared by walking structure of both trees together.
+///
+/// # Examples
+/// ```
+/// let mut detector = OcChangeDetector::new(lower, upper, 8);
+/// detector.set_input(&scan_a);
+/// detector.set_input(&scan_b);
+/// // Indices of `scan_b` in voxels which are empty in `scan_a`.
+/// let new_points = detector.new_points();
+/// // Indices of `scan_a` in voxels which are empty in `scan_b`.
+/// let removed_points = detector.removed_points();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct OcChangeDetector<'a, T: BasicFloat, P>
+where
+    P: Index<usize, Output = T> + Clone + Copy + Serialize,
+{
+    /// Lower bound of both trees.
+    pub lower: P,
+    /// Upper bound of both trees.
+    pub upper: P,
+    /// Depth of voxels to compare.
+    pub depth: usize,
+    /// Minimum number of points of a changed voxel to report. Default: 1.
+    pub min_points: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    previous: Option<OcTree<'a, T, P>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    current: Option<OcTree<'a, T, P>>,
+}
+
+impl<'a, T: BasicFloat, P> OcChangeDetector<'a, T, P>
+where
+    P: Into<[T; 3]> + Index<usize, Output = T> + Clone + Copy + Serialize,
+    [T; 3]: Into<P>,
+{
+    pub fn new(lower: P, upper: P, depth: usize) -> Self {
+        Self {
+            lower,
+            upper,
+            depth,
+            min_points: 1,
+            previous: None,
+            current: None,
+        }
+    }
+
+    pub fn set_min_points(&mut self, min_points: usize) {
+        self.min_points = min_points;
+    }
+
+    /// Move current tree to previous buffer.
+    pub fn switch_buffers(&mut self) {
+        self.previous = self.current.take();
+    }
+
+    /// Switch buffers, then build current tree of `data`.
+    pub fn set_input(&mut self, data: &'a [P]) {
+        self.switch_buffers();
+        // `max_points = 0` splits all non-empty nodes to `depth`,
+        // so both trees have the same structure for same voxels.
+        let mut tree = OcTree::new(0, self.depth);
+        tree.set_data(data);
+        tree.set_bounds(self.lower, self.upper);
+        tree.build();
+        self.current = Some(tree);
+    }
+
+    pub fn previous(&self) -> Option<&OcTree<'a, T, P>> {
+        self.previous.as_ref()
+    }
+
+    pub fn current(&self) -> Option<&OcTree<'a, T, P>> {
+        self.current.as_ref()
+    }
+}
+
+impl<'a, T: BasicFloat, P> OcChangeDetector<'a, T, P>
+where
+    P: Into<[T; 3]> + Index<usize, Output = T> + Clone + Copy + Serialize,
+{
+    /// Voxels occupied in current but empty in previous. Indices of current data.
+    pub fn new_voxels(&self) -> Vec<OcVoxel> {
+        match &self.current {
+            Some(current) => self.difference(self.previous.as_ref(), current),
+            None => vec![],
+        }
+    }
+
+    /// Voxels occupied in previous but empty in current. Indices of previous data.
+    pub fn removed_voxels(&self) -> Vec<OcVoxel> {
+        match &self.previous {
+            Some(previous) => self.difference(self.current.as_ref(), previous),
+            None => vec![],
+        }
+    }
+
+    /// Indices of current data in new voxels.
+    pub fn new_points(&self) -> Vec<usize> {
+        Self::flatten(self.new_voxels())
+    }
+
+    /// Indices of previous data in removed voxels.
+    pub fn removed_points(&self) -> Vec<usize> {
+        Self::flatten(self.removed_voxels())
+    }
+
+    fn flatten(voxels: Vec<OcVoxel>) -> Vec<usize> {
+        let mut points = voxels
+            .into_iter()
+            .flat_map(|voxel| voxel.points)
+            .collect::<Vec<_>>();
+        points.sort();
+        points
+    }
+
+    /// Voxels occupied in `to` but empty in `from`.
+    fn difference(&self, from: Option<&OcTree<'a, T, P>>, to: &OcTree<'a, T, P>) -> Vec<OcVoxel> {
+        let mut out = vec![];
+        if to.nodes.is_empty() {
+            return out;
+        }
+        let from = from.and_then(|tree| {
+            if tree.nodes.is_empty() {
+                None
+            } else {
+                Some((tree, 0))
+            }
+        });
+        self.difference_(from, (to, 0), OcKey::root(), &mut out);
+        out
+    }
+
+    fn difference_(
+        &self,
+        from: Option<(&OcTree<'a, T, P>, usize)>,
+        to: (&OcTree<'a, T, P>, usize),
+        key: OcKey,
+        out: &mut Vec<OcVoxel>,
+    ) {
+        let to_node = &to.0.nodes[to.1];
+        let from_node = from.map(|(tree, i)| &tree.nodes[i]);
+        match to_node.feature {
+            OcFeature::Split(children) => {
+                children.iter().enumerate().for_each(|(id, &child)| {
+                    let from_child = match (from, from_node.map(|n| n.feature)) {
+                        (Some((tree, _)), Some(OcFeature::Split(from_children))) => {
+                            Some((tree, from_children[id]))
+                        }
+                        _ => None,
+                    };
+                    self.difference_(from_child, (to.0, child), key.child(id), out);
+                });
+            }
+            OcFeature::Leaf => {
+                if to_node.points.is_empty() || to_node.points.len() < self.min_points {
+                    return;
+                }
+                let from_empty = match from_node {
+                    None => true,
+                    Some(node) => match node.feature {
+                        OcFeature::Leaf => node.points.is_empty(),
+                        OcFeature::Split(_) => false,
+                    },
+                };
+                if from_empty {
+                    out.push(OcVoxel {
+                        key,
+                        points: to_node.points.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(all(feature = "pure", not(feature = "core")))]
+use crate::serde::{self, Deserialize, Serialize};
+#[cfg(all(feature = "core", not(feature = "pure")))]
+use f3l_core::serde::{self, Deserialize, Serialize};
+
+/// Integer coordinate of a voxel at `depth`.
+///
+/// Each component is in `[0, 2^depth)`, root is `depth = 0` and `key = [0, 0, 0]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct OcKey {
+    pub depth: usize,
+    pub key: [usize; 3],
+}
+
+/// Connectivity of voxel neighbors.
+/// * Face  : 6 neighbors share a face.
+/// * Edge  : 18 neighbors share a face or an edge.
+/// * Vertex: 26 neighbors share a face, an edge or a vertex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum OcConnectivity {
+    Face,
+    Edge,
+    Vertex,
+}
+
+impl OcConnectivity {
+    /// Maximum number of non-zero offsets of a neighbor.
+    #[inline]
+    fn max_offsets(&self) -> usize {
+        match self {
+            OcConnectivity::Face => 1,
+            OcConnectivity::Edge => 2,
+            OcConnectivity::Vertex => 3,
+        }
+    }
+}
+
+/// An occupied voxel and indices of points inside.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct OcVoxel {
+    pub key: OcKey,
+    pub points: Vec<usize>,
+}
+
+impl OcKey {
+    pub fn root() -> Self {
+        Self {
+            depth: 0,
+            key: [0, 0, 0],
+        }
+    }
+
+    /// Number of voxels per axis at this depth.
+    #[inline]
+    pub fn resolution(&self) -> usize {
+        1 << self.depth
+    }
+
+    /// Key of child, `id` is the same as [`crate::OcLeaf::locate_at`].
+    #[inline]
+    pub fn child(&self, id: usize) -> Self {
+        let mut key = self.key;
+        (0..3).for_each(|i| {
+            key[i] = key[i] * 2 + ((id >> i) & 1);
+        });
+        Self {
+            depth: self.depth + 1,
+            key,
+        }
+    }
+
+    /// Key of parent, `None` if this is root.
+    #[inline]
+    pub fn parent(&self) -> Option<Self> {
+        if self.depth == 0 {
+            return None;
+        }
+        Some(Self {
+            depth: self.depth - 1,
+            key: [self.key[0] / 2, self.key[1] / 2, self.key[2] / 2],
+        })
+    }
+
+    /// Child id of ancestor at `level` to reach this key. See [`crate::OcLeaf::locate_at`].
+    #[inline]
+    pub fn child_id_at(&self, level: usize) -> usize {
+        let shift = self.depth - level - 1;
+        (0..3).fold(0, |acc, i| acc + ((self.key[i] >> shift) & 1) * (1 << i))
+    }
+
+    /// Neighbors inside bounds with same depth, whether occupied or not.
+    pub fn neighbors(&self, connectivity: OcConnectivity) -> Vec<OcKey> {
+        let max = self.resolution() as i64;
+        let mut out = Vec::with_capacity(26);
+        for dz in -1..=1_i64 {
+            for dy in -1..=1_i64 {
+                for dx in -1..=1_i64 {
+                    let offset = [dx, dy, dz];
+                    let nb_offsets = offset.iter().filter(|&&o| o != 0).count();
+                    if nb_offsets == 0 || nb_offsets > connectivity.max_offsets() {
+                        continue;
+                    }
+                    let mut key = [0_usize; 3];
+                    let inside = (0..3).all(|i| {
+                        let k = self.key[i] as i64 + offset[i];
+                        key[i] = k.max(0) as usize;
+                        k >= 0 && k < max
+                    });
+                    if inside {
+                        out.push(OcKey {
+                            depth: self.depth,
+                            key,
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[test]
+fn oc_key_child_parent() {
+    let root = OcKey::root();
+    (0..8).for_each(|id| {
+        let child = root.child(id);
+        assert_eq!(child.child_id_at(0), id);
+        assert_eq!(child.parent(), Some(root));
+    });
+    let key = root.child(5).child(3);
+    assert_eq!(key.key, [3, 1, 2]);
+    assert_eq!(key.child_id_at(0), 5);
+    assert_eq!(key.child_id_at(1), 3);
+}
+
+#[test]
+fn oc_key_neighbors() {
+    let center = OcKey {
+        depth: 2,
+        key: [1, 1, 1],
+    };
+    assert_eq!(center.neighbors(OcConnectivity::Face).len(), 6);
+    assert_eq!(center.neighbors(OcConnectivity::Edge).len(), 18);
+    assert_eq!(center.neighbors(OcConnectivity::Vertex).len(), 26);
+
+    let corner = OcKey {
+        depth: 2,
+        key: [0, 0, 0],
+    };
+    assert_eq!(corner.neighbors(OcConnectivity::Face).len(), 3);
+    assert_eq!(corner.neighbors(OcConnectivity::Vertex).len(), 7);
+}
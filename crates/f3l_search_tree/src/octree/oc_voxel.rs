@@ -0,0 +1,232 @@
+#[cfg(all(feature = "pure", not(feature = "core")))]
+use crate::{serde::Serialize, BasicFloat};
+#[cfg(all(feature = "core", not(feature = "pure")))]
+use f3l_core::{serde::Serialize, BasicFloat};
+use std::{collections::BTreeMap, ops::Index};
+
+use crate::{OcConnectivity, OcFeature, OcKey, OcTree, OcVoxel};
+
+// Voxel
+//
+// Voxels deeper than a `Leaf` node are split virtually by the middle of bounds,
+// the same as `OcLeaf::locate_at`, so the octree could be used as a spatial hash
+// at any depth.
+impl<'a, T: BasicFloat, P> OcTree<'a, T, P>
+where
+    P: Into<[T; 3]> + Index<usize, Output = T> + Clone + Copy + Serialize,
+{
+    /// Key of voxel at `depth` which contains `point`.
+    /// Return `None` if tree is not built or point is outside bounds.
+    pub fn key_at(&self, point: &P, depth: usize) -> Option<OcKey> {
+        if self.nodes.is_empty() || !self.nodes[0].is_inside(*point) {
+            return None;
+        }
+        let mut i_node = 0;
+        let mut key = OcKey::root();
+        while key.depth < depth {
+            let node = &self.nodes[i_node];
+            match node.feature {
+                OcFeature::Split(children) => {
+                    let id = node.locate_at(*point).unwrap();
+                    i_node = children[id];
+                    key = key.child(id);
+                }
+                OcFeature::Leaf => {
+                    return Some(Self::descend_virtual(
+                        point, node.lower, node.upper, key, depth,
+                    ));
+                }
+            }
+        }
+        Some(key)
+    }
+
+    /// Lower and upper bounds of voxel.
+    /// Return `None` if tree is not built or key is out of range.
+    pub fn voxel_bounds(&self, key: &OcKey) -> Option<([T; 3], [T; 3])> {
+        if self.nodes.is_empty() || key.key.iter().any(|&k| k >= key.resolution()) {
+            return None;
+        }
+        let two = T::from(2).unwrap();
+        let mut i_node = Some(0);
+        let mut lower = self.nodes[0].lower;
+        let mut upper = self.nodes[0].upper;
+        (0..key.depth).for_each(|level| {
+            let id = key.child_id_at(level);
+            if let Some(OcFeature::Split(children)) = i_node.map(|i| self.nodes[i].feature) {
+                let child = &self.nodes[children[id]];
+                i_node = Some(children[id]);
+                lower = child.lower;
+                upper = child.upper;
+                return;
+            }
+            i_node = None;
+            (0..3).for_each(|i| {
+                let mid = (upper[i] + lower[i]) / two;
+                if (id >> i) & 1 == 0 {
+                    upper[i] = mid;
+                } else {
+                    lower[i] = mid;
+                }
+            });
+        });
+        Some((lower, upper))
+    }
+
+    /// Indices of points inside voxel.
+    pub fn points_in(&self, key: &OcKey) -> Vec<usize> {
+        let data = match self.data {
+            Some(data) if !self.nodes.is_empty() => data,
+            _ => return vec![],
+        };
+        if key.key.iter().any(|&k| k >= key.resolution()) {
+            return vec![];
+        }
+        let mut i_node = 0;
+        let mut current = OcKey::root();
+        while current.depth < key.depth {
+            let node = &self.nodes[i_node];
+            match node.feature {
+                OcFeature::Split(children) => {
+                    let id = key.child_id_at(current.depth);
+                    i_node = children[id];
+                    current = current.child(id);
+                }
+                OcFeature::Leaf => {
+                    return node
+                        .points
+                        .iter()
+                        .filter(|&&i| {
+                            Self::descend_virtual(
+                                &data[i], node.lower, node.upper, current, key.depth,
+                            ) == *key
+                        })
+                        .copied()
+                        .collect();
+                }
+            }
+        }
+        let mut points = vec![];
+        self.collect_points(i_node, &mut points);
+        points
+    }
+
+    /// Voxel contains any point or not.
+    #[inline]
+    pub fn is_occupied(&self, key: &OcKey) -> bool {
+        !self.points_in(key).is_empty()
+    }
+
+    /// Occupied neighbors of voxel with same depth.
+    pub fn occupied_neighbors(&self, key: &OcKey, connectivity: OcConnectivity) -> Vec<OcKey> {
+        key.neighbors(connectivity)
+            .into_iter()
+            .filter(|k| self.is_occupied(k))
+            .collect()
+    }
+
+    /// Iterate occupied voxels at `depth`, sorted by key.
+    pub fn voxels(&self, depth: usize) -> impl Iterator<Item = OcVoxel> {
+        let mut voxels = BTreeMap::new();
+        if let Some(data) = self.data {
+            if !self.nodes.is_empty() {
+                self.collect_voxels(0, OcKey::root(), depth, data, &mut voxels);
+            }
+        }
+        voxels
+            .into_iter()
+            .map(|(key, points)| OcVoxel { key, points })
+    }
+
+    /// Centroid of points per occupied voxel at `depth`, sorted by key.
+    pub fn centroids(&self, depth: usize) -> Vec<(OcKey, [T; 3])> {
+        let data = match self.data {
+            Some(data) => data,
+            None => return vec![],
+        };
+        self.voxels(depth)
+            .map(|voxel| {
+                let factor = T::one() / T::from(voxel.points.len()).unwrap();
+                let mut centroid = [T::zero(); 3];
+                voxel.points.iter().for_each(|&i| {
+                    (0..3).for_each(|j| centroid[j] += data[i][j] * factor);
+                });
+                (voxel.key, centroid)
+            })
+            .collect()
+    }
+
+    /// Level of detail pyramid, centroids of depth `0` to `max_depth`.
+    pub fn lod(&self, max_depth: usize) -> Vec<Vec<(OcKey, [T; 3])>> {
+        (0..=max_depth).map(|depth| self.centroids(depth)).collect()
+    }
+
+    fn collect_voxels(
+        &self,
+        i_node: usize,
+        key: OcKey,
+        depth: usize,
+        data: &[P],
+        voxels: &mut BTreeMap<OcKey, Vec<usize>>,
+    ) {
+        let node = &self.nodes[i_node];
+        if key.depth == depth {
+            let mut points = vec![];
+            self.collect_points(i_node, &mut points);
+            if !points.is_empty() {
+                voxels.entry(key).or_default().extend(points);
+            }
+            return;
+        }
+        match node.feature {
+            OcFeature::Split(children) => {
+                children.iter().enumerate().for_each(|(id, &child)| {
+                    self.collect_voxels(child, key.child(id), depth, data, voxels);
+                });
+            }
+            OcFeature::Leaf => {
+                node.points.iter().for_each(|&i| {
+                    let k = Self::descend_virtual(&data[i], node.lower, node.upper, key, depth);
+                    voxels.entry(k).or_default().push(i);
+                });
+            }
+        }
+    }
+
+    fn collect_points(&self, i_node: usize, points: &mut Vec<usize>) {
+        let node = &self.nodes[i_node];
+        match node.feature {
+            OcFeature::Split(children) => {
+                children
+                    .iter()
+                    .for_each(|&child| self.collect_points(child, points));
+            }
+            OcFeature::Leaf => points.extend_from_slice(&node.points),
+        }
+    }
+
+    /// Split bounds by middle until `depth`, from voxel `key` to deeper one.
+    fn descend_virtual(
+        point: &P,
+        mut lower: [T; 3],
+        mut upper: [T; 3],
+        mut key: OcKey,
+        depth: usize,
+    ) -> OcKey {
+        let two = T::from(2).unwrap();
+        while key.depth < depth {
+            let mut id = 0;
+            (0..3).for_each(|i| {
+                let mid = (upper[i] + lower[i]) / two;
+                if point[i] < mid {
+                    upper[i] = mid;
+                } else {
+                    lower[i] = mid;
+                    id += 1 << i;
+                }
+            });
+            key = key.child(id);
+        }
+        key
+    }
+}
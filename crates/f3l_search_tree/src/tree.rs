@@ -1,44 +1,63 @@
 #[cfg(all(feature = "pure", not(feature = "core")))]
-use crate::serde::{self, Deserialize, Serialize};
+use crate::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 #[cfg(all(feature = "core", not(feature = "pure")))]
-use f3l_core::serde::{self, Deserialize, Serialize};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
 
 /// Search Method
 /// * Count : KNN
 /// * Radius: Radius Search
+/// * Hybrid: KNN, but neighbors must be inside radius
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub enum SearchBy {
+pub enum SearchBy<T> {
     Count(usize),
-    Radius(f32),
+    Radius(T),
+    Hybrid { k: usize, radius: T },
 }
 
 #[test]
 fn test() {
-    let count = SearchBy::Count(1);
-    let radius = SearchBy::Radius(0.1);
+    let count = SearchBy::<f32>::Count(1);
+    let radius = SearchBy::Radius(0.1f32);
+    let hybrid = SearchBy::Hybrid {
+        k: 1,
+        radius: 0.1f64,
+    };
 
     println!("Count: {}", serde_json::to_string(&count).unwrap());
     println!("Radius: {}", serde_json::to_string(&radius).unwrap());
+    println!("Hybrid: {}", serde_json::to_string(&hybrid).unwrap());
 }
 
-/// Search `KNN` and `Radius`
-pub trait TreeSearch<P> {
+/// Search `KNN`, `Radius` and `Hybrid`
+pub trait TreeSearch<P, T: BasicFloat> {
     fn add_ignore(&mut self, idx: usize);
     fn add_ignores(&mut self, idx: &[usize]);
     fn set_ignore(&mut self, enable: bool);
 
     fn search_knn_ids(&self, point: &P, k: usize) -> Vec<usize>;
-    fn search_radius_ids(&self, point: &P, radius: f32) -> Vec<usize>;
-
-    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, f32)>;
-    fn search_radius(&self, point: &P, radius: f32) -> Vec<P>;
+    fn search_radius_ids(&self, point: &P, radius: T) -> Vec<usize>;
+    /// Nearest `k` ids inside `radius`, sorted by distance.
+    fn search_hybrid_ids(&self, point: &P, k: usize, radius: T) -> Vec<usize>;
+
+    fn search_knn(&self, point: &P, k: usize) -> Vec<(P, T)>;
+    fn search_radius(&self, point: &P, radius: T) -> Vec<P>;
+    /// Nearest `k` points inside `radius`, sorted by distance.
+    fn search_hybrid(&self, point: &P, k: usize, radius: T) -> Vec<(P, T)>;
 }
 
 /// Result of `KNN` and `Radius`
 pub trait TreeResult {
     type T;
     type Output;
+    /// Type of distance, same as element type of tree.
+    type Distance: BasicFloat;
     /// New with `k` neighbors of `KNN` or `radius` of `Radius Search`
     fn new(arg: Self::T) -> Self;
     /// Args and initialize the data capacity.
@@ -47,11 +66,11 @@ pub trait TreeResult {
     fn result(&self) -> Vec<Self::Output>;
 
     /// Add to result data
-    fn add(&mut self, data: usize, distance: f32);
+    fn add(&mut self, data: usize, distance: Self::Distance);
     /// Check data is full
     fn is_full(&self) -> bool;
     /// Get farthest point of data
-    fn worst(&self) -> f32;
+    fn worst(&self) -> Self::Distance;
     /// Clear data
     fn clear(&mut self);
     /// Search Farthest
@@ -61,35 +80,36 @@ pub trait TreeResult {
 /// KNN result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct TreeKnnResult {
+pub struct TreeKnnResult<T: BasicFloat> {
     /// KNN ids and distances.
-    pub data: Vec<(usize, f32)>,
+    pub data: Vec<(usize, T)>,
     /// Target of `K`.
     pub size: usize,
     /// Length of data.
     pub count: usize,
     /// Used in searching.
-    pub farthest: f32,
+    pub farthest: T,
     /// Is Search Farthest
     pub search_farthest: bool,
 }
 
-impl TreeKnnResult {
+impl<T: BasicFloat> TreeKnnResult<T> {
     pub fn set_search_farthest(&mut self, search_farthest: bool) {
         self.search_farthest = search_farthest;
     }
 }
 
-impl TreeResult for TreeKnnResult {
+impl<T: BasicFloat> TreeResult for TreeKnnResult<T> {
     type T = usize;
-    type Output = (usize, f32);
+    type Output = (usize, T);
+    type Distance = T;
 
     fn new(arg: Self::T) -> Self {
         Self {
             data: Vec::with_capacity(arg),
             size: arg,
             count: 0,
-            farthest: f32::MAX,
+            farthest: T::max_value(),
             search_farthest: false,
         }
     }
@@ -99,7 +119,7 @@ impl TreeResult for TreeKnnResult {
             data: Vec::with_capacity(arg),
             size: capacity,
             count: 0,
-            farthest: f32::MAX,
+            farthest: T::max_value(),
             search_farthest: false,
         }
     }
@@ -113,7 +133,7 @@ impl TreeResult for TreeKnnResult {
         queue
     }
 
-    fn add(&mut self, data: usize, distance: f32) {
+    fn add(&mut self, data: usize, distance: T) {
         let mut need_sort = false;
         if self.count < self.size {
             need_sort = true;
@@ -148,7 +168,7 @@ impl TreeResult for TreeKnnResult {
     }
 
     #[inline]
-    fn worst(&self) -> f32 {
+    fn worst(&self) -> T {
         self.farthest
     }
 
@@ -167,18 +187,18 @@ impl TreeResult for TreeKnnResult {
 /// Radius Search result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
-pub struct TreeRadiusResult {
+pub struct TreeRadiusResult<T: BasicFloat> {
     /// Neighbors in radius.
     pub data: Vec<usize>,
     /// Length of data
     pub count: usize,
     /// Target radius
-    pub radius: f32,
+    pub radius: T,
     /// `Optional`: full check when `count` more than `size`
     pub size: Option<usize>,
 }
 
-impl TreeRadiusResult {
+impl<T: BasicFloat> TreeRadiusResult<T> {
     pub fn set_to_maximum_size(self, maximum_size: usize) -> Self {
         let TreeRadiusResult {
             data,
@@ -203,9 +223,10 @@ impl TreeRadiusResult {
     }
 }
 
-impl TreeResult for TreeRadiusResult {
-    type T = f32;
+impl<T: BasicFloat> TreeResult for TreeRadiusResult<T> {
+    type T = T;
     type Output = usize;
+    type Distance = T;
 
     fn new(arg: Self::T) -> Self {
         Self {
@@ -229,7 +250,7 @@ impl TreeResult for TreeRadiusResult {
         self.data.clone()
     }
 
-    fn add(&mut self, data: usize, distance: f32) {
+    fn add(&mut self, data: usize, distance: T) {
         if distance > self.radius {
             return;
         }
@@ -246,7 +267,7 @@ impl TreeResult for TreeRadiusResult {
     }
 
     #[inline]
-    fn worst(&self) -> f32 {
+    fn worst(&self) -> T {
         self.radius
     }
 
@@ -260,3 +281,92 @@ impl TreeResult for TreeRadiusResult {
         false
     }
 }
+
+/// Hybrid Search result
+///
+/// Keep nearest `k` neighbors, but reject any farther than `radius`.
+/// Unlike [`TreeRadiusResult::set_maximum_size`], which stops at the first
+/// `size` neighbors in traversal order, this one returns the true nearest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct TreeHybridResult<T: BasicFloat> {
+    /// Neighbor ids and distances.
+    pub data: Vec<(usize, T)>,
+    /// Target of `K`.
+    pub size: usize,
+    /// Length of data.
+    pub count: usize,
+    /// Target radius
+    pub radius: T,
+    /// Used in searching, never larger than `radius`.
+    pub farthest: T,
+}
+
+impl<T: BasicFloat> TreeResult for TreeHybridResult<T> {
+    /// (`k`, `radius`)
+    type T = (usize, T);
+    type Output = (usize, T);
+    type Distance = T;
+
+    fn new(arg: Self::T) -> Self {
+        Self::with_capacity(arg, arg.0)
+    }
+
+    fn with_capacity(arg: Self::T, capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            size: arg.0,
+            count: 0,
+            radius: arg.1,
+            farthest: arg.1,
+        }
+    }
+
+    fn result(&self) -> Vec<Self::Output> {
+        let mut queue = self.data.clone();
+        queue.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        queue
+    }
+
+    fn add(&mut self, data: usize, distance: T) {
+        if distance > self.farthest || self.size == 0 {
+            return;
+        }
+        if self.count < self.size {
+            self.data.push((data, distance));
+            self.count += 1;
+            if self.count == self.size {
+                // Only sort when data is full
+                self.data.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                self.farthest = self.data.last().unwrap().1;
+            }
+        } else {
+            let idx = self.data.partition_point(|x| x.1 < distance);
+            self.data.insert(idx, (data, distance));
+            self.data.pop();
+            self.farthest = self.data.last().unwrap().1;
+        }
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.count >= self.size
+    }
+
+    #[inline]
+    fn worst(&self) -> T {
+        self.farthest
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.data.clear();
+        self.count = 0;
+        self.farthest = self.radius;
+    }
+
+    #[inline]
+    fn is_farthest(&self) -> bool {
+        false
+    }
+}
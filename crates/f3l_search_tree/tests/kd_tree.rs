@@ -1,3 +1,5 @@
+mod common;
+
 mod kd_tree {
     use approx::assert_relative_eq;
     #[cfg(all(feature = "core", not(feature = "pure")))]
@@ -164,6 +166,20 @@ mod kd_tree {
                 });
                 assert_relative_eq!(count, 0f32);
             }
+
+            #[test]
+            fn query_hybrid_1d() {
+                let data = (0..10).map(|i| [i as f32]).collect::<Vec<_>>();
+                let mut tree = KdTree::with_data(1, &data);
+                tree.build();
+                // nearest 3 of 3.1 to 7.1: 5, 6, 4
+                let result = tree.search_hybrid_ids(&[5.1f32], 3, 2f32);
+                assert_eq!(result, vec![5, 6, 4]);
+                // only 4 inside radius
+                let result = tree.search_hybrid(&[5.1f32], 6, 2f32);
+                assert_eq!(result.len(), 4);
+                assert_relative_eq!(result.last().unwrap().1, 1.9f32, epsilon = 1e-5);
+            }
         }
 
         #[cfg(all(feature = "core", not(feature = "pure")))]
@@ -229,4 +245,150 @@ mod kd_tree {
             }
         }
     }
+
+    mod precision {
+        use super::*;
+
+        #[test]
+        fn query_knn_f64_utm() {
+            // 1cm spacing, far from origin, not representable in f32.
+            let data = (0..100)
+                .map(|i| [500_000.0 + i as f64 * 0.01, 4_000_000.0, 100.0])
+                .collect::<Vec<_>>();
+            let mut tree = KdTree::with_data(3, &data);
+            tree.build();
+
+            let target = [500_000.503f64, 4_000_000.0, 100.0];
+            let result = tree.search_knn(&target, 2);
+            assert_relative_eq!(result[0].0[0], 500_000.50, epsilon = 1e-9);
+            assert_relative_eq!(result[0].1, 0.003, epsilon = 1e-6);
+            assert_relative_eq!(result[1].0[0], 500_000.51, epsilon = 1e-9);
+
+            let result = tree.search_radius_ids(&target, 0.015);
+            let mut result = result;
+            result.sort();
+            assert_eq!(result, vec![49, 50, 51]);
+        }
+    }
+
+    mod pruning {
+        use super::*;
+
+        fn distances(data: &[[f32; 3]], target: &[f32; 3]) -> Vec<f32> {
+            let mut d = data
+                .iter()
+                .map(|p| {
+                    (0..3)
+                        .fold(0f32, |acc, i| acc + (p[i] - target[i]).powi(2))
+                        .sqrt()
+                })
+                .collect::<Vec<_>>();
+            d.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            d
+        }
+
+        // Splits of the same axis on a path must not be summed into the lower bound.
+        #[test]
+        fn query_grid_same_as_brute_force() {
+            let data = crate::common::grid::<3>(0..=9, 1., 0.);
+            let mut tree = KdTree::with_data(3, &data);
+            tree.build();
+
+            data.iter().for_each(|target| {
+                let d = distances(&data, target);
+
+                let radius = tree.search_radius_ids(target, 2.5);
+                assert_eq!(radius.len(), d.iter().filter(|&&d| d <= 2.5).count());
+
+                let knn = tree.search_knn(target, 30);
+                assert_relative_eq!(knn.last().unwrap().1, d[29]);
+            });
+        }
+    }
+
+    mod metric {
+        use super::*;
+
+        fn histograms() -> Vec<[f32; 4]> {
+            (0..400)
+                .map(|i| {
+                    let i = i as f32;
+                    [
+                        (i * 0.37).sin().abs(),
+                        (i * 0.11).cos().abs(),
+                        (i * 0.73).sin().powi(2),
+                        (i * 0.05).fract(),
+                    ]
+                })
+                .collect()
+        }
+
+        fn brute_force<M: KdMetric<f32>>(
+            metric: &M,
+            data: &[[f32; 4]],
+            target: &[f32; 4],
+            k: usize,
+        ) -> Vec<usize> {
+            let mut dist = data
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, metric.distance(p, target, 4)))
+                .collect::<Vec<_>>();
+            dist.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            dist.into_iter().take(k).map(|(i, _)| i).collect()
+        }
+
+        fn check<M: KdMetric<f32>>(metric: M) {
+            let data = histograms();
+            let mut tree = KdTree::with_metric(4, metric.clone());
+            tree.set_data(&data);
+            tree.build();
+
+            [[0.2f32, 0.5, 0.1, 0.7], [0.9, 0.1, 0.4, 0.33]]
+                .iter()
+                .for_each(|target| {
+                    let result = tree.search_knn_ids(target, 5);
+                    assert_eq!(result, brute_force(&metric, &data, target, 5));
+                });
+        }
+
+        #[test]
+        fn query_knn_manhattan() {
+            check(Manhattan);
+        }
+
+        #[test]
+        fn query_knn_chebyshev() {
+            check(Chebyshev);
+        }
+
+        #[test]
+        fn query_knn_chi_squared() {
+            check(ChiSquared);
+        }
+
+        #[test]
+        fn query_knn_hellinger() {
+            check(Hellinger);
+        }
+
+        #[test]
+        fn query_knn_weighted() {
+            check(WeightedEuclidean::new(&[1f32, 4., 0.5, 2.]));
+        }
+
+        #[test]
+        fn query_radius_manhattan() {
+            let data = (0..10)
+                .flat_map(|x| (0..10).map(move |y| [x as f32, y as f32]))
+                .collect::<Vec<_>>();
+            let mut tree = KdTree::with_metric(2, Manhattan);
+            tree.set_data(&data);
+            tree.build();
+            // |dx| + |dy| <= 1 around (5, 5)
+            let mut result = tree.search_radius_ids(&[5f32, 5.], 1.01);
+            result.sort();
+            assert_eq!(result, vec![45, 54, 55, 56, 65]);
+        }
+    }
 }
@@ -0,0 +1,225 @@
+mod common;
+
+mod oc_tree {
+    use f3l_search_tree::*;
+
+    fn sorted_distances(data: &[[f32; 3]], target: [f32; 3]) -> Vec<f32> {
+        let mut d = data
+            .iter()
+            .map(|p| {
+                (0..3)
+                    .fold(0f32, |acc, j| acc + (p[j] - target[j]).powi(2))
+                    .sqrt()
+            })
+            .collect::<Vec<_>>();
+        d.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        d
+    }
+
+    // Distance to other nodes is already squared, pruning must not square it again.
+    #[test]
+    fn query_knn_same_as_brute_force() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut tree = OcTree::with_data(&data, 10, 5);
+        tree.build();
+
+        let targets = crate::common::grid::<3>(0..=3, 2.9, 0.35);
+        targets.iter().for_each(|&target| {
+            let d = sorted_distances(&data, target);
+            let knn = tree.search_knn(&target, 20);
+            assert_eq!(knn.len(), 20);
+            knn.iter()
+                .zip(&d)
+                .for_each(|(&(_, a), &b)| assert!((a - b).abs() < 1e-4, "{a} != {b}"));
+        });
+    }
+
+    fn brute_force(data: &[[f32; 3]], target: [f32; 3], k: usize, radius: f32) -> Vec<usize> {
+        let mut dist = data
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let d = (0..3).fold(0f32, |acc, j| acc + (p[j] - target[j]).powi(2));
+                (i, d)
+            })
+            .filter(|&(_, d)| d <= radius * radius)
+            .collect::<Vec<_>>();
+        dist.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        dist.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn query_hybrid_3d() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut tree = OcTree::with_data(&data, 10, 5);
+        tree.build();
+
+        let target = [4.3f32, 5.2, 6.1];
+        let result = tree.search_hybrid_ids(&target, 5, 1.5);
+        assert_eq!(result, brute_force(&data, target, 5, 1.5));
+
+        let result = tree.search_hybrid_ids(&target, 100, 1.5);
+        assert_eq!(result, brute_force(&data, target, 100, 1.5));
+    }
+
+    #[test]
+    fn query_hybrid_3d_kdtree_equal() {
+        let data = crate::common::grid::<3>(0..=9, 1., 0.);
+        let mut octree = OcTree::with_data(&data, 10, 5);
+        octree.build();
+        let mut kdtree = KdTree::with_data(3, &data);
+        kdtree.build();
+
+        let target = [2.7f32, 8.4, 0.9];
+        assert_eq!(
+            octree.search_hybrid_ids(&target, 8, 2.0),
+            kdtree.search_hybrid_ids(&target, 8, 2.0)
+        );
+    }
+
+    #[test]
+    fn query_knn_f64_utm() {
+        let data = (0..10)
+            .flat_map(|x| {
+                (0..10).map(move |y| {
+                    [
+                        500_000.0 + x as f64 * 0.01,
+                        4_000_000.0 + y as f64 * 0.01,
+                        100.0,
+                    ]
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut tree = OcTree::with_data(&data, 4, 6);
+        tree.build();
+
+        let target = [500_000.042f64, 4_000_000.071, 100.0];
+        let result = tree.search_knn_ids(&target, 1);
+        assert_eq!(result, vec![4 * 10 + 7]);
+        let result = tree.search_knn(&target, 1);
+        assert!((result[0].1 - (0.002f64.powi(2) + 0.001f64.powi(2)).sqrt()).abs() < 1e-6);
+    }
+
+    mod voxel {
+        use super::*;
+
+        fn build(data: &[[f32; 3]]) -> OcTree<'_, f32, [f32; 3]> {
+            let mut tree = OcTree::with_data(data, 10, 3);
+            tree.build();
+            tree
+        }
+
+        #[test]
+        fn voxels_cover_all_points() {
+            let data = crate::common::grid::<3>(0..=9, 1., 0.);
+            let tree = build(&data);
+            (0..6).for_each(|depth| {
+                let nb_points = tree.voxels(depth).map(|v| v.points.len()).sum::<usize>();
+                assert_eq!(nb_points, data.len());
+            });
+            assert_eq!(tree.voxels(0).count(), 1);
+            assert_eq!(tree.voxels(1).count(), 8);
+        }
+
+        #[test]
+        fn key_at_matches_voxels() {
+            let data = crate::common::grid::<3>(0..=9, 1., 0.);
+            let tree = build(&data);
+            // Depth 5 is deeper than tree, split virtually.
+            [2, 5].into_iter().for_each(|depth| {
+                tree.voxels(depth).for_each(|voxel| {
+                    voxel.points.iter().for_each(|&i| {
+                        assert_eq!(tree.key_at(&data[i], depth), Some(voxel.key));
+                    });
+                    let mut points = tree.points_in(&voxel.key);
+                    points.sort();
+                    let mut expected = voxel.points.clone();
+                    expected.sort();
+                    assert_eq!(points, expected);
+
+                    let (lower, upper) = tree.voxel_bounds(&voxel.key).unwrap();
+                    voxel.points.iter().for_each(|&i| {
+                        (0..3).for_each(|j| {
+                            assert!(data[i][j] >= lower[j] && data[i][j] <= upper[j]);
+                        });
+                    });
+                });
+            });
+            assert_eq!(tree.key_at(&[-1f32, 0., 0.], 2), None);
+        }
+
+        #[test]
+        fn centroids_lod() {
+            let data = crate::common::grid::<3>(0..=9, 1., 0.);
+            let tree = build(&data);
+            let lod = tree.lod(2);
+            assert_eq!(lod.len(), 3);
+            assert_eq!(lod[0].len(), 1);
+            (0..3).for_each(|i| assert!((lod[0][0].1[i] - 4.5).abs() < 1e-4));
+            assert_eq!(lod[1].len(), 8);
+            // Keys are sorted.
+            assert!(lod[2].windows(2).all(|w| w[0].0 < w[1].0));
+        }
+
+        #[test]
+        fn neighbors() {
+            let data = crate::common::grid::<3>(0..=9, 1., 0.);
+            let tree = build(&data);
+            let corner = tree.key_at(&data[0], 1).unwrap();
+            assert_eq!(corner.key, [0, 0, 0]);
+            assert_eq!(
+                tree.occupied_neighbors(&corner, OcConnectivity::Face).len(),
+                3
+            );
+            assert_eq!(
+                tree.occupied_neighbors(&corner, OcConnectivity::Vertex)
+                    .len(),
+                7
+            );
+
+            // Only 2 points, far from each other.
+            let sparse = vec![[0f32, 0., 0.], [1., 1., 1.]];
+            let mut tree = OcTree::with_data(&sparse, 1, 4);
+            tree.build();
+            let key = tree.key_at(&sparse[0], 3).unwrap();
+            assert!(tree
+                .occupied_neighbors(&key, OcConnectivity::Vertex)
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn change_detection() {
+        let scan_a = crate::common::grid::<3>(0..=9, 1., 0.);
+        // Remove `x < 2`, add a wall at `x = 11`.
+        let scan_b = scan_a
+            .iter()
+            .filter(|p| p[0] >= 2.0)
+            .copied()
+            .chain((0..10).flat_map(|y| (0..10).map(move |z| [11f32, y as f32, z as f32])))
+            .collect::<Vec<_>>();
+
+        let mut detector = OcChangeDetector::new([0f32, 0., 0.], [12f32, 12., 12.], 4);
+        detector.set_input(&scan_a);
+        // Nothing in previous buffer, all are new.
+        assert_eq!(detector.new_points().len(), scan_a.len());
+        detector.set_input(&scan_b);
+
+        let expected_new = (scan_b.len() - 100..scan_b.len()).collect::<Vec<_>>();
+        assert_eq!(detector.new_points(), expected_new);
+
+        let expected_removed = scan_a
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p[0] < 2.0)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(detector.removed_points(), expected_removed);
+        assert!(detector.removed_voxels().iter().all(|v| v.key.key[0] < 3));
+
+        // Same scan, nothing changed.
+        detector.set_input(&scan_b);
+        assert!(detector.new_points().is_empty());
+        assert!(detector.removed_points().is_empty());
+    }
+}
@@ -59,11 +59,13 @@ fn main() {
         threshold: 0.02,
         max_iterations: 2000,
         threads: 1,
+        ..Default::default()
     };
     let mut model = SacModelPlane::with_data(&vertices);
     let mut algorithm = SacRansac {
         parameter,
         inliers: vec![],
+        ..Default::default()
     };
 
     use std::time::Instant;
@@ -0,0 +1,352 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    ops::Index,
+};
+
+use f3l_core::{
+    rayon::prelude::*,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_search_tree::{KdTree, TreeSearch};
+
+use crate::{F3lCluster, F3lClusterParameter};
+
+/// Color-based Region Growing, like `RegionGrowingRGB` of PCL.
+///
+/// Ref: Zhan et al., Color-based segmentation of point clouds.
+///
+/// 1. Neighbors are searched in `tolerance`, at most `nb_in_tolerance` if it is larger than 0.
+///    Only `nb_in_tolerance` nearest if `tolerance` is 0.
+/// 2. Regions grow from unlabeled points in order, over neighbors whose color distance
+///    to the current point is smaller than `point_color_threshold`.
+/// 3. Adjacent regions whose mean colors are closer than `region_color_threshold` are merged,
+///    the closest pair first.
+/// 4. Regions smaller than `min_nb_data` are merged to the adjacent one of the closest mean color.
+///
+/// Colors are set by [`ColorRegionGrowing::set_colors`], indexed the same as data.
+/// `extract` returns nothing if the number of colors differs from data, including colors not set.
+/// Distance is euclidean of 3 channels, so thresholds are in the same scale as colors.
+///
+/// # Examples
+/// ```
+/// let (vertices, colors) = load_ply_rgb("../../data/rgbd_scene.ply");
+/// let parameter = F3lClusterParameter {
+///     tolerance: 0.02f32,
+///     nb_in_tolerance: 0,
+///     min_nb_data: 200,
+///     max_nb_data: vertices.len(),
+///     max_nb_cluster: 20,
+/// };
+/// let mut extractor = ColorRegionGrowing::new(parameter, 6., 5.);
+/// extractor.set_colors(&colors);
+/// let clusters = extractor.extract(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ColorRegionGrowing<'a, T, P, const D: usize>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
+{
+    pub parameter: F3lClusterParameter<T>,
+    /// Max color distance between neighbors in a region.
+    pub point_color_threshold: T,
+    /// Max mean color distance between regions to merge.
+    pub region_color_threshold: T,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    tree: KdTree<'a, T, P>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    colors: Vec<[T; 3]>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    clusters: Vec<Vec<usize>>,
+}
+
+/// Root of region `i`, with path halving.
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+#[inline]
+fn color_distance<T: BasicFloat>(a: &[T; 3], b: &[T; 3]) -> T {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+impl<'a, T, P, const D: usize> ColorRegionGrowing<'a, T, P, D>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; D]: Into<P>,
+{
+    pub fn new(
+        parameter: F3lClusterParameter<T>,
+        point_color_threshold: T,
+        region_color_threshold: T,
+    ) -> Self {
+        Self {
+            parameter,
+            point_color_threshold,
+            region_color_threshold,
+            tree: KdTree::<T, P>::new(D),
+            colors: vec![],
+            clusters: vec![],
+        }
+    }
+
+    pub fn set_point_color_threshold(&mut self, point_color_threshold: T) {
+        self.point_color_threshold = point_color_threshold;
+    }
+
+    pub fn set_region_color_threshold(&mut self, region_color_threshold: T) {
+        self.region_color_threshold = region_color_threshold;
+    }
+
+    /// Colors of each point of data, required before `extract`.
+    pub fn set_colors<C: Into<[T; 3]> + Copy>(&mut self, colors: &[C]) {
+        self.colors = colors.iter().map(|&c| c.into()).collect();
+    }
+
+    fn neighbors(&self, p: &P) -> Vec<usize> {
+        let (k, radius) = (self.parameter.nb_in_tolerance, self.parameter.tolerance);
+        if radius <= T::zero() {
+            self.tree.search_knn_ids(p, k)
+        } else if k == 0 {
+            self.tree.search_radius_ids(p, radius)
+        } else {
+            self.tree.search_hybrid_ids(p, k, radius)
+        }
+    }
+
+    /// Label of each point by growing on color distance between neighbors.
+    fn grow(&self, neighbors: &[Vec<usize>]) -> (Vec<usize>, usize) {
+        let mut labels = vec![usize::MAX; neighbors.len()];
+        let mut nb_regions = 0;
+        (0..neighbors.len()).for_each(|i| {
+            if labels[i] != usize::MAX {
+                return;
+            }
+            labels[i] = nb_regions;
+            let mut queue = VecDeque::from([i]);
+            while let Some(current) = queue.pop_front() {
+                neighbors[current].iter().for_each(|&j| {
+                    if labels[j] == usize::MAX
+                        && color_distance(&self.colors[current], &self.colors[j])
+                            < self.point_color_threshold
+                    {
+                        labels[j] = nb_regions;
+                        queue.push_back(j);
+                    }
+                });
+            }
+            nb_regions += 1;
+        });
+        (labels, nb_regions)
+    }
+}
+
+impl<'a, T, P, const D: usize> F3lCluster<'a, T, P> for ColorRegionGrowing<'a, T, P, D>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; D]: Into<P>,
+{
+    fn set_parameter(&mut self, parameter: F3lClusterParameter<T>) {
+        self.parameter = parameter;
+    }
+
+    fn parameter(&self) -> F3lClusterParameter<T> {
+        self.parameter
+    }
+
+    fn clusters(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Empty if colors do not match data, see [`ColorRegionGrowing::set_colors`].
+    fn extract(&mut self, data: &'a [P]) -> Vec<Vec<usize>> {
+        if data.is_empty() {
+            return vec![];
+        }
+        if !self.apply_extract(data) {
+            return vec![];
+        }
+
+        self.clusters.clone()
+    }
+
+    /// Return false if colors do not match data.
+    fn apply_extract(&mut self, data: &'a [P]) -> bool {
+        self.clusters.clear();
+        if self.colors.len() != data.len() {
+            return false;
+        }
+        if self.tree.dim != D {
+            self.tree = KdTree::<T, P>::new(D);
+        }
+        self.tree.set_data(data);
+        self.tree.build();
+
+        let neighbors = data
+            .par_iter()
+            .map(|p| self.neighbors(p))
+            .collect::<Vec<_>>();
+        let (labels, nb_regions) = self.grow(&neighbors);
+
+        // Sum of colors and size of regions, kept in roots.
+        let mut sums = vec![[T::zero(); 3]; nb_regions];
+        let mut sizes = vec![0usize; nb_regions];
+        labels.iter().zip(&self.colors).for_each(|(&l, c)| {
+            (0..3).for_each(|i| sums[l][i] += c[i]);
+            sizes[l] += 1;
+        });
+        let mean = |sums: &[[T; 3]], sizes: &[usize], r: usize| {
+            let n = T::from(sizes[r]).unwrap();
+            [sums[r][0] / n, sums[r][1] / n, sums[r][2] / n]
+        };
+        let mut adjacency = BTreeSet::new();
+        neighbors.iter().enumerate().for_each(|(i, n)| {
+            n.iter().for_each(|&j| {
+                let (a, b) = (labels[i], labels[j]);
+                if a != b {
+                    adjacency.insert((a.min(b), a.max(b)));
+                }
+            });
+        });
+
+        let mut parent = (0..nb_regions).collect::<Vec<_>>();
+        let union =
+            |parent: &mut [usize], sums: &mut [[T; 3]], sizes: &mut [usize], a: usize, b: usize| {
+                parent[b] = a;
+                sizes[a] += sizes[b];
+                let s = sums[b];
+                (0..3).for_each(|i| sums[a][i] += s[i]);
+            };
+
+        // Merge similar regions, the closest pair first.
+        let mut edges = adjacency
+            .iter()
+            .map(|&(a, b)| {
+                let d = color_distance(&mean(&sums, &sizes, a), &mean(&sums, &sizes, b));
+                (d, a, b)
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by(|a, b| a.0.to_f64().unwrap().total_cmp(&b.0.to_f64().unwrap()));
+        edges.into_iter().for_each(|(_, a, b)| {
+            let (a, b) = (find(&mut parent, a), find(&mut parent, b));
+            if a == b {
+                return;
+            }
+            let d = color_distance(&mean(&sums, &sizes, a), &mean(&sums, &sizes, b));
+            if d < self.region_color_threshold {
+                union(&mut parent, &mut sums, &mut sizes, a, b);
+            }
+        });
+
+        // Merge small regions to the closest adjacent one, until nothing changes.
+        loop {
+            let mut adjacent = vec![vec![]; nb_regions];
+            adjacency.iter().for_each(|&(a, b)| {
+                let (a, b) = (find(&mut parent, a), find(&mut parent, b));
+                if a != b {
+                    adjacent[a].push(b);
+                    adjacent[b].push(a);
+                }
+            });
+            let mut merged = false;
+            (0..nb_regions).for_each(|r| {
+                if find(&mut parent, r) != r || sizes[r] >= self.parameter.min_nb_data {
+                    return;
+                }
+                let own = mean(&sums, &sizes, r);
+                let closest = adjacent[r]
+                    .iter()
+                    .map(|&a| find(&mut parent, a))
+                    .filter(|&a| a != r)
+                    .map(|a| (color_distance(&own, &mean(&sums, &sizes, a)), a))
+                    .min_by(|a, b| {
+                        a.0.to_f64()
+                            .unwrap()
+                            .total_cmp(&b.0.to_f64().unwrap())
+                            .then(a.1.cmp(&b.1))
+                    });
+                if let Some((_, target)) = closest {
+                    union(&mut parent, &mut sums, &mut sizes, target, r);
+                    merged = true;
+                }
+            });
+            if !merged {
+                break;
+            }
+        }
+
+        let mut clusters = vec![vec![]; nb_regions];
+        labels.iter().enumerate().for_each(|(i, &l)| {
+            clusters[find(&mut parent, l)].push(i);
+        });
+        let mut clusters = clusters
+            .into_iter()
+            .filter(|c| {
+                c.len() >= self.parameter.min_nb_data && c.len() <= self.parameter.max_nb_data
+            })
+            .collect::<Vec<_>>();
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        self.clusters = clusters
+            .into_iter()
+            .take(self.parameter.max_nb_cluster)
+            .collect();
+        true
+    }
+
+    fn at(&self, id: usize) -> Result<Vec<P>, String> {
+        if id >= self.clusters.len() {
+            return Err(format!(
+                "Out of Range, available to {}",
+                self.clusters.len() as isize - 1
+            ));
+        }
+        let cluster = &self.clusters[id];
+        let data = cluster
+            .iter()
+            .map(|&i| self.tree.data.unwrap()[i])
+            .collect::<Vec<_>>();
+        Ok(data)
+    }
+
+    fn max_cluster(&self) -> Vec<P> {
+        self.at(0).unwrap()
+    }
+}
+
+#[test]
+fn serde() {
+    let cluster: ColorRegionGrowing<f32, [f32; 3], 3> =
+        ColorRegionGrowing::new(F3lClusterParameter::default(), 6., 5.);
+    let text = r#"{
+        "parameter":{
+            "tolerance":0.0,
+            "nb_in_tolerance":0,
+            "min_nb_data":0,
+            "max_nb_data":0,
+            "max_nb_cluster":0
+        },
+        "point_color_threshold":6.0,
+        "region_color_threshold":5.0
+    }"#;
+    let cluster_serde: ColorRegionGrowing<f32, [f32; 3], 3> = serde_json::from_str(text).unwrap();
+    assert_eq!(cluster.parameter, cluster_serde.parameter);
+    assert_eq!(
+        cluster.point_color_threshold,
+        cluster_serde.point_color_threshold
+    );
+    assert_eq!(
+        cluster.region_color_threshold,
+        cluster_serde.region_color_threshold
+    );
+}
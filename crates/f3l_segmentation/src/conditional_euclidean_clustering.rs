@@ -0,0 +1,205 @@
+use std::ops::Index;
+
+use f3l_core::{rayon::prelude::*, BasicFloat};
+use f3l_search_tree::{KdTree, TreeSearch};
+
+use crate::{F3lCluster, F3lClusterParameter};
+
+/// Conditional Euclidean Clustering, like `ConditionalEuclideanClustering` of PCL.
+///
+/// Neighbors in `tolerance` join the cluster only if `condition(point, neighbor, distance)` is true,
+/// ex: intensities or normals are similar.
+///
+/// Clusters smaller than `min_nb_data` or larger than `max_nb_data` are not dropped,
+/// but kept in [`ConditionalEuclideanClustering::too_small_clusters`] and
+/// [`ConditionalEuclideanClustering::too_large_clusters`].
+/// Valid clusters beyond the largest `max_nb_cluster` are kept in
+/// [`ConditionalEuclideanClustering::excess_clusters`].
+///
+/// `nb_in_tolerance` is ignored, neighbors are decided by `condition` only.
+///
+/// Not serializable, cause of the closure. Serialize `parameter` instead.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_remove_plane.ply");
+/// let parameter = F3lClusterParameter {
+///     tolerance: 0.02f32,
+///     nb_in_tolerance: 1,
+///     min_nb_data: 100,
+///     max_nb_data: 25000,
+///     max_nb_cluster: 5,
+/// };
+/// let mut extractor = ConditionalEuclideanClustering::new(parameter, |a: &[f32; 3], b, _| {
+///     (a[2] - b[2]).abs() < 0.005
+/// });
+/// let clusters = extractor.extract(&vertices);
+/// let rejected = extractor.too_small_clusters();
+/// ```
+pub struct ConditionalEuclideanClustering<'a, T, P, F, const D: usize>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Index<usize, Output = T>,
+    F: Fn(&P, &P, T) -> bool,
+{
+    pub parameter: F3lClusterParameter<T>,
+    pub condition: F,
+    tree: KdTree<'a, T, P>,
+    clusters: Vec<Vec<usize>>,
+    too_small: Vec<Vec<usize>>,
+    too_large: Vec<Vec<usize>>,
+    excess: Vec<Vec<usize>>,
+}
+
+impl<'a, T, P, F, const D: usize> ConditionalEuclideanClustering<'a, T, P, F, D>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; D]: Into<P>,
+    F: Fn(&P, &P, T) -> bool,
+{
+    pub fn new(parameter: F3lClusterParameter<T>, condition: F) -> Self {
+        Self {
+            parameter,
+            condition,
+            tree: KdTree::<T, P>::new(D),
+            clusters: vec![],
+            too_small: vec![],
+            too_large: vec![],
+            excess: vec![],
+        }
+    }
+
+    pub fn set_condition(&mut self, condition: F) {
+        self.condition = condition;
+    }
+
+    /// Clusters smaller than `min_nb_data` of last `extract`.
+    pub fn too_small_clusters(&self) -> Vec<Vec<usize>> {
+        self.too_small.clone()
+    }
+
+    /// Clusters larger than `max_nb_data` of last `extract`.
+    pub fn too_large_clusters(&self) -> Vec<Vec<usize>> {
+        self.too_large.clone()
+    }
+
+    /// Valid clusters beyond the largest `max_nb_cluster` of last `extract`, largest first.
+    pub fn excess_clusters(&self) -> Vec<Vec<usize>> {
+        self.excess.clone()
+    }
+}
+
+impl<'a, T, P, F, const D: usize> F3lCluster<'a, T, P>
+    for ConditionalEuclideanClustering<'a, T, P, F, D>
+where
+    T: BasicFloat,
+    P: Into<[T; D]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; D]: Into<P>,
+    F: Fn(&P, &P, T) -> bool,
+{
+    fn set_parameter(&mut self, parameter: F3lClusterParameter<T>) {
+        self.parameter = parameter;
+    }
+
+    fn parameter(&self) -> F3lClusterParameter<T> {
+        self.parameter
+    }
+
+    fn clusters(&self) -> usize {
+        self.clusters.len()
+    }
+
+    fn extract(&mut self, data: &'a [P]) -> Vec<Vec<usize>> {
+        if data.is_empty() {
+            return vec![];
+        }
+        if !self.apply_extract(data) {
+            return vec![];
+        }
+
+        self.clusters.clone()
+    }
+
+    fn apply_extract(&mut self, data: &'a [P]) -> bool {
+        self.clusters.clear();
+        self.too_small.clear();
+        self.too_large.clear();
+        self.excess.clear();
+        if self.tree.dim != D {
+            self.tree = KdTree::<T, P>::new(D);
+        }
+        self.tree.set_data(data);
+        self.tree.build();
+
+        let neighbors = data
+            .par_iter()
+            .map(|p| self.tree.search_radius_ids(p, self.parameter.tolerance))
+            .collect::<Vec<_>>();
+        let distance = |a: &P, b: &P| {
+            (0..D)
+                .fold(T::zero(), |acc, i| acc + (a[i] - b[i]).powi(2))
+                .sqrt()
+        };
+
+        let mut visited = vec![false; data.len()];
+        let mut clusters = vec![];
+        (0..data.len()).for_each(|i| {
+            if visited[i] {
+                return;
+            }
+            let mut pts = 0usize;
+            let mut cluster = vec![i];
+            visited[i] = true;
+
+            while pts < cluster.len() {
+                let current = cluster[pts];
+                neighbors[current].iter().for_each(|&j| {
+                    if visited[j] {
+                        return;
+                    }
+                    let (p, q) = (&data[current], &data[j]);
+                    if (self.condition)(p, q, distance(p, q)) {
+                        cluster.push(j);
+                        visited[j] = true;
+                    }
+                });
+                pts += 1;
+            }
+
+            cluster.sort();
+            if cluster.len() < self.parameter.min_nb_data {
+                self.too_small.push(cluster);
+            } else if cluster.len() > self.parameter.max_nb_data {
+                self.too_large.push(cluster);
+            } else {
+                clusters.push(cluster);
+            }
+        });
+        clusters.sort_by_key(|c: &Vec<usize>| std::cmp::Reverse(c.len()));
+        if clusters.len() > self.parameter.max_nb_cluster {
+            self.excess = clusters.split_off(self.parameter.max_nb_cluster);
+        }
+        self.clusters = clusters;
+        true
+    }
+
+    fn at(&self, id: usize) -> Result<Vec<P>, String> {
+        if id >= self.clusters.len() {
+            return Err(format!(
+                "Out of Range, available to {}",
+                self.clusters.len() as isize - 1
+            ));
+        }
+        let cluster = &self.clusters[id];
+        let data = cluster
+            .iter()
+            .map(|&i| self.tree.data.unwrap()[i])
+            .collect::<Vec<_>>();
+        Ok(data)
+    }
+
+    fn max_cluster(&self) -> Vec<P> {
+        self.at(0).unwrap()
+    }
+}
@@ -96,8 +96,7 @@ where
         self.tree.set_data(data);
         self.tree.build();
 
-        let radius = self.parameter.tolerance.to_f32().unwrap();
-        let radius = radius * radius;
+        let radius = self.parameter.tolerance * self.parameter.tolerance;
         let mut result = TreeRadiusResult::new(radius);
         let mut result_inner = TreeRadiusResult::new(radius);
         let by = SearchBy::Radius(radius);
@@ -3,8 +3,11 @@ use f3l_core::{
     BasicFloat,
 };
 
+mod color_region_growing;
+mod conditional_euclidean_clustering;
 mod db_scan;
 mod euclidean_cluster_extraction;
+mod region_growing;
 mod sac_segmentation;
 
 /// Cluster Extractor parameter
@@ -54,6 +57,9 @@ pub trait F3lCluster<'a, T: BasicFloat, P> {
     fn max_cluster(&self) -> Vec<P>;
 }
 
+pub use color_region_growing::ColorRegionGrowing;
+pub use conditional_euclidean_clustering::ConditionalEuclideanClustering;
 pub use db_scan::*;
 pub use euclidean_cluster_extraction::EuclideanClusterExtractor;
+pub use region_growing::RegionGrowing;
 pub use sac_segmentation::*;
@@ -0,0 +1,288 @@
+use std::{collections::VecDeque, ops::Index};
+
+use f3l_core::{
+    compute_covariance_matrix, jacobi_eigen_square_n,
+    rayon::prelude::*,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use f3l_search_tree::{KdTree, TreeSearch};
+
+use crate::{F3lCluster, F3lClusterParameter};
+
+/// Region Growing, smoothness constrained segmentation.
+///
+/// Ref: Rabbani et al., Segmentation of Point Clouds using Smoothness Constraint.
+///
+/// 1. Search `nb_in_tolerance` nearest neighbors of each point,
+///    limited in `tolerance` if it is larger than 0.
+/// 2. Normal and curvature `λ0 / (λ0 + λ1 + λ2)` are computed by covariance of neighbors.
+///    Normals could be replaced by [`RegionGrowing::set_normals`], like outputs of `NormalEstimation` of `f3l_features`.
+/// 3. Unlabeled point of the lowest curvature is a new seed.
+///    Neighbors of a seed whose normal angle to it is smaller than `angle_threshold` join the region,
+///    and become seeds if their curvatures are smaller than `curvature_threshold`.
+///
+/// Points without normal are never in a region.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+/// let parameter = F3lClusterParameter {
+///     tolerance: 0f32,
+///     nb_in_tolerance: 30,
+///     min_nb_data: 100,
+///     max_nb_data: vertices.len(),
+///     max_nb_cluster: 10,
+/// };
+/// let mut extractor = RegionGrowing::new(parameter);
+/// extractor.set_angle_threshold(3f32.to_radians());
+/// extractor.set_curvature_threshold(0.05);
+/// let clusters = extractor.extract(&vertices);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct RegionGrowing<'a, T, P>
+where
+    T: BasicFloat,
+    P: Into<[T; 3]> + Clone + Copy + Index<usize, Output = T>,
+{
+    pub parameter: F3lClusterParameter<T>,
+    /// Max angle between normals in radians. Default: 3 degrees.
+    pub angle_threshold: T,
+    /// Max curvature of seeds. Default: 0.05.
+    pub curvature_threshold: T,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    tree: KdTree<'a, T, P>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<Option<[T; 3]>>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    curvatures: Vec<T>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    clusters: Vec<Vec<usize>>,
+}
+
+impl<'a, T, P> RegionGrowing<'a, T, P>
+where
+    T: BasicFloat,
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; 3]: Into<P>,
+{
+    pub fn new(parameter: F3lClusterParameter<T>) -> Self {
+        Self {
+            parameter,
+            angle_threshold: T::from(3f64.to_radians()).unwrap(),
+            curvature_threshold: T::from(0.05).unwrap(),
+            tree: KdTree::<T, P>::new(3),
+            normals: vec![],
+            curvatures: vec![],
+            clusters: vec![],
+        }
+    }
+
+    pub fn set_angle_threshold(&mut self, angle_threshold: T) {
+        self.angle_threshold = angle_threshold;
+    }
+
+    pub fn set_curvature_threshold(&mut self, curvature_threshold: T) {
+        self.curvature_threshold = curvature_threshold;
+    }
+
+    /// Use these normals instead of computing. Empty to clear.
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+
+    /// Curvatures computed by last `extract`.
+    pub fn curvatures(&self) -> Vec<T> {
+        self.curvatures.clone()
+    }
+
+    fn neighbors(&self, p: &P) -> Vec<usize> {
+        let k = self.parameter.nb_in_tolerance;
+        if self.parameter.tolerance > T::zero() {
+            self.tree.search_hybrid_ids(p, k, self.parameter.tolerance)
+        } else {
+            self.tree.search_knn_ids(p, k)
+        }
+    }
+
+    /// Normal and curvature by covariance of neighbors.
+    fn local_surface(data: &[P], neighbors: &[usize]) -> (Option<[T; 3]>, T) {
+        if neighbors.len() < 3 {
+            return (None, T::zero());
+        }
+        let cloud = neighbors.iter().map(|&i| data[i]).collect::<Vec<_>>();
+        let (cov, _) = compute_covariance_matrix(&cloud);
+        let eigen = jacobi_eigen_square_n(cov);
+        let sum = eigen.iter().fold(T::zero(), |acc, e| acc + e.eigenvalue);
+        let minimal = eigen
+            .iter()
+            .min_by(|a, b| {
+                a.eigenvalue
+                    .to_f64()
+                    .unwrap()
+                    .total_cmp(&b.eigenvalue.to_f64().unwrap())
+            })
+            .unwrap();
+        let curvature = if sum > T::zero() {
+            minimal.eigenvalue / sum
+        } else {
+            T::zero()
+        };
+        (Some(minimal.eigenvector), curvature)
+    }
+}
+
+impl<'a, T, P> F3lCluster<'a, T, P> for RegionGrowing<'a, T, P>
+where
+    T: BasicFloat,
+    P: Into<[T; 3]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+    [T; 3]: Into<P>,
+{
+    fn set_parameter(&mut self, parameter: F3lClusterParameter<T>) {
+        self.parameter = parameter;
+    }
+
+    fn parameter(&self) -> F3lClusterParameter<T> {
+        self.parameter
+    }
+
+    fn clusters(&self) -> usize {
+        self.clusters.len()
+    }
+
+    fn extract(&mut self, data: &'a [P]) -> Vec<Vec<usize>> {
+        if data.is_empty() {
+            return vec![];
+        }
+        if !self.apply_extract(data) {
+            return vec![];
+        }
+
+        self.clusters.clone()
+    }
+
+    /// Return false if normals are set but do not match data.
+    fn apply_extract(&mut self, data: &'a [P]) -> bool {
+        self.clusters.clear();
+        let given = !self.normals.is_empty();
+        if given && self.normals.len() != data.len() {
+            return false;
+        }
+        self.tree.set_data(data);
+        self.tree.build();
+
+        let neighbors = data
+            .par_iter()
+            .map(|p| self.neighbors(p))
+            .collect::<Vec<_>>();
+        let (normals, curvatures): (Vec<_>, Vec<_>) = neighbors
+            .par_iter()
+            .map(|n| Self::local_surface(data, n))
+            .unzip();
+        let normals = if given { self.normals.clone() } else { normals };
+        self.curvatures = curvatures;
+
+        let mut order = (0..data.len())
+            .filter(|&i| normals[i].is_some())
+            .collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            self.curvatures[a]
+                .to_f64()
+                .unwrap()
+                .total_cmp(&self.curvatures[b].to_f64().unwrap())
+        });
+
+        let cos_threshold = self.angle_threshold.cos();
+        let dot = |a: &[T; 3], b: &[T; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let mut labeled = vec![false; data.len()];
+        let mut clusters = vec![];
+        order.into_iter().for_each(|i| {
+            if labeled[i] {
+                return;
+            }
+            labeled[i] = true;
+            let mut cluster = vec![i];
+            let mut seeds = VecDeque::from([i]);
+            while let Some(seed) = seeds.pop_front() {
+                let n = normals[seed].unwrap();
+                neighbors[seed].iter().for_each(|&j| {
+                    if labeled[j] {
+                        return;
+                    }
+                    let m = match &normals[j] {
+                        Some(m) => m,
+                        None => return,
+                    };
+                    let len = (dot(&n, &n) * dot(m, m)).sqrt();
+                    if len <= T::zero() || dot(&n, m).abs() / len < cos_threshold {
+                        return;
+                    }
+                    labeled[j] = true;
+                    cluster.push(j);
+                    if self.curvatures[j] < self.curvature_threshold {
+                        seeds.push_back(j);
+                    }
+                });
+            }
+            if cluster.len() >= self.parameter.min_nb_data
+                && cluster.len() <= self.parameter.max_nb_data
+            {
+                cluster.sort();
+                clusters.push(cluster);
+            }
+        });
+        clusters.sort_by_key(|c: &Vec<usize>| std::cmp::Reverse(c.len()));
+        self.clusters = clusters
+            .into_iter()
+            .take(self.parameter.max_nb_cluster)
+            .collect();
+        true
+    }
+
+    fn at(&self, id: usize) -> Result<Vec<P>, String> {
+        if id >= self.clusters.len() {
+            return Err(format!(
+                "Out of Range, available to {}",
+                self.clusters.len() as isize - 1
+            ));
+        }
+        let cluster = &self.clusters[id];
+        let data = cluster
+            .iter()
+            .map(|&i| self.tree.data.unwrap()[i])
+            .collect::<Vec<_>>();
+        Ok(data)
+    }
+
+    fn max_cluster(&self) -> Vec<P> {
+        self.at(0).unwrap()
+    }
+}
+
+#[test]
+fn serde() {
+    let cluster: RegionGrowing<f32, [f32; 3]> = RegionGrowing::new(F3lClusterParameter::default());
+    let text = r#"{
+        "parameter":{
+            "tolerance":0.0,
+            "nb_in_tolerance":0,
+            "min_nb_data":0,
+            "max_nb_data":0,
+            "max_nb_cluster":0
+        },
+        "angle_threshold":0.05235988,
+        "curvature_threshold":0.05
+    }"#;
+    let cluster_serde: RegionGrowing<f32, [f32; 3]> = serde_json::from_str(text).unwrap();
+    assert_eq!(cluster.parameter, cluster_serde.parameter);
+    assert_eq!(cluster.angle_threshold, cluster_serde.angle_threshold);
+    assert_eq!(
+        cluster.curvature_threshold,
+        cluster_serde.curvature_threshold
+    );
+}
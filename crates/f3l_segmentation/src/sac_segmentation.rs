@@ -3,7 +3,7 @@ use f3l_core::{
     BasicFloat,
 };
 
-use crate::sac_algorithm::{SacAlgorithm, SacAlgorithmGetSet};
+use crate::sac_algorithm::SacAlgorithm;
 
 pub mod sac_algorithm;
 pub mod sac_model;
@@ -46,25 +46,98 @@ pub struct SacSegment<M: sac_model::ModelCoefficient> {
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     pub inliers: Vec<usize>,
+    /// Indices of data sorted by quality descending, used by `PROSAC`.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub ordering: Vec<usize>,
+    /// Numbers of hypotheses evaluated by last `compute`.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    /// Score of last `compute`, see algorithms of [`sac_algorithm::SacAlgorithmType`].
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
 }
 
+impl<M: sac_model::ModelCoefficient> SacSegment<M> {
+    /// Run algorithm selected by `algorithm` on `model`.
+    fn run<'a, P, T, R>(&mut self, model: &mut R) -> bool
+    where
+        P: Into<[T; 3]> + Copy,
+        T: BasicFloat,
+        R: SacModel<'a, P, T> + Send + Sync,
+        R::CoefficientsType: Send + Sync,
+        R::SampleIdxType: TryFrom<Vec<usize>>,
+    {
+        use sac_algorithm::*;
+
+        let parameter = self.algorithm_parameter;
+        match self.algorithm {
+            SacAlgorithmType::RANSAC => self.collect(SacRansac::with_parameter(parameter), model),
+            SacAlgorithmType::MSAC => self.collect(SacMsac::with_parameter(parameter), model),
+            SacAlgorithmType::MLESAC => self.collect(SacMlesac::with_parameter(parameter), model),
+            SacAlgorithmType::PROSAC => {
+                let mut algorithm = SacProsac::with_parameter(parameter);
+                algorithm.set_ordering(&self.ordering);
+                self.collect(algorithm, model)
+            }
+            SacAlgorithmType::LMEDS => self.collect(SacLmeds::with_parameter(parameter), model),
+            SacAlgorithmType::LORANSAC => {
+                self.collect(SacLoRansac::with_parameter(parameter), model)
+            }
+        }
+    }
+
+    fn collect<'a, P, T, R, A>(&mut self, mut algorithm: A, model: &mut R) -> bool
+    where
+        P: Copy,
+        T: BasicFloat,
+        R: SacModel<'a, P, T>,
+        A: SacAlgorithm<'a, P, T, R>,
+    {
+        let b = algorithm.compute(model);
+        self.inliers = algorithm.get_inliers().clone();
+        self.iterations = algorithm.get_iterations();
+        self.score = algorithm.get_score();
+        b
+    }
+}
+
+/// `$field => $setter` passes fields of coefficient struct to model, like `axis => set_axis`.
 macro_rules! impl_compute {
-    ($segment:ident, $model_type:ty, $model_struct:ident) => {
+    ($segment:ident, $model_type:ty, $model_struct:ident, normals $(, $field:ident => $setter:ident)*) => {
+        impl<T: BasicFloat> $segment<$model_type> {
+            /// `normals` are indexed the same as data.
+            pub fn compute<'a, P, N>(&mut self, data: &'a [P], normals: &[Option<N>]) -> bool
+            where
+                P: Into<[T; 3]> + Clone + Copy + Sync,
+                N: Into<[T; 3]> + Copy,
+            {
+                if normals.len() != data.len() {
+                    return false;
+                }
+                let mut model = $model_struct::with_data(data);
+                model.set_normals(normals);
+                $(model.$setter(self.model.$field);)*
+                assert!($model_struct::<'a, P, T>::NB_SAMPLE <= data.len());
+                let b = self.run(&mut model);
+                self.model.coefficients = model.get_coefficient();
+                b
+            }
+        }
+    };
+    ($segment:ident, $model_type:ty, $model_struct:ident $(, $field:ident => $setter:ident)*) => {
         impl<T: BasicFloat> $segment<$model_type> {
             pub fn compute<'a, P>(&mut self, data: &'a [P]) -> bool
             where
                 P: Into<[T; 3]> + Clone + Copy + Sync,
             {
-                let mut algorithm = match self.algorithm {
-                    sac_algorithm::SacAlgorithmType::RANSAC => {
-                        sac_algorithm::SacRansac::with_parameter(self.algorithm_parameter)
-                    }
-                };
                 let mut model = $model_struct::with_data(data);
+                $(model.$setter(self.model.$field);)*
                 assert!($model_struct::<'a, P, T>::NB_SAMPLE <= data.len());
-                let b = algorithm.compute(&mut model);
+                let b = self.run(&mut model);
                 self.model.coefficients = model.get_coefficient();
-                self.inliers = algorithm.get_inliers().clone();
                 b
             }
         }
@@ -75,6 +148,38 @@ impl_compute!(SacSegment, PlaneCoefficient<T>, SacModelPlane);
 impl_compute!(SacSegment, LineCoefficient<T>, SacModelLine);
 impl_compute!(SacSegment, Circle3dCoefficient<T>, SacModelCircle3d);
 impl_compute!(SacSegment, SphereCoefficient<T>, SacModelSphere);
+impl_compute!(SacSegment, Line2dCoefficient<T>, SacModelLine2d);
+impl_compute!(SacSegment, Circle2dCoefficient<T>, SacModelCircle2d);
+impl_compute!(SacSegment, Ellipse2dCoefficient<T>, SacModelEllipse2d);
+impl_compute!(
+    SacSegment,
+    CylinderCoefficient<T>,
+    SacModelCylinder,
+    normals
+);
+impl_compute!(SacSegment, ConeCoefficient<T>, SacModelCone, normals);
+impl_compute!(SacSegment, TorusCoefficient<T>, SacModelTorus, normals);
+impl_compute!(
+    SacSegment,
+    PerpendicularPlaneCoefficient<T>,
+    SacModelPerpendicularPlane,
+    axis => set_axis,
+    eps_angle => set_eps_angle
+);
+impl_compute!(
+    SacSegment,
+    ParallelPlaneCoefficient<T>,
+    SacModelParallelPlane,
+    axis => set_axis,
+    eps_angle => set_eps_angle
+);
+impl_compute!(
+    SacSegment,
+    NormalPlaneCoefficient<T>,
+    SacModelNormalPlane,
+    normals,
+    normal_distance_weight => set_normal_distance_weight
+);
 
 #[test]
 fn segment_plane() {
@@ -152,6 +257,8 @@ fn segment_sphere() {
 mod test_serde {
     #[allow(unused_imports)]
     use super::*;
+    #[allow(unused_imports)]
+    use crate::sac_algorithm::{SacAlgorithmParameter, SacAlgorithmType};
 
     #[allow(unused_macros)]
     macro_rules! serde_convert {
@@ -184,6 +291,34 @@ mod test_serde {
         );
     }
 
+    #[test]
+    fn serde_seed() {
+        let text = r#"{
+                "algorithm":"MSAC",
+                "algorithm_parameter":{
+                    "probability":0.99,
+                    "threshold":0.1,
+                    "max_iterations":1000,
+                    "threads":1,
+                    "refine_iterations":0,
+                    "seed":42
+                }
+            }"#;
+
+        serde_convert!(
+            SacSegment::<PlaneCoefficient<f32>> {
+                algorithm: SacAlgorithmType::MSAC,
+                algorithm_parameter: SacAlgorithmParameter {
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text,
+            SacSegment<PlaneCoefficient<f32>>
+        );
+    }
+
     #[test]
     fn serde_line() {
         let text = r#"{
@@ -1,4 +1,14 @@
+mod sac_lmeds;
+mod sac_lo_ransac;
+mod sac_mlesac;
+mod sac_msac;
+mod sac_prosac;
 mod sac_ransac;
+pub use sac_lmeds::*;
+pub use sac_lo_ransac::*;
+pub use sac_mlesac::*;
+pub use sac_msac::*;
+pub use sac_prosac::*;
 pub use sac_ransac::*;
 
 use f3l_core::{
@@ -7,6 +17,8 @@ use f3l_core::{
 };
 
 use super::sac_model::SacModel;
+use f3l_core::seeded_rng;
+use rand::{Rng, SeedableRng};
 
 /// Algorithm of Optimization
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,6 +26,16 @@ use super::sac_model::SacModel;
 pub enum SacAlgorithmType {
     #[default]
     RANSAC,
+    /// See [`SacMsac`]
+    MSAC,
+    /// See [`SacMlesac`]
+    MLESAC,
+    /// See [`SacProsac`]
+    PROSAC,
+    /// See [`SacLmeds`]
+    LMEDS,
+    /// See [`SacLoRansac`]
+    LORANSAC,
 }
 
 /// Parameter of algorithm of Optimization
@@ -27,7 +49,21 @@ pub struct SacAlgorithmParameter {
     /// If reach `max_iteration`, Optimization will be `terminate`.
     pub max_iterations: usize,
     /// Use parallel. Default `1` (single thread).
+    ///
+    /// Only [`SacRansac`] runs in parallel, each thread runs its share of `max_iterations`.
     pub threads: usize,
+    /// Iterations of refinement, coefficients are re-estimated from all inliers by least squares,
+    /// then inliers are selected again, until inliers are stable. Default `0` (no refinement).
+    ///
+    /// See [`SacModel::refine_model_coefficients`].
+    #[serde(default)]
+    pub refine_iterations: usize,
+    /// Seed of random samples, same seed gives same result. Default `None` (from entropy).
+    ///
+    /// Generator is [`f3l_core::SeededRng`], which is portable across platforms and versions.
+    /// Use [`SacAlgorithm::compute_with_rng`] for other generators.
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 impl Default for SacAlgorithmParameter {
@@ -37,6 +73,8 @@ impl Default for SacAlgorithmParameter {
             threshold: 0.1,
             max_iterations: 1000,
             threads: 1,
+            refine_iterations: 0,
+            seed: None,
         }
     }
 }
@@ -44,7 +82,12 @@ impl Default for SacAlgorithmParameter {
 /// A trait to get inliers data.
 pub trait SacAlgorithmGetSet {
     fn with_parameter(parameter: SacAlgorithmParameter) -> Self;
+    fn get_parameter(&self) -> &SacAlgorithmParameter;
     fn get_inliers(&self) -> &Vec<usize>;
+    /// Numbers of hypotheses evaluated by last `compute`.
+    fn get_iterations(&self) -> usize;
+    /// Score of the final model, meaning depends on algorithm.
+    fn get_score(&self) -> f32;
 }
 
 /// A trait to support algorithm computing.
@@ -53,5 +96,131 @@ where
     T: BasicFloat,
     R: SacModel<'a, P, T>,
 {
-    fn compute(&mut self, model: &mut R) -> bool;
+    /// Compute with generator of `seed` of parameter, see [`f3l_core::seeded_rng`].
+    fn compute(&mut self, model: &mut R) -> bool {
+        let mut rng = seeded_rng(self.get_parameter().seed);
+        self.compute_with_rng(model, &mut rng)
+    }
+    /// Compute with samples drawn from `rng`.
+    ///
+    /// Algorithms running in threads seed a generator per thread from `rng`, in order of threads.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send;
+}
+
+/// Iterations needed to reach `probability` with ratio of inliers, like [`SacRansac`].
+pub(crate) fn adaptive_iterations(
+    probability: f32,
+    nb_inlier: usize,
+    nb_data: usize,
+    nb_sample: usize,
+) -> f32 {
+    let w = nb_inlier as f32 / nb_data as f32;
+    let p_outlier = (1. - w.powi(nb_sample as i32)).clamp(f32::EPSILON, 1. - f32::EPSILON);
+    (1. - probability).ln() / p_outlier.ln()
+}
+
+/// Select inliers within `tolerance` of `coefficients`, then refine by them up to `iterations`,
+/// until inliers are stable.
+///
+/// Return refined coefficients if any, and inliers.
+pub(crate) fn refine<'a, P, T, R>(
+    model: &R,
+    coefficients: &R::CoefficientsType,
+    tolerance: T,
+    iterations: usize,
+) -> (Option<R::CoefficientsType>, Vec<usize>)
+where
+    P: Copy,
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+{
+    let mut inliers = model.select_indices_within_tolerance(coefficients, tolerance);
+    let mut refined = None;
+    for _ in 0..iterations {
+        let current = refined.as_ref().unwrap_or(coefficients);
+        let Some(next) = model.refine_model_coefficients(&inliers, current) else {
+            break;
+        };
+        let next_inliers = model.select_indices_within_tolerance(&next, tolerance);
+        if next_inliers.len() < R::NB_SAMPLE {
+            break;
+        }
+        let stable = next_inliers == inliers;
+        refined = Some(next);
+        inliers = next_inliers;
+        if stable {
+            break;
+        }
+    }
+    (refined, inliers)
+}
+
+/// Random `nb` distinct items of `pool`.
+pub(crate) fn sample_from<G: Rng + ?Sized>(rng: &mut G, pool: &[usize], nb: usize) -> Vec<usize> {
+    rand::seq::index::sample(rng, pool.len(), nb)
+        .into_iter()
+        .map(|i| pool[i])
+        .collect()
+}
+
+/// Hypothesize and verify in single thread, keep the one of lowest `cost` of distances.
+///
+/// Stop early like [`SacRansac`] if `adaptive`, by inliers within `threshold`.
+/// Samples are drawn from `rng`.
+/// Return best coefficients with its cost, and numbers of iterations.
+pub(crate) fn minimize_cost<'a, P, T, R, C, G>(
+    model: &R,
+    parameter: &SacAlgorithmParameter,
+    rng: &mut G,
+    adaptive: bool,
+    cost: C,
+) -> (Option<(R::CoefficientsType, f64)>, usize)
+where
+    P: Copy,
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+    C: Fn(&[T]) -> f64,
+    G: Rng + ?Sized,
+{
+    let SacAlgorithmParameter {
+        probability,
+        threshold,
+        max_iterations,
+        ..
+    } = *parameter;
+    let threshold = T::from(threshold).unwrap();
+    let max_skip = max_iterations * 100;
+    let nb_data = model.data_len();
+
+    let mut best: Option<(R::CoefficientsType, f64)> = None;
+    let mut iterations = 0;
+    let mut skipped = 0;
+    let mut k = f32::MAX;
+    while iterations < max_iterations && skipped <= max_skip && (iterations as f32) < k {
+        let samples = model.get_random_samples(rng);
+        let coefficients = match model.compute_model_coefficients(&samples) {
+            Ok(coefficients) => coefficients,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        iterations += 1;
+        let distances = model.get_distance_to_model(&coefficients);
+        let c = cost(&distances);
+        // Degenerate coefficients could pass, like `NaN`.
+        if c.is_nan() {
+            continue;
+        }
+        if best.as_ref().is_none_or(|(_, b)| c < *b) {
+            if adaptive {
+                let nb_inlier = distances.iter().filter(|&&d| d < threshold).count();
+                k = adaptive_iterations(probability, nb_inlier, nb_data, R::NB_SAMPLE).max(1.);
+            }
+            best = Some((coefficients, c));
+        }
+    }
+    (best, iterations)
 }
@@ -0,0 +1,100 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use super::{minimize_cost, refine, SacAlgorithm, SacAlgorithmGetSet, SacAlgorithmParameter};
+use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
+
+/// Least Median of Squares
+///
+/// Hypotheses are ranked by median of squared distances, `threshold` is not used.
+/// All `max_iterations` are evaluated.
+/// Inliers are within `2.5` robust standard deviation, `1.4826 (1 + 5 / (n - p)) sqrt(median)`.
+/// Score is the median, lower is better.
+///
+/// Ref: Rousseeuw and Leroy, Robust Regression and Outlier Detection.
+///
+/// See [`SacAlgorithmParameter`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SacLmeds {
+    pub parameter: SacAlgorithmParameter,
+    pub inliers: Vec<usize>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
+}
+
+impl SacAlgorithmGetSet for SacLmeds {
+    fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
+        Self {
+            parameter,
+            ..Default::default()
+        }
+    }
+
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
+    fn get_inliers(&self) -> &Vec<usize> {
+        &self.inliers
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl<'a, P: Copy, T, R> SacAlgorithm<'a, P, T, R> for SacLmeds
+where
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+{
+    /// Compute LMedS
+    ///
+    /// End with reach `max_iteration`.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
+        let (best, iterations) = minimize_cost(model, &self.parameter, rng, false, |distances| {
+            let mut squared = distances
+                .iter()
+                .map(|d| (*d * *d).to_f64().unwrap())
+                .collect::<Vec<_>>();
+            let mid = squared.len() / 2;
+            *squared.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1
+        });
+        self.iterations = iterations;
+
+        let Some((coefficient, median)) = best else {
+            self.inliers.clear();
+            return false;
+        };
+        self.score = median as f32;
+
+        let dof = model.data_len().saturating_sub(R::NB_SAMPLE).max(1) as f64;
+        let sigma = 1.4826 * (1. + 5. / dof) * median.sqrt();
+        let tolerance = T::from(2.5 * sigma).unwrap().max(T::epsilon());
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            tolerance,
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
+
+        true
+    }
+}
@@ -0,0 +1,171 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use super::{
+    adaptive_iterations, refine, sample_from, SacAlgorithm, SacAlgorithmGetSet,
+    SacAlgorithmParameter,
+};
+use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
+
+/// Locally Optimized Ransac
+///
+/// Like [`SacRansac`](super::SacRansac) in single thread,
+/// each new best model is optimized by an inner Ransac sampling only from its inliers,
+/// repeated `local_iterations` times. Score is numbers of inliers.
+///
+/// Ref: Chum, Matas and Kittler, Locally Optimized RANSAC.
+///
+/// See [`SacAlgorithmParameter`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SacLoRansac {
+    pub parameter: SacAlgorithmParameter,
+    pub inliers: Vec<usize>,
+    /// Numbers of inner samples of local optimization. Default `20`.
+    pub local_iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
+}
+
+impl Default for SacLoRansac {
+    fn default() -> Self {
+        Self {
+            parameter: Default::default(),
+            inliers: vec![],
+            local_iterations: 20,
+            iterations: 0,
+            score: 0.,
+        }
+    }
+}
+
+impl SacLoRansac {
+    /// Inner Ransac from inliers of `coefficients`, return the better one.
+    fn local_optimize<'a, P: Copy, T: BasicFloat, R: SacModel<'a, P, T>, G: Rng + ?Sized>(
+        &self,
+        rng: &mut G,
+        model: &R,
+        coefficients: R::CoefficientsType,
+        nb_inlier: usize,
+        threshold: T,
+    ) -> (R::CoefficientsType, usize)
+    where
+        R::SampleIdxType: TryFrom<Vec<usize>>,
+    {
+        let mut best = (coefficients, nb_inlier);
+        let mut inliers = model.select_indices_within_tolerance(&best.0, threshold);
+        for _ in 0..self.local_iterations {
+            if inliers.len() <= R::NB_SAMPLE {
+                break;
+            }
+            let Ok(samples) = R::SampleIdxType::try_from(sample_from(rng, &inliers, R::NB_SAMPLE))
+            else {
+                break;
+            };
+            let Ok(coefficients) = model.compute_model_coefficients(&samples) else {
+                continue;
+            };
+            let nb = model.count_indices_within_tolerance(&coefficients, threshold);
+            if nb > best.1 {
+                best = (coefficients, nb);
+                inliers = model.select_indices_within_tolerance(&best.0, threshold);
+            }
+        }
+        best
+    }
+}
+
+impl SacAlgorithmGetSet for SacLoRansac {
+    fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
+        Self {
+            parameter,
+            ..Default::default()
+        }
+    }
+
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
+    fn get_inliers(&self) -> &Vec<usize> {
+        &self.inliers
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl<'a, P: Copy, T, R> SacAlgorithm<'a, P, T, R> for SacLoRansac
+where
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+    R::SampleIdxType: TryFrom<Vec<usize>>,
+{
+    /// Compute Lo-Ransac
+    ///
+    /// End with reach `max_iteration` or probability more than numbers of iteration.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
+        let SacAlgorithmParameter {
+            probability,
+            threshold,
+            max_iterations,
+            ..
+        } = self.parameter;
+        let threshold = T::from(threshold).unwrap();
+        let nb_data = model.data_len();
+        let max_skip = max_iterations * 100;
+
+        self.iterations = 0;
+        let mut skipped = 0;
+        let mut k = f32::MAX;
+        let mut best: Option<(R::CoefficientsType, usize)> = None;
+        while self.iterations < max_iterations
+            && skipped <= max_skip
+            && (self.iterations as f32) < k
+        {
+            let samples = model.get_random_samples(rng);
+            let Ok(coefficients) = model.compute_model_coefficients(&samples) else {
+                skipped += 1;
+                continue;
+            };
+            self.iterations += 1;
+            let nb_inlier = model.count_indices_within_tolerance(&coefficients, threshold);
+            if best.as_ref().is_none_or(|(_, b)| nb_inlier > *b) {
+                let optimized = self.local_optimize(rng, model, coefficients, nb_inlier, threshold);
+                k = adaptive_iterations(probability, optimized.1, nb_data, R::NB_SAMPLE).max(1.);
+                best = Some(optimized);
+            }
+        }
+
+        let Some((coefficient, nb_inlier)) = best else {
+            self.inliers.clear();
+            return false;
+        };
+        self.score = nb_inlier as f32;
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            threshold,
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
+
+        true
+    }
+}
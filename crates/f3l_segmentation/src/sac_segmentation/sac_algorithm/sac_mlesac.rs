@@ -0,0 +1,134 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use super::{minimize_cost, refine, SacAlgorithm, SacAlgorithmGetSet, SacAlgorithmParameter};
+use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
+
+/// Maximum Likelihood Estimation Sample Consensus
+///
+/// Distances are a mixture of gaussian inliers, `sigma = threshold / 1.96`,
+/// and uniform outliers over diagonal of bounding box.
+/// Mixing ratio is estimated by EM, hypotheses are ranked by negative log likelihood.
+/// Score is the negative log likelihood, lower is better.
+///
+/// Ref: Torr and Zisserman, MLESAC: A New Robust Estimator with Application to Estimating Image Geometry.
+///
+/// See [`SacAlgorithmParameter`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SacMlesac {
+    pub parameter: SacAlgorithmParameter,
+    pub inliers: Vec<usize>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
+}
+
+impl SacMlesac {
+    /// Iterations of EM for mixing ratio.
+    const EM_ITERATIONS: usize = 5;
+}
+
+impl SacAlgorithmGetSet for SacMlesac {
+    fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
+        Self {
+            parameter,
+            ..Default::default()
+        }
+    }
+
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
+    fn get_inliers(&self) -> &Vec<usize> {
+        &self.inliers
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl<'a, P, T, R> SacAlgorithm<'a, P, T, R> for SacMlesac
+where
+    P: Into<[T; 3]> + Copy,
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+{
+    /// Compute Mlesac
+    ///
+    /// End with reach `max_iteration` or probability more than numbers of iteration.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
+        let threshold = T::from(self.parameter.threshold).unwrap();
+        let sigma = self.parameter.threshold as f64 / 1.96;
+        let mut min = [f64::MAX; 3];
+        let mut max = [f64::MIN; 3];
+        model.samples().iter().for_each(|&p| {
+            let p: [T; 3] = p.into();
+            (0..3).for_each(|i| {
+                let v = p[i].to_f64().unwrap();
+                min[i] = min[i].min(v);
+                max[i] = max[i].max(v);
+            });
+        });
+        let diagonal = (0..3)
+            .map(|i| (max[i] - min[i]).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let p_outlier = 1. / diagonal.max(f64::EPSILON);
+        let factor = 1. / ((2. * std::f64::consts::PI).sqrt() * sigma);
+
+        let (best, iterations) = minimize_cost(model, &self.parameter, rng, true, |distances| {
+            let p_inlier = distances
+                .iter()
+                .map(|d| {
+                    let d = d.to_f64().unwrap();
+                    factor * (-d * d / (2. * sigma * sigma)).exp()
+                })
+                .collect::<Vec<_>>();
+            let mut gamma = 0.5;
+            for _ in 0..Self::EM_ITERATIONS {
+                gamma = p_inlier
+                    .iter()
+                    .map(|p| gamma * p / (gamma * p + (1. - gamma) * p_outlier))
+                    .sum::<f64>()
+                    / p_inlier.len() as f64;
+            }
+            -p_inlier
+                .iter()
+                .map(|p| (gamma * p + (1. - gamma) * p_outlier).ln())
+                .sum::<f64>()
+        });
+        self.iterations = iterations;
+
+        let Some((coefficient, cost)) = best else {
+            self.inliers.clear();
+            return false;
+        };
+        self.score = cost as f32;
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            threshold,
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
+
+        true
+    }
+}
@@ -0,0 +1,92 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use super::{minimize_cost, refine, SacAlgorithm, SacAlgorithmGetSet, SacAlgorithmParameter};
+use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
+
+/// M-estimator Sample Consensus
+///
+/// Like [`SacRansac`](super::SacRansac), but hypotheses are ranked by truncated quadratic cost,
+/// `sum(min(d^2, threshold^2))`, instead of numbers of inliers. Score is the cost, lower is better.
+///
+/// See [`SacAlgorithmParameter`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SacMsac {
+    pub parameter: SacAlgorithmParameter,
+    pub inliers: Vec<usize>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
+}
+
+impl SacAlgorithmGetSet for SacMsac {
+    fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
+        Self {
+            parameter,
+            ..Default::default()
+        }
+    }
+
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
+    fn get_inliers(&self) -> &Vec<usize> {
+        &self.inliers
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl<'a, P: Copy, T, R> SacAlgorithm<'a, P, T, R> for SacMsac
+where
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+{
+    /// Compute Msac
+    ///
+    /// End with reach `max_iteration` or probability more than numbers of iteration.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
+        let threshold = T::from(self.parameter.threshold).unwrap();
+        let th2 = (threshold * threshold).to_f64().unwrap();
+        let (best, iterations) = minimize_cost(model, &self.parameter, rng, true, |distances| {
+            distances
+                .iter()
+                .map(|d| (*d * *d).to_f64().unwrap().min(th2))
+                .sum()
+        });
+        self.iterations = iterations;
+
+        let Some((coefficient, cost)) = best else {
+            self.inliers.clear();
+            return false;
+        };
+        self.score = cost as f32;
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            threshold,
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
+
+        true
+    }
+}
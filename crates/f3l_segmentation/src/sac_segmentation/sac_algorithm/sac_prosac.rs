@@ -0,0 +1,165 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use super::{
+    adaptive_iterations, refine, sample_from, SacAlgorithm, SacAlgorithmGetSet,
+    SacAlgorithmParameter,
+};
+use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
+
+/// Progressive Sample Consensus
+///
+/// Samples are drawn from top of `ordering`, indices of data sorted by quality descending,
+/// and the pool grows progressively to all data. Empty `ordering` means order of data.
+/// Stop like [`SacRansac`](super::SacRansac). Score is numbers of inliers.
+///
+/// Ref: Chum and Matas, Matching with PROSAC - Progressive Sample Consensus.
+///
+/// See [`SacAlgorithmParameter`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SacProsac {
+    pub parameter: SacAlgorithmParameter,
+    pub inliers: Vec<usize>,
+    /// Indices of data, the best first.
+    pub ordering: Vec<usize>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
+}
+
+impl SacProsac {
+    pub fn set_ordering(&mut self, ordering: &[usize]) {
+        self.ordering = ordering.to_vec();
+    }
+}
+
+impl SacAlgorithmGetSet for SacProsac {
+    fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
+        Self {
+            parameter,
+            ..Default::default()
+        }
+    }
+
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
+    fn get_inliers(&self) -> &Vec<usize> {
+        &self.inliers
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl<'a, P: Copy, T, R> SacAlgorithm<'a, P, T, R> for SacProsac
+where
+    T: BasicFloat,
+    R: SacModel<'a, P, T>,
+    R::SampleIdxType: TryFrom<Vec<usize>>,
+{
+    /// Compute Prosac
+    ///
+    /// Return `false` if `ordering` is not empty and not the same length of data.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
+        let SacAlgorithmParameter {
+            probability,
+            threshold,
+            max_iterations,
+            ..
+        } = self.parameter;
+        let threshold = T::from(threshold).unwrap();
+        let nb_data = model.data_len();
+        let m = R::NB_SAMPLE;
+        self.iterations = 0;
+        self.inliers.clear();
+        if !self.ordering.is_empty() && self.ordering.len() != nb_data {
+            return false;
+        }
+        let ordering = if self.ordering.is_empty() {
+            (0..nb_data).collect::<Vec<_>>()
+        } else {
+            self.ordering.clone()
+        };
+        if nb_data < m {
+            return false;
+        }
+
+        // Growth function, `t_n` is expected numbers of samples from top `n` in `max_iterations`.
+        let mut n = m;
+        let mut t_n = (0..m).fold(max_iterations.max(1) as f64, |acc, i| {
+            acc * (n - i) as f64 / (nb_data - i) as f64
+        });
+        let mut t_n_prime = 1usize;
+
+        let max_skip = max_iterations * 100;
+        let mut skipped = 0;
+        let mut t = 0;
+        let mut k = f32::MAX;
+        let mut best: Option<(R::CoefficientsType, usize)> = None;
+        while self.iterations < max_iterations
+            && skipped <= max_skip
+            && (self.iterations as f32) < k
+        {
+            t += 1;
+            if t > t_n_prime && n < nb_data {
+                let t_n_next = t_n * (n + 1) as f64 / (n + 1 - m) as f64;
+                t_n_prime += (t_n_next - t_n).ceil() as usize;
+                t_n = t_n_next;
+                n += 1;
+            }
+            // Top `n - 1` with the `n`th, or any of top `n` if behind of growth.
+            let samples = if t_n_prime < t {
+                sample_from(rng, &ordering[..n], m)
+            } else {
+                let mut samples = sample_from(rng, &ordering[..n - 1], m - 1);
+                samples.push(ordering[n - 1]);
+                samples
+            };
+            let Ok(samples) = R::SampleIdxType::try_from(samples) else {
+                return false;
+            };
+            let Ok(coefficients) = model.compute_model_coefficients(&samples) else {
+                skipped += 1;
+                continue;
+            };
+            self.iterations += 1;
+            let nb_inlier = model.count_indices_within_tolerance(&coefficients, threshold);
+            if best.as_ref().is_none_or(|(_, b)| nb_inlier > *b) {
+                k = adaptive_iterations(probability, nb_inlier, nb_data, m).max(1.);
+                best = Some((coefficients, nb_inlier));
+            }
+        }
+
+        let Some((coefficient, nb_inlier)) = best else {
+            return false;
+        };
+        self.score = nb_inlier as f32;
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            threshold,
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
+
+        true
+    }
+}
@@ -1,34 +1,54 @@
-use std::sync::{Arc, Mutex};
-
 use f3l_core::{
+    rayon::prelude::*,
     serde::{self, Deserialize, Serialize},
     BasicFloat,
 };
 
-use super::{SacAlgorithm, SacAlgorithmGetSet, SacAlgorithmParameter};
+use super::{adaptive_iterations, refine, SacAlgorithm, SacAlgorithmGetSet, SacAlgorithmParameter};
 use crate::sac_model::SacModel;
+use rand::{Rng, SeedableRng};
 
 /// Ransac
 ///
+/// Score is numbers of inliers.
+///
 /// See [`SacAlgorithmParameter`]
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
 pub struct SacRansac {
     pub parameter: SacAlgorithmParameter,
     pub inliers: Vec<usize>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub iterations: usize,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub score: f32,
 }
 
 impl SacAlgorithmGetSet for SacRansac {
     fn with_parameter(parameter: SacAlgorithmParameter) -> Self {
         Self {
             parameter,
-            inliers: vec![],
+            ..Default::default()
         }
     }
 
+    fn get_parameter(&self) -> &SacAlgorithmParameter {
+        &self.parameter
+    }
+
     fn get_inliers(&self) -> &Vec<usize> {
         &self.inliers
     }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_score(&self) -> f32 {
+        self.score
+    }
 }
 
 impl<'a, P: Copy, T, R> SacAlgorithm<'a, P, T, R> for SacRansac
@@ -40,96 +60,85 @@ where
     /// Compute Ransac
     ///
     /// End with reach `max_iteration` or probability more than numbers of iteration.
-    fn compute(&mut self, model: &mut R) -> bool {
+    ///
+    /// Each thread runs its share of `max_iteration` with its own generator seeded from `rng`,
+    /// the best of threads wins, earlier thread first if tie.
+    /// Single thread draws from `rng` directly.
+    fn compute_with_rng<G>(&mut self, model: &mut R, rng: &mut G) -> bool
+    where
+        G: Rng + SeedableRng + Send,
+    {
         let SacAlgorithmParameter {
             probability,
             threshold,
             max_iterations,
             threads,
+            ..
         } = self.parameter;
-
-        let max_skip = max_iterations * 100;
-
-        let skipped = Arc::new(Mutex::new(0usize));
-        let iterations = Arc::new(Mutex::new(0usize));
-        let nb_best_inliers = Arc::new(Mutex::new(0usize));
-
+        let threads = threads.max(1);
         let nb_data = model.data_len();
-        let log_probability = (1. - probability).ln();
-        let one_over_indices = 1. / nb_data as f32;
         let nb_sample = R::NB_SAMPLE;
 
-        let coefficient = model.get_coefficient();
-        let coefficient = Arc::new(Mutex::new(coefficient));
-
         // Do closure in each thread.
-        let closure = || loop {
-            {
-                let lock = iterations.lock().unwrap();
-                if *lock > max_iterations {
-                    break;
-                }
-                let lock = skipped.lock().unwrap();
-                if *lock > max_skip {
-                    break;
-                }
-            }
-            let samples = model.get_random_samples();
-
-            let result = model.compute_model_coefficients(&samples);
-            if result.is_err() {
-                let mut lock = skipped.lock().unwrap();
-                *lock += 1;
-                break;
-            }
-            let result = result.unwrap();
-            let nb_inlier =
-                model.count_indices_within_tolerance(&result, T::from(threshold).unwrap());
-
-            let mut k: f32 = 0f32;
-            {
-                let mut lock = nb_best_inliers.lock().unwrap();
-                if nb_inlier > *lock {
-                    *lock = nb_inlier;
-
-                    let mut lock = coefficient.lock().unwrap();
-                    *lock = result;
-                    let w = nb_inlier as f32 * one_over_indices;
-                    let mut p_outlier = 1. - w.powi(nb_sample as i32);
-                    p_outlier = p_outlier.max(f32::EPSILON);
-                    p_outlier = p_outlier.min(1. - f32::EPSILON);
-                    k = log_probability / p_outlier.ln();
-                }
-            }
-            {
-                let mut lock = iterations.lock().unwrap();
-                *lock += 1;
-
-                if k > 1. && *lock as f32 > k {
-                    break;
+        let closure = |stream: usize, rng: &mut G| {
+            let share = max_iterations / threads + usize::from(stream < max_iterations % threads);
+            let max_skip = share * 100;
+            let mut iterations = 0usize;
+            let mut skipped = 0usize;
+            let mut k = f32::MAX;
+            let mut best: Option<(R::CoefficientsType, usize)> = None;
+            // Estimated iterations of all threads.
+            while iterations < share && skipped <= max_skip && ((iterations * threads) as f32) < k {
+                let samples = model.get_random_samples(rng);
+                let Ok(result) = model.compute_model_coefficients(&samples) else {
+                    skipped += 1;
+                    continue;
+                };
+                iterations += 1;
+                let nb_inlier =
+                    model.count_indices_within_tolerance(&result, T::from(threshold).unwrap());
+                if best.as_ref().is_none_or(|(_, b)| nb_inlier > *b) {
+                    k = adaptive_iterations(probability, nb_inlier, nb_data, nb_sample).max(1.);
+                    best = Some((result, nb_inlier));
                 }
             }
+            (best, iterations)
         };
 
         // Use rayon parallel when threads > 1
-        if threads > 1 {
+        let results = if threads > 1 {
             let pool = f3l_core::rayon::ThreadPoolBuilder::new()
                 .num_threads(threads)
                 .build()
                 .unwrap();
-            pool.in_place_scope(|s| {
-                for _ in 0..threads {
-                    s.spawn(|_| closure());
-                }
-            });
+            let mut rngs = (0..threads)
+                .map(|_| G::seed_from_u64(rng.next_u64()))
+                .collect::<Vec<_>>();
+            pool.install(|| {
+                rngs.par_iter_mut()
+                    .enumerate()
+                    .map(|(stream, rng)| closure(stream, rng))
+                    .collect::<Vec<_>>()
+            })
         } else {
-            closure();
-        }
+            vec![closure(0, rng)]
+        };
 
-        let coefficient = coefficient.lock().unwrap();
-        model.set_coefficient(&coefficient);
-        self.inliers =
-            model.select_indices_within_tolerance(&coefficient, T::from(threshold).unwrap());
+        self.iterations = results.iter().map(|(_, iterations)| iterations).sum();
+        let (coefficient, nb_best_inliers) = results
+            .into_iter()
+            .filter_map(|(best, _)| best)
+            .reduce(|a, b| if b.1 > a.1 { b } else { a })
+            .unwrap_or((model.get_coefficient(), 0));
+        self.score = nb_best_inliers as f32;
+        let (refined, inliers) = refine(
+            model,
+            &coefficient,
+            T::from(threshold).unwrap(),
+            self.parameter.refine_iterations,
+        );
+        model.set_coefficient(refined.as_ref().unwrap_or(&coefficient));
+        self.inliers = inliers;
 
         true
     }
@@ -4,14 +4,31 @@ use f3l_core::{
 };
 use rand::Rng;
 
+mod least_squares;
+mod sac_model_circle2d;
 mod sac_model_circle3d;
+mod sac_model_cone;
+mod sac_model_constrained_plane;
+mod sac_model_cylinder;
+mod sac_model_ellipse2d;
 mod sac_model_line;
+mod sac_model_line2d;
+mod sac_model_normal_plane;
 mod sac_model_plane;
 mod sac_model_sphere;
+mod sac_model_torus;
+pub use sac_model_circle2d::*;
 pub use sac_model_circle3d::*;
+pub use sac_model_cone::*;
+pub use sac_model_constrained_plane::*;
+pub use sac_model_cylinder::*;
+pub use sac_model_ellipse2d::*;
 pub use sac_model_line::*;
+pub use sac_model_line2d::*;
+pub use sac_model_normal_plane::*;
 pub use sac_model_plane::*;
 pub use sac_model_sphere::*;
+pub use sac_model_torus::*;
 
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "self::serde")]
@@ -21,6 +38,15 @@ pub enum SacModelType {
     SacModelLine,
     SacModelCircle3d,
     SacModelSphere,
+    SacModelCylinder,
+    SacModelCone,
+    SacModelTorus,
+    SacModelLine2d,
+    SacModelCircle2d,
+    SacModelEllipse2d,
+    SacModelPerpendicularPlane,
+    SacModelParallelPlane,
+    SacModelNormalPlane,
 }
 
 pub trait ModelCoefficient {
@@ -33,7 +59,11 @@ pub trait ModelCoefficient {
 ///
 /// Implement this to Customize model.
 /// Currently support [`SacModelPlane`], [`SacModelSphere`],
-/// [`SacModelLine`], [`SacModelCircle3d`]
+/// [`SacModelLine`], [`SacModelCircle3d`],
+/// [`SacModelCylinder`], [`SacModelCone`], [`SacModelTorus`] (need normals),
+/// [`SacModelLine2d`], [`SacModelCircle2d`], [`SacModelEllipse2d`] (on `XY`),
+/// [`SacModelPerpendicularPlane`], [`SacModelParallelPlane`] (constrained by axis),
+/// [`SacModelNormalPlane`] (need normals)
 pub trait SacModel<'a, P: Copy, T: BasicFloat> {
     type SampleIdxType;
     type CoefficientsType;
@@ -53,16 +83,9 @@ pub trait SacModel<'a, P: Copy, T: BasicFloat> {
     fn data_len(&self) -> usize {
         self.samples().len()
     }
-    /// Random numbers of indices by `NB_SAMPLE`.
-    fn get_random_sample_id(&self) -> Vec<usize> {
-        let mut rng = rand::thread_rng();
-        let nb = self.data_len();
-        use std::collections::HashSet;
-        let mut set = HashSet::new();
-        while set.len() < Self::NB_SAMPLE {
-            set.insert(rng.gen_range(0..nb));
-        }
-        set.into_iter().collect()
+    /// Random numbers of indices by `NB_SAMPLE`, drawn from `rng`.
+    fn get_random_sample_id<G: Rng + ?Sized>(&self, rng: &mut G) -> Vec<usize> {
+        rand::seq::index::sample(rng, self.data_len(), Self::NB_SAMPLE).into_vec()
     }
     /// Returns a distance list and uses `coefficients` to calculate the distance from data to the model.
     fn get_distance_to_model(&self, coefficients: &Self::CoefficientsType) -> Vec<T> {
@@ -96,8 +119,8 @@ pub trait SacModel<'a, P: Copy, T: BasicFloat> {
     }
     /// Return distance between target `point` and `coefficients`.
     fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T;
-    /// Get array of indices of samples.
-    fn get_random_samples(&self) -> Self::SampleIdxType;
+    /// Get array of indices of samples, drawn from `rng`.
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType;
     /// Return `CoefficientsType` of samples.
     ///
     /// # Err
@@ -108,4 +131,14 @@ pub trait SacModel<'a, P: Copy, T: BasicFloat> {
         &self,
         samples: &Self::SampleIdxType,
     ) -> Result<Self::CoefficientsType, String>;
+    /// Re-estimate coefficients from all `inliers` by least squares, start from `coefficients`.
+    ///
+    /// `None` if not supported or failed. Default is not supported.
+    fn refine_model_coefficients(
+        &self,
+        _inliers: &[usize],
+        _coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        None
+    }
 }
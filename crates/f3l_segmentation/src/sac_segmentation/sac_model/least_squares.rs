@@ -0,0 +1,166 @@
+//! Least squares fitting used by refinement of models, computed in `f64`.
+use f3l_core::{
+    compute_covariance_matrix, jacobi_eigen_square_n, pivoted_solve, BasicFloat, Eigen,
+};
+
+/// `inliers` of `data` in `f64`.
+pub(crate) fn points_f64<P, T>(data: &[P], inliers: &[usize]) -> Vec<[f64; 3]>
+where
+    P: Into<[T; 3]> + Copy,
+    T: BasicFloat,
+{
+    inliers
+        .iter()
+        .map(|&i| {
+            let p: [T; 3] = data[i].into();
+            p.map(|v| v.to_f64().unwrap())
+        })
+        .collect()
+}
+
+#[inline]
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Centroid and eigens of covariance, sorted by eigenvalue ascending.
+pub(crate) fn pca<const D: usize>(points: &[[f64; D]]) -> ([f64; D], [Eigen<f64, D>; D]) {
+    let (cov, centroid) = compute_covariance_matrix(points);
+    let mut eigen = jacobi_eigen_square_n(cov);
+    eigen.sort_by(|a, b| a.eigenvalue.total_cmp(&b.eigenvalue));
+    (centroid, eigen)
+}
+
+/// Minimize sum of squared `residuals` from `x` by Levenberg-Marquardt,
+/// Jacobian is computed by forward difference.
+pub(crate) fn levenberg_marquardt<const N: usize, F>(
+    mut x: [f64; N],
+    residuals: F,
+    iterations: usize,
+) -> [f64; N]
+where
+    F: Fn(&[f64; N]) -> Vec<f64>,
+{
+    let cost = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>();
+    let mut r = residuals(&x);
+    let mut current = cost(&r);
+    let mut lambda = 1e-3;
+    for _ in 0..iterations {
+        let jacobian = (0..N)
+            .map(|j| {
+                let h = 1e-7 * x[j].abs().max(1.);
+                let mut xh = x;
+                xh[j] += h;
+                residuals(&xh)
+                    .iter()
+                    .zip(&r)
+                    .map(|(a, b)| (a - b) / h)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut jtj = [[0f64; N]; N];
+        let mut jtr = [0f64; N];
+        for i in 0..N {
+            for j in i..N {
+                jtj[i][j] = dot(&jacobian[i], &jacobian[j]);
+                jtj[j][i] = jtj[i][j];
+            }
+            jtr[i] = -dot(&jacobian[i], &r);
+        }
+
+        let mut improved = false;
+        for _ in 0..10 {
+            let mut damped = jtj;
+            (0..N).for_each(|i| damped[i][i] += lambda * (jtj[i][i] + 1e-12));
+            let Some(step) = pivoted_solve(&damped, &jtr, 1e-300) else {
+                lambda *= 10.;
+                continue;
+            };
+            let mut next = x;
+            (0..N).for_each(|i| next[i] += step[i]);
+            let next_r = residuals(&next);
+            let next_cost = cost(&next_r);
+            if next_cost < current {
+                let converged = current - next_cost <= 1e-15 * current.max(1e-300);
+                x = next;
+                r = next_r;
+                current = next_cost;
+                lambda = (lambda * 0.1).max(1e-12);
+                improved = !converged;
+                break;
+            }
+            lambda *= 10.;
+        }
+        if !improved {
+            break;
+        }
+    }
+    x
+}
+
+/// Sphere, or circle in 2D (`D <= 3`), by algebraic fit
+/// `|p|^2 + a . p + b = 0` on centered points, then geometric fit by [`levenberg_marquardt`].
+///
+/// Return center and radius.
+pub(crate) fn fit_sphere<const D: usize>(points: &[[f64; D]]) -> Option<([f64; D], f64)> {
+    if points.len() <= D {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mut centroid = [0f64; D];
+    points
+        .iter()
+        .for_each(|p| (0..D).for_each(|i| centroid[i] += p[i] / n));
+    let centered = points
+        .iter()
+        .map(|p| {
+            let mut q = *p;
+            (0..D).for_each(|i| q[i] -= centroid[i]);
+            q
+        })
+        .collect::<Vec<_>>();
+
+    // Normal equations of `[p, 1] . [a, b] = -|p|^2`, at most 4 unknowns.
+    let m = D + 1;
+    let mut ata = [[0f64; 4]; 4];
+    let mut atb = [0f64; 4];
+    centered.iter().for_each(|p| {
+        let row = (0..m)
+            .map(|i| if i < D { p[i] } else { 1. })
+            .collect::<Vec<_>>();
+        let rhs = -p.iter().map(|v| v * v).sum::<f64>();
+        for i in 0..m {
+            for j in 0..m {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * rhs;
+        }
+    });
+    // Pad unused unknown to keep system square.
+    (m..4).for_each(|i| ata[i][i] = 1.);
+    let x = pivoted_solve(&ata, &atb, 1e-300)?;
+    let mut center = [0f64; D];
+    (0..D).for_each(|i| center[i] = -x[i] / 2.);
+    let r2 = center.iter().map(|v| v * v).sum::<f64>() - x[D];
+    if r2 <= 0. {
+        return None;
+    }
+
+    let mut init = [0f64; 4];
+    (0..D).for_each(|i| init[i] = center[i]);
+    init[D] = r2.sqrt();
+    let fitted = levenberg_marquardt(
+        init,
+        |x| {
+            centered
+                .iter()
+                .map(|p| (0..D).map(|i| (p[i] - x[i]).powi(2)).sum::<f64>().sqrt() - x[D])
+                .collect()
+        },
+        50,
+    );
+    let mut center = [0f64; D];
+    (0..D).for_each(|i| center[i] = fitted[i] + centroid[i]);
+    let radius = fitted[D].abs();
+    radius.is_finite().then_some((center, radius))
+}
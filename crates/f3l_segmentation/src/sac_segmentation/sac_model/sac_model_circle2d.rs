@@ -0,0 +1,142 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+
+use rand::Rng;
+
+use super::{
+    least_squares::{fit_sphere, points_f64},
+    ModelCoefficient, SacModel,
+};
+
+/// Compute 2d circle on `XY`, `Z` is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelCircle2d<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Center of Circle
+    /// - Radius
+    pub coefficients: ([T; 2], T),
+    data: Option<&'a [P]>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Circle2dCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 2], T),
+}
+
+impl<T: BasicFloat> ModelCoefficient for Circle2dCoefficient<T> {
+    type CoefficientsType = ([T; 2], T);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelCircle2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], T::zero()),
+            data: None,
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], T::zero()),
+            data: Some(data),
+        }
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelCircle2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 3];
+
+    type CoefficientsType = ([T; 2], T);
+
+    /// 3 points (not collinear) be a circle.
+    const NB_SAMPLE: usize = 3;
+
+    /// center XY, radius.
+    const NB_COEFFICIENTS: usize = 3;
+
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (c, r) = coefficients;
+        ([p[0], p[1]].distance_between(c) - *r).abs()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1], sample[2]]
+    }
+
+    /// Ref: [Circumcircle](https://en.wikipedia.org/wiki/Circumcircle#Cartesian_coordinates_2)
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let [a, b, c]: [[T; 3]; 3] = if let Some(data) = self.data {
+            samples.map(|i| data[i].into())
+        } else {
+            return Err("Data corrupted.".to_owned());
+        };
+        // Relative to `a`, for precision.
+        let (bx, by) = (b[0] - a[0], b[1] - a[1]);
+        let (cx, cy) = (c[0] - a[0], c[1] - a[1]);
+        let d = T::from(2).unwrap() * (bx * cy - by * cx);
+        if d.abs() <= T::epsilon() * (bx * bx + by * by + cx * cx + cy * cy) {
+            return Err("Collinear".to_owned());
+        }
+        let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+        let ux = (cy * b2 - by * c2) / d;
+        let uy = (bx * c2 - cx * b2) / d;
+
+        Ok(([a[0] + ux, a[1] + uy], (ux * ux + uy * uy).sqrt()))
+    }
+
+    /// Algebraic fit on `XY`, then geometric fit by Levenberg-Marquardt.
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        _coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers)
+            .into_iter()
+            .map(|p| [p[0], p[1]])
+            .collect::<Vec<_>>();
+        let (center, radius) = fit_sphere(&points)?;
+        Some((
+            center.map(|v| T::from(v).unwrap()),
+            T::from(radius).unwrap(),
+        ))
+    }
+}
@@ -5,7 +5,12 @@ use f3l_core::{
 };
 use std::marker::PhantomData;
 
-use super::{ModelCoefficient, SacModel};
+use rand::Rng;
+
+use super::{
+    least_squares::{fit_sphere, pca, points_f64},
+    ModelCoefficient, SacModel,
+};
 
 /// Compute a 3d circle, not sphere.
 #[derive(Debug, Clone, Default)]
@@ -112,8 +117,8 @@ where
         self.data.unwrap()
     }
 
-    fn get_random_samples(&self) -> Self::SampleIdxType {
-        let sample = self.get_random_sample_id();
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
         [sample[0], sample[1], sample[2]]
     }
 
@@ -143,4 +148,42 @@ where
             vec![]
         }
     }
+
+    /// 1. Plane of circle by PCA.
+    /// 2. Circle of inliers projected on the plane, by algebraic fit,
+    ///    then geometric fit by Levenberg-Marquardt.
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers);
+        let (centroid, eigen) = pca(&points);
+        let mut normal = eigen[0].eigenvector.normalized();
+        // Keep orientation of `coefficients`.
+        if normal.dot(&coefficients.1.map(|v| v.to_f64().unwrap())) < 0. {
+            normal = normal.map(|v| -v);
+        }
+        let u = eigen[2].eigenvector.normalized();
+        let v = normal.cross(&u);
+        let projected = points
+            .iter()
+            .map(|p| {
+                let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+                [d.dot(&u), d.dot(&v)]
+            })
+            .collect::<Vec<_>>();
+        let (center, radius) = fit_sphere(&projected)?;
+        let center = [0, 1, 2].map(|i| centroid[i] + center[0] * u[i] + center[1] * v[i]);
+        (center.iter().chain(&normal).all(|v| v.is_finite())).then(|| {
+            (
+                center.map(|v| T::from(v).unwrap()),
+                normal.map(|v| T::from(v).unwrap()),
+                T::from(radius).unwrap(),
+            )
+        })
+    }
 }
@@ -0,0 +1,180 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+
+use rand::Rng;
+
+use super::{ModelCoefficient, SacModel};
+
+/// Compute a cone, need normals.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelCone<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Apex
+    /// - Direction of axis, from apex into cone
+    /// - Half opening angle in radians
+    pub coefficients: ([T; 3], [T; 3], T),
+    data: Option<&'a [P]>,
+    normals: Vec<Option<[T; 3]>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ConeCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 3], [T; 3], T),
+}
+
+impl<T: BasicFloat> ModelCoefficient for ConeCoefficient<T> {
+    type CoefficientsType = ([T; 3], [T; 3], T);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelCone<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero()),
+            data: None,
+            normals: vec![],
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero()),
+            data: Some(data),
+            normals: vec![],
+        }
+    }
+
+    /// Normals indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelCone<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 3];
+
+    type CoefficientsType = ([T; 3], [T; 3], T);
+
+    /// 3 points with normals.
+    const NB_SAMPLE: usize = 3;
+
+    /// apex XYZ, direction XYZ, angle.
+    const NB_COEFFICIENTS: usize = 7;
+
+    /// In plane of axis and point, distance to the generatrix, or to apex if behind it.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (apex, dir, angle) = coefficients;
+        let dir = dir.normalized();
+        let v = [p[0] - apex[0], p[1] - apex[1], p[2] - apex[2]];
+        let h = v.dot(&dir);
+        let r = v.cross(&dir).len();
+        let (sin, cos) = angle.sin_cos();
+        if h * cos + r * sin < T::zero() {
+            v.len()
+        } else {
+            (r * cos - h * sin).abs()
+        }
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1], sample[2]]
+    }
+
+    /// 1. Apex is the intersection of 3 tangent planes.
+    /// 2. Unit vectors from apex to points end on a circle, whose normal is the axis.
+    /// 3. Angle is mean of angles between axis and the unit vectors.
+    ///
+    /// Ref: PCL `SampleConsensusModelCone`.
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let points: [[T; 3]; 3] = if let Some(data) = self.data {
+            samples.map(|i| data[i].into())
+        } else {
+            return Err("Data corrupted.".to_owned());
+        };
+        let mut normals = [[T::zero(); 3]; 3];
+        for (k, &i) in samples.iter().enumerate() {
+            match self.normals.get(i) {
+                Some(Some(n)) => normals[k] = n.normalized(),
+                _ => return Err("Normals missing.".to_owned()),
+            }
+        }
+        let [n1, n2, n3] = normals;
+        let (o23, o31, o12) = (n2.cross(&n3), n3.cross(&n1), n1.cross(&n2));
+        let denom = n1.dot(&o23);
+        if denom.abs() <= T::from(1e-6).unwrap() {
+            return Err("Tangent planes do not meet at a point.".to_owned());
+        }
+        let [d1, d2, d3] = [0, 1, 2].map(|k| normals[k].dot(&points[k]));
+        let apex = [0, 1, 2].map(|i| (d1 * o23[i] + d2 * o31[i] + d3 * o12[i]) / denom);
+
+        let mut units = [[T::zero(); 3]; 3];
+        for k in 0..3 {
+            let v = [
+                points[k][0] - apex[0],
+                points[k][1] - apex[1],
+                points[k][2] - apex[2],
+            ];
+            if v.len() <= T::epsilon() {
+                return Err("Sample at apex.".to_owned());
+            }
+            units[k] = v.normalized();
+        }
+        let e1 = [0, 1, 2].map(|i| units[1][i] - units[0][i]);
+        let e2 = [0, 1, 2].map(|i| units[2][i] - units[0][i]);
+        let mut dir = e1.cross(&e2);
+        if dir.len() <= T::epsilon() {
+            return Err("Degenerate samples.".to_owned());
+        }
+        dir.normalize();
+        // Axis points into cone.
+        if units
+            .iter()
+            .map(|u| u.dot(&dir))
+            .fold(T::zero(), |a, b| a + b)
+            < T::zero()
+        {
+            dir = dir.map(|v| -v);
+        }
+        let angle = units
+            .iter()
+            .map(|u| u.dot(&dir).max(-T::one()).min(T::one()).acos())
+            .fold(T::zero(), |a, b| a + b)
+            / T::from(3).unwrap();
+        Ok((apex, dir, angle))
+    }
+}
@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+
+use super::{plane_axis_angle, ModelCoefficient, SacModel, SacModelPlane};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+use rand::Rng;
+
+/// How a constrained plane is oriented to its axis.
+pub trait PlaneOrientation {
+    /// Reason of rejected hypotheses.
+    const REJECTED: &'static str;
+
+    /// Deviation of plane, from acute angle between its normal and axis.
+    fn deviation<T: BasicFloat>(angle: T) -> T;
+}
+
+/// Normal of plane along axis, like gravity for floors.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Perpendicular;
+
+/// Plane containing direction of axis, like gravity for walls.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Parallel;
+
+impl PlaneOrientation for Perpendicular {
+    const REJECTED: &'static str = "Not perpendicular to axis";
+
+    fn deviation<T: BasicFloat>(angle: T) -> T {
+        angle
+    }
+}
+
+impl PlaneOrientation for Parallel {
+    const REJECTED: &'static str = "Not parallel to axis";
+
+    fn deviation<T: BasicFloat>(angle: T) -> T {
+        T::from(std::f64::consts::FRAC_PI_2).unwrap() - angle
+    }
+}
+
+/// Compute a 3d plane model, perpendicular to `axis`.
+pub type SacModelPerpendicularPlane<'a, P, T> = SacModelConstrainedPlane<'a, P, T, Perpendicular>;
+
+/// Compute a 3d plane model, parallel to `axis`.
+pub type SacModelParallelPlane<'a, P, T> = SacModelConstrainedPlane<'a, P, T, Parallel>;
+
+/// Compute a 3d plane model, oriented to `axis` by `O`.
+///
+/// Hypotheses deviating more than `eps_angle` are rejected.
+/// If `axis` is zero or `eps_angle` is not positive, it acts as [`SacModelPlane`].
+///
+/// Coefficients: `coefficients_0` x + `coefficients_1` y + `coefficients_2` z + `coefficients_3` = 0
+#[derive(Debug, Clone, Default)]
+pub struct SacModelConstrainedPlane<'a, P, T: BasicFloat, O: PlaneOrientation>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub coefficients: [T; 4],
+    pub axis: [T; 3],
+    /// Max deviation in radians.
+    pub eps_angle: T,
+    plane: SacModelPlane<'a, P, T>,
+    orientation: PhantomData<O>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct PerpendicularPlaneCoefficient<T: BasicFloat> {
+    pub coefficients: [T; 4],
+    pub axis: [T; 3],
+    pub eps_angle: T,
+}
+
+impl<T: BasicFloat> ModelCoefficient for PerpendicularPlaneCoefficient<T> {
+    type CoefficientsType = [T; 4];
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ParallelPlaneCoefficient<T: BasicFloat> {
+    pub coefficients: [T; 4],
+    pub axis: [T; 3],
+    pub eps_angle: T,
+}
+
+impl<T: BasicFloat> ModelCoefficient for ParallelPlaneCoefficient<T> {
+    type CoefficientsType = [T; 4];
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat, O: PlaneOrientation> SacModelConstrainedPlane<'a, P, T, O>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: [T::zero(); 4],
+            axis: [T::zero(); 3],
+            eps_angle: T::zero(),
+            plane: SacModelPlane::new(),
+            orientation: PhantomData,
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: [T::zero(); 4],
+            axis: [T::zero(); 3],
+            eps_angle: T::zero(),
+            plane: SacModelPlane::with_data(data),
+            orientation: PhantomData,
+        }
+    }
+
+    pub fn set_axis(&mut self, axis: [T; 3]) {
+        self.axis = axis;
+    }
+
+    pub fn set_eps_angle(&mut self, eps_angle: T) {
+        self.eps_angle = eps_angle;
+    }
+
+    fn is_valid(&self, coefficient: &[T; 4]) -> bool {
+        match plane_axis_angle(coefficient, &self.axis, self.eps_angle) {
+            Some(angle) => O::deviation(angle) <= self.eps_angle,
+            None => true,
+        }
+    }
+}
+
+impl<'a, P, T: BasicFloat, O: PlaneOrientation> SacModel<'a, P, T>
+    for SacModelConstrainedPlane<'a, P, T, O>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 3];
+
+    type CoefficientsType = [T; 4];
+
+    /// Any 3 points(not overlay or parallel) span a plane.
+    const NB_SAMPLE: usize = 3;
+
+    /// `coefficients_0` x + `coefficients_1` y + `coefficients_2` z + `coefficients_3` = 0
+    const NB_COEFFICIENTS: usize = 4;
+
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        SacModelPlane::<P, T>::compute_point_to_model(p, coefficients)
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.plane.set_data(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.plane.samples()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        self.plane.get_random_samples(rng)
+    }
+
+    /// Plane of samples, rejected if it deviates from orientation to `axis` more than `eps_angle`.
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let coefficient = self.plane.compute_model_coefficients(samples)?;
+        if !self.is_valid(&coefficient) {
+            return Err(O::REJECTED.to_owned());
+        }
+        Ok(coefficient)
+    }
+
+    /// Plane by PCA, rejected like samples.
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        let refined = self
+            .plane
+            .refine_model_coefficients(inliers, coefficients)?;
+        self.is_valid(&refined).then_some(refined)
+    }
+}
@@ -0,0 +1,201 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+
+use rand::Rng;
+
+use super::{
+    least_squares::{levenberg_marquardt, points_f64},
+    ModelCoefficient, SacModel,
+};
+
+/// Compute a cylinder, need normals.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelCylinder<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Point on axis
+    /// - Direction of axis
+    /// - Radius
+    pub coefficients: ([T; 3], [T; 3], T),
+    data: Option<&'a [P]>,
+    normals: Vec<Option<[T; 3]>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct CylinderCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 3], [T; 3], T),
+}
+
+impl<T: BasicFloat> ModelCoefficient for CylinderCoefficient<T> {
+    type CoefficientsType = ([T; 3], [T; 3], T);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelCylinder<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero()),
+            data: None,
+            normals: vec![],
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero()),
+            data: Some(data),
+            normals: vec![],
+        }
+    }
+
+    /// Normals indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelCylinder<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 2];
+
+    type CoefficientsType = ([T; 3], [T; 3], T);
+
+    /// 2 points with normals.
+    const NB_SAMPLE: usize = 2;
+
+    /// point XYZ, direction XYZ, radius.
+    const NB_COEFFICIENTS: usize = 7;
+
+    /// Distance to axis minus radius.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (c, dir, r) = coefficients;
+        let v = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        (v.cross(&dir.normalized()).len() - *r).abs()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1]]
+    }
+
+    /// Normal lines of points on cylinder intersect the axis perpendicularly.
+    /// 1. Direction of axis is `n1 x n2`.
+    /// 2. Point on axis is the closest point between normal lines.
+    ///
+    /// Ref: PCL `SampleConsensusModelCylinder`.
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let [i1, i2] = *samples;
+        let (p1, p2): ([T; 3], [T; 3]) = if let Some(data) = self.data {
+            (data[i1].into(), data[i2].into())
+        } else {
+            return Err("Data corrupted.".to_owned());
+        };
+        let (n1, n2) = match (self.normals.get(i1), self.normals.get(i2)) {
+            (Some(Some(n1)), Some(Some(n2))) => (n1.normalized(), n2.normalized()),
+            _ => return Err("Normals missing.".to_owned()),
+        };
+        let dir = n1.cross(&n2);
+        if dir.len() <= T::from(1e-4).unwrap() {
+            return Err("Parallel normals.".to_owned());
+        }
+        let dir = dir.normalized();
+
+        // Closest points of `p1 + s n1` and `p2 + t n2`.
+        let w = [p1[0] - p2[0], p1[1] - p2[1], p1[2] - p2[2]];
+        let (b, d, e) = (n1.dot(&n2), n1.dot(&w), n2.dot(&w));
+        let denom = T::one() - b * b;
+        let s = (b * e - d) / denom;
+        let c = [p1[0] + s * n1[0], p1[1] + s * n1[1], p1[2] + s * n1[2]];
+
+        let radius = [p1[0] - c[0], p1[1] - c[1], p1[2] - c[2]].cross(&dir).len();
+        Ok((c, dir, radius))
+    }
+
+    /// Levenberg-Marquardt of distances to surface, start from `coefficients`.
+    /// Point on axis is moved to the closest one to centroid of inliers.
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_COEFFICIENTS {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers);
+        let (c, dir, r) = coefficients;
+        let (c, dir) = (
+            c.map(|v| v.to_f64().unwrap()),
+            dir.map(|v| v.to_f64().unwrap()),
+        );
+        let init = [
+            c[0],
+            c[1],
+            c[2],
+            dir[0],
+            dir[1],
+            dir[2],
+            r.to_f64().unwrap(),
+        ];
+        let x = levenberg_marquardt(
+            init,
+            |x| {
+                let d = [x[3], x[4], x[5]];
+                let len = d.len();
+                points
+                    .iter()
+                    .map(|p| {
+                        let v = [p[0] - x[0], p[1] - x[1], p[2] - x[2]];
+                        v.cross(&d).len() / len - x[6]
+                    })
+                    .collect()
+            },
+            50,
+        );
+        let dir = [x[3], x[4], x[5]].normalized();
+        let n = points.len() as f64;
+        let centroid = points.iter().fold([0f64; 3], |acc, p| {
+            [acc[0] + p[0] / n, acc[1] + p[1] / n, acc[2] + p[2] / n]
+        });
+        let t = [centroid[0] - x[0], centroid[1] - x[1], centroid[2] - x[2]].dot(&dir);
+        let c = [0, 1, 2].map(|i| x[i] + t * dir[i]);
+        (c.iter().chain(&dir).chain(&[x[6]]).all(|v| v.is_finite())).then(|| {
+            (
+                c.map(|v| T::from(v).unwrap()),
+                dir.map(|v| T::from(v).unwrap()),
+                T::from(x[6].abs()).unwrap(),
+            )
+        })
+    }
+}
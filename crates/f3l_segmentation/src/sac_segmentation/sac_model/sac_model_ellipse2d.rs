@@ -0,0 +1,258 @@
+use f3l_core::{
+    pivoted_rref,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat,
+};
+
+use rand::Rng;
+
+use super::{ModelCoefficient, SacModel};
+
+/// Compute 2d ellipse on `XY`, `Z` is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelEllipse2d<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Center
+    /// - Semi-major and semi-minor axis
+    /// - Angle from `X` to major axis, in radians
+    pub coefficients: ([T; 2], [T; 2], T),
+    data: Option<&'a [P]>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Ellipse2dCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 2], [T; 2], T),
+}
+
+impl<T: BasicFloat> ModelCoefficient for Ellipse2dCoefficient<T> {
+    type CoefficientsType = ([T; 2], [T; 2], T);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelEllipse2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], [T::zero(); 2], T::zero()),
+            data: None,
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], [T::zero(); 2], T::zero()),
+            data: Some(data),
+        }
+    }
+
+    /// Null vector of `5 x 6` matrix by [`pivoted_rref`], `None` if rank is not 5.
+    fn null_space(mut m: [[f64; 6]; 5]) -> Option<[f64; 6]> {
+        let pivots = pivoted_rref(&mut m, 1e-12);
+        if pivots.len() < 5 {
+            return None;
+        }
+        let free = (0..6).find(|c| !pivots.contains(c)).unwrap();
+        let mut v = [0f64; 6];
+        v[free] = 1.;
+        pivots
+            .iter()
+            .zip(&m)
+            .for_each(|(&c, row)| v[c] = -row[free]);
+        Some(v)
+    }
+
+    /// Ref: Eberly, Distance from a Point to an Ellipse, an Ellipsoid, or a Hyperellipsoid.
+    ///
+    /// `e0 >= e1 > 0`, `y0, y1 >= 0`.
+    fn distance_in_quadrant(e0: f64, e1: f64, y0: f64, y1: f64) -> f64 {
+        if y1 > 0. {
+            if y0 > 0. {
+                let (z0, z1) = (y0 / e0, y1 / e1);
+                let g = z0 * z0 + z1 * z1 - 1.;
+                if g == 0. {
+                    return 0.;
+                }
+                let r0 = (e0 / e1).powi(2);
+                // Bisection of root of `(r0 z0 / (s + r0))^2 + (z1 / (s + 1))^2 - 1`.
+                let n0 = r0 * z0;
+                let mut s0 = z1 - 1.;
+                let mut s1 = if g < 0. {
+                    0.
+                } else {
+                    (n0 * n0 + z1 * z1).sqrt() - 1.
+                };
+                let mut s = 0.;
+                for _ in 0..200 {
+                    s = (s0 + s1) * 0.5;
+                    if s == s0 || s == s1 {
+                        break;
+                    }
+                    let g = (n0 / (s + r0)).powi(2) + (z1 / (s + 1.)).powi(2) - 1.;
+                    if g > 0. {
+                        s0 = s;
+                    } else if g < 0. {
+                        s1 = s;
+                    } else {
+                        break;
+                    }
+                }
+                let x0 = r0 * y0 / (s + r0);
+                let x1 = y1 / (s + 1.);
+                ((x0 - y0).powi(2) + (x1 - y1).powi(2)).sqrt()
+            } else {
+                (y1 - e1).abs()
+            }
+        } else {
+            let numer = e0 * y0;
+            let denom = e0 * e0 - e1 * e1;
+            if numer < denom {
+                let xde0 = numer / denom;
+                let x0 = e0 * xde0;
+                let x1 = e1 * (1. - xde0 * xde0).sqrt();
+                ((x0 - y0).powi(2) + x1 * x1).sqrt()
+            } else {
+                (y0 - e0).abs()
+            }
+        }
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelEllipse2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 5];
+
+    type CoefficientsType = ([T; 2], [T; 2], T);
+
+    /// 5 points determine a conic.
+    const NB_SAMPLE: usize = 5;
+
+    /// center XY, semi axes, angle.
+    const NB_COEFFICIENTS: usize = 5;
+
+    /// Euclidean distance to the closest point of ellipse.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (c, axes, angle) = coefficients;
+        let (dx, dy) = (
+            (p[0] - c[0]).to_f64().unwrap(),
+            (p[1] - c[1]).to_f64().unwrap(),
+        );
+        let (sin, cos) = angle.to_f64().unwrap().sin_cos();
+        // Local frame, `X` along major axis.
+        let (x, y) = (dx * cos + dy * sin, -dx * sin + dy * cos);
+        let (a, b) = (axes[0].to_f64().unwrap(), axes[1].to_f64().unwrap());
+        // Round-off of rotation should not leave the axes.
+        let snap = |v: f64| if v.abs() <= a * 1e-12 { 0. } else { v.abs() };
+        T::from(Self::distance_in_quadrant(a, b, snap(x), snap(y))).unwrap()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1], sample[2], sample[3], sample[4]]
+    }
+
+    /// 1. Conic `A x^2 + B xy + C y^2 + D x + E y + F = 0` through 5 normalized samples.
+    /// 2. Conic is an ellipse if `B^2 - 4AC < 0`.
+    ///
+    /// Ref: [Ellipse](https://en.wikipedia.org/wiki/Ellipse#General_ellipse)
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let points: [[f64; 2]; 5] = if let Some(data) = self.data {
+            samples.map(|i| {
+                let p: [T; 3] = data[i].into();
+                [p[0].to_f64().unwrap(), p[1].to_f64().unwrap()]
+            })
+        } else {
+            return Err("Data corrupted.".to_owned());
+        };
+        // Normalize for conditioning.
+        let mean = points
+            .iter()
+            .fold([0f64; 2], |acc, p| [acc[0] + p[0] / 5., acc[1] + p[1] / 5.]);
+        let scale = points
+            .iter()
+            .map(|p| ((p[0] - mean[0]).powi(2) + (p[1] - mean[1]).powi(2)).sqrt())
+            .sum::<f64>()
+            / 5.;
+        if scale <= 0. {
+            return Err("Overlay".to_owned());
+        }
+        let rows = points.map(|p| {
+            let (x, y) = ((p[0] - mean[0]) / scale, (p[1] - mean[1]) / scale);
+            [x * x, x * y, y * y, x, y, 1.]
+        });
+        let [a, b, c, d, e, f] = match Self::null_space(rows) {
+            Some(v) => v,
+            None => return Err("Degenerate samples".to_owned()),
+        };
+
+        let disc = b * b - 4. * a * c;
+        if disc >= 0. {
+            return Err("Not an ellipse".to_owned());
+        }
+        let cx = (2. * c * d - b * e) / disc;
+        let cy = (2. * a * e - b * d) / disc;
+        let common = 2. * (a * e * e + c * d * d - b * d * e + disc * f);
+        let root = ((a - c).powi(2) + b * b).sqrt();
+        let mut major = -(common * (a + c + root)).sqrt() / disc;
+        let mut minor = -(common * (a + c - root)).sqrt() / disc;
+        if !(major.is_finite() && minor.is_finite()) || minor <= 0. || major <= 0. {
+            return Err("Not an ellipse".to_owned());
+        }
+        let mut angle = if b != 0. {
+            ((c - a - root) / b).atan()
+        } else if a < c {
+            0.
+        } else {
+            std::f64::consts::FRAC_PI_2
+        };
+        // Sign of conic decides which one is major.
+        if major < minor {
+            std::mem::swap(&mut major, &mut minor);
+            angle += std::f64::consts::FRAC_PI_2;
+        }
+        if angle > std::f64::consts::FRAC_PI_2 {
+            angle -= std::f64::consts::PI;
+        }
+
+        Ok((
+            [
+                T::from(cx * scale + mean[0]).unwrap(),
+                T::from(cy * scale + mean[1]).unwrap(),
+            ],
+            [
+                T::from(major * scale).unwrap(),
+                T::from(minor * scale).unwrap(),
+            ],
+            T::from(angle).unwrap(),
+        ))
+    }
+}
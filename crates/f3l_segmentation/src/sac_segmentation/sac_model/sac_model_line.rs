@@ -3,7 +3,12 @@ use f3l_core::{
     BasicFloat, SimpleSliceMath,
 };
 
-use super::{ModelCoefficient, SacModel};
+use rand::Rng;
+
+use super::{
+    least_squares::{pca, points_f64},
+    ModelCoefficient, SacModel,
+};
 
 /// Compute 3d line
 #[derive(Debug, Clone, Default)]
@@ -97,8 +102,8 @@ where
         self.data.unwrap()
     }
 
-    fn get_random_samples(&self) -> Self::SampleIdxType {
-        let sample = self.get_random_sample_id();
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
         [sample[0], sample[1]]
     }
 
@@ -116,4 +121,29 @@ where
         let dir = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]].normalized();
         Ok((p0, dir))
     }
+
+    /// Line through centroid of inliers,
+    /// direction is eigenvector of maximal eigenvalue of covariance (PCA).
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers);
+        let (centroid, eigen) = pca(&points);
+        let mut dir = eigen[2].eigenvector.normalized();
+        // Keep orientation of `coefficients`.
+        if dir.dot(&coefficients.1.map(|v| v.to_f64().unwrap())) < 0. {
+            dir = dir.map(|v| -v);
+        }
+        (centroid.iter().chain(&dir).all(|v| v.is_finite())).then(|| {
+            (
+                centroid.map(|v| T::from(v).unwrap()),
+                dir.map(|v| T::from(v).unwrap()),
+            )
+        })
+    }
 }
@@ -0,0 +1,147 @@
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+
+use rand::Rng;
+
+use super::{
+    least_squares::{pca, points_f64},
+    ModelCoefficient, SacModel,
+};
+
+/// Compute 2d line on `XY`, `Z` is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelLine2d<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Point on Line
+    /// - Direction
+    pub coefficients: ([T; 2], [T; 2]),
+    data: Option<&'a [P]>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct Line2dCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 2], [T; 2]),
+}
+
+impl<T: BasicFloat> ModelCoefficient for Line2dCoefficient<T> {
+    type CoefficientsType = ([T; 2], [T; 2]);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelLine2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], [T::zero(); 2]),
+            data: None,
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 2], [T::zero(); 2]),
+            data: Some(data),
+        }
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelLine2d<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 2];
+
+    type CoefficientsType = ([T; 2], [T; 2]);
+
+    /// Any 2 points (not the same).
+    const NB_SAMPLE: usize = 2;
+
+    /// - Point on Line
+    /// - Direction
+    const NB_COEFFICIENTS: usize = 4;
+
+    /// `|(p - p1) x dir|`, with normalized `dir`.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (p1, dir) = coefficients;
+        let dir = dir.normalized();
+        ((p[0] - p1[0]) * dir[1] - (p[1] - p1[1]) * dir[0]).abs()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1]]
+    }
+
+    /// Samples 0 as Point of line. norm(samples 1-samples 0) as direction
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let [p0, p1] = *samples;
+        let (p0, p1): ([T; 3], [T; 3]) = if let Some(data) = self.data {
+            (data[p0].into(), data[p1].into())
+        } else {
+            return Err("Data corrupted".to_owned());
+        };
+        let dir = [p1[0] - p0[0], p1[1] - p0[1]];
+        if dir.len() <= T::epsilon() {
+            return Err("Overlay".to_owned());
+        }
+        Ok(([p0[0], p0[1]], dir.normalized()))
+    }
+
+    /// Line through centroid of inliers on `XY`,
+    /// direction is eigenvector of maximal eigenvalue of covariance (PCA).
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers)
+            .into_iter()
+            .map(|p| [p[0], p[1]])
+            .collect::<Vec<_>>();
+        let (centroid, eigen) = pca(&points);
+        let mut dir = eigen[1].eigenvector.normalized();
+        // Keep orientation of `coefficients`.
+        if dir.dot(&coefficients.1.map(|v| v.to_f64().unwrap())) < 0. {
+            dir = dir.map(|v| -v);
+        }
+        (centroid.iter().chain(&dir).all(|v| v.is_finite())).then(|| {
+            (
+                centroid.map(|v| T::from(v).unwrap()),
+                dir.map(|v| T::from(v).unwrap()),
+            )
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use super::{ModelCoefficient, SacModel, SacModelPlane};
+use f3l_core::{
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+use rand::Rng;
+
+/// Compute a 3d plane model, need normals.
+///
+/// Distance of a point blends its distance to plane and angle between its normal and normal of plane:
+/// `w * angle + (1 - w) * distance`, `w` is `normal_distance_weight`.
+/// Points without normal use distance only.
+///
+/// Coefficients: `coefficients_0` x + `coefficients_1` y + `coefficients_2` z + `coefficients_3` = 0
+#[derive(Debug, Clone, Default)]
+pub struct SacModelNormalPlane<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub coefficients: [T; 4],
+    /// Weight of angular distance in `[0, 1]`.
+    pub normal_distance_weight: T,
+    plane: SacModelPlane<'a, P, T>,
+    normals: Vec<Option<[T; 3]>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct NormalPlaneCoefficient<T: BasicFloat> {
+    pub coefficients: [T; 4],
+    pub normal_distance_weight: T,
+}
+
+impl<T: BasicFloat> ModelCoefficient for NormalPlaneCoefficient<T> {
+    type CoefficientsType = [T; 4];
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModelNormalPlane<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: [T::zero(); 4],
+            normal_distance_weight: T::zero(),
+            plane: SacModelPlane::new(),
+            normals: vec![],
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: [T::zero(); 4],
+            normal_distance_weight: T::zero(),
+            plane: SacModelPlane::with_data(data),
+            normals: vec![],
+        }
+    }
+
+    /// Normals indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+
+    pub fn set_normal_distance_weight(&mut self, weight: T) {
+        self.normal_distance_weight = weight;
+    }
+
+    /// Blended distance of `i`th point, see [`SacModelNormalPlane`].
+    pub fn compute_index_to_model(&self, i: usize, coefficients: &[T; 4]) -> T {
+        let distance = Self::compute_point_to_model(self.samples()[i], coefficients);
+        let w = self.normal_distance_weight;
+        match self.normals.get(i) {
+            Some(Some(n)) if w > T::zero() => {
+                let plane_normal = [coefficients[0], coefficients[1], coefficients[2]];
+                let cos = n
+                    .normalized()
+                    .dot(&plane_normal.normalized())
+                    .abs()
+                    .min(T::one());
+                w * cos.acos() + (T::one() - w) * distance
+            }
+            _ => distance,
+        }
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelNormalPlane<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 3];
+
+    type CoefficientsType = [T; 4];
+
+    /// Any 3 points(not overlay or parallel) span a plane.
+    const NB_SAMPLE: usize = 3;
+
+    /// `coefficients_0` x + `coefficients_1` y + `coefficients_2` z + `coefficients_3` = 0
+    const NB_COEFFICIENTS: usize = 4;
+
+    /// Distance to plane only, normals are used by indices.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        SacModelPlane::<P, T>::compute_point_to_model(p, coefficients)
+    }
+
+    fn get_distance_to_model(&self, coefficients: &Self::CoefficientsType) -> Vec<T> {
+        (0..self.data_len())
+            .map(|i| self.compute_index_to_model(i, coefficients))
+            .collect()
+    }
+
+    fn select_indices_within_tolerance(
+        &self,
+        coefficients: &Self::CoefficientsType,
+        tolerance: T,
+    ) -> Vec<usize> {
+        (0..self.data_len())
+            .filter(|&i| self.compute_index_to_model(i, coefficients) < tolerance)
+            .collect()
+    }
+
+    fn count_indices_within_tolerance(
+        &self,
+        coefficients: &Self::CoefficientsType,
+        tolerance: T,
+    ) -> usize {
+        (0..self.data_len())
+            .filter(|&i| self.compute_index_to_model(i, coefficients) < tolerance)
+            .count()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.plane.set_data(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.plane.samples()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        self.plane.get_random_samples(rng)
+    }
+
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        self.plane.compute_model_coefficients(samples)
+    }
+
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        self.plane.refine_model_coefficients(inliers, coefficients)
+    }
+}
@@ -1,9 +1,13 @@
-use super::{ModelCoefficient, SacModel};
+use super::{
+    least_squares::{pca, points_f64},
+    ModelCoefficient, SacModel,
+};
 use f3l_core::{
     apply_both,
     serde::{self, Deserialize, Serialize},
     BasicFloat, SimpleSliceMath,
 };
+use rand::Rng;
 
 /// Compute a 3d plane model.
 /// Any 3 points(not overlay or parallel) span a plane.
@@ -92,8 +96,8 @@ where
         self.data.unwrap()
     }
 
-    fn get_random_samples(&self) -> Self::SampleIdxType {
-        let sample = self.get_random_sample_id();
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
         [sample[0], sample[1], sample[2]]
     }
 
@@ -125,4 +129,46 @@ where
 
         Ok(coefficient)
     }
+
+    /// Plane through centroid of inliers,
+    /// normal is eigenvector of minimal eigenvalue of covariance (PCA).
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers);
+        let (centroid, eigen) = pca(&points);
+        let mut normal = eigen[0].eigenvector.normalized();
+        // Keep orientation of `coefficients`.
+        let old = [coefficients[0], coefficients[1], coefficients[2]].map(|v| v.to_f64().unwrap());
+        if normal.dot(&old) < 0. {
+            normal = normal.map(|v| -v);
+        }
+        let d = -normal.dot(&centroid);
+        let refined = [normal[0], normal[1], normal[2], d];
+        refined
+            .iter()
+            .all(|v| v.is_finite())
+            .then(|| refined.map(|v| T::from(v).unwrap()))
+    }
+}
+
+/// Acute angle between normal of plane and `axis`, in `[0, PI / 2]`.
+///
+/// `None` if `axis` is zero or `eps_angle` is not positive, which means no constraint.
+pub(crate) fn plane_axis_angle<T: BasicFloat>(
+    coefficient: &[T; 4],
+    axis: &[T; 3],
+    eps_angle: T,
+) -> Option<T> {
+    if eps_angle <= T::zero() || axis.len() <= T::epsilon() {
+        return None;
+    }
+    let normal = [coefficient[0], coefficient[1], coefficient[2]].normalized();
+    let cos = normal.dot(&axis.normalized()).abs().min(T::one());
+    Some(cos.acos())
 }
@@ -4,7 +4,12 @@ use f3l_core::{
 };
 use std::marker::PhantomData;
 
-use super::{ModelCoefficient, SacModel};
+use rand::Rng;
+
+use super::{
+    least_squares::{fit_sphere, points_f64},
+    ModelCoefficient, SacModel,
+};
 
 /// Compute a sphere
 #[derive(Debug, Clone, Default)]
@@ -88,8 +93,8 @@ where
         self.data.unwrap()
     }
 
-    fn get_random_samples(&self) -> Self::SampleIdxType {
-        let sample = self.get_random_sample_id();
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
         [sample[0], sample[1], sample[2], sample[3]]
     }
 
@@ -175,4 +180,21 @@ where
 
         Ok((center, radius))
     }
+
+    /// Algebraic fit, then geometric fit by Levenberg-Marquardt.
+    fn refine_model_coefficients(
+        &self,
+        inliers: &[usize],
+        _coefficients: &Self::CoefficientsType,
+    ) -> Option<Self::CoefficientsType> {
+        if inliers.len() < Self::NB_SAMPLE {
+            return None;
+        }
+        let points = points_f64(self.data?, inliers);
+        let (center, radius) = fit_sphere(&points)?;
+        Some((
+            center.map(|v| T::from(v).unwrap()),
+            T::from(radius).unwrap(),
+        ))
+    }
 }
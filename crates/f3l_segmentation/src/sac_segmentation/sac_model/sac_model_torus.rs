@@ -0,0 +1,256 @@
+use f3l_core::{
+    find_circle,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, SimpleSliceMath,
+};
+
+use rand::Rng;
+
+use super::{ModelCoefficient, SacModel};
+
+/// Compute a torus, need normals.
+#[derive(Debug, Clone, Default)]
+pub struct SacModelTorus<'a, P, T: BasicFloat>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    /// - Center
+    /// - Direction of axis
+    /// - Major radius, center to center of tube
+    /// - Minor radius, of tube
+    pub coefficients: ([T; 3], [T; 3], T, T),
+    data: Option<&'a [P]>,
+    normals: Vec<Option<[T; 3]>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct TorusCoefficient<T: BasicFloat> {
+    pub coefficients: ([T; 3], [T; 3], T, T),
+}
+
+impl<T: BasicFloat> ModelCoefficient for TorusCoefficient<T> {
+    type CoefficientsType = ([T; 3], [T; 3], T, T);
+
+    fn coe(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+}
+
+/// Center, axis, major and minor radius.
+type Torus = ([f64; 3], [f64; 3], f64, f64);
+
+/// Determinant of 3 columns.
+#[inline]
+fn det(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3]) -> f64 {
+    a.dot(&b.cross(c))
+}
+
+/// Real roots of `c0 + c1 x + c2 x^2 + c3 x^3`.
+fn real_roots(c: [f64; 4]) -> Vec<f64> {
+    let scale = c.iter().fold(0f64, |acc, v| acc.max(v.abs()));
+    if scale == 0. {
+        return vec![];
+    }
+    let [c0, c1, c2, c3] = c.map(|v| v / scale);
+    let eps = 1e-12;
+    let roots = if c3.abs() < eps {
+        if c2.abs() < eps {
+            if c1.abs() < eps {
+                vec![]
+            } else {
+                vec![-c0 / c1]
+            }
+        } else {
+            let d = c1 * c1 - 4. * c2 * c0;
+            if d < 0. {
+                vec![]
+            } else {
+                let d = d.sqrt();
+                vec![(-c1 + d) / (2. * c2), (-c1 - d) / (2. * c2)]
+            }
+        }
+    } else {
+        // Depressed cubic `t^3 + p t + q`, `x = t - a / 3`.
+        let (a, b, cc) = (c2 / c3, c1 / c3, c0 / c3);
+        let p = b - a * a / 3.;
+        let q = 2. * a * a * a / 27. - a * b / 3. + cc;
+        let shift = -a / 3.;
+        let d = (q / 2.).powi(2) + (p / 3.).powi(3);
+        if d > 0. {
+            let s = d.sqrt();
+            vec![(-q / 2. + s).cbrt() + (-q / 2. - s).cbrt() + shift]
+        } else if p.abs() < eps {
+            vec![shift]
+        } else {
+            let m = 2. * (-p / 3.).sqrt();
+            let theta = (3. * q / (p * m)).clamp(-1., 1.).acos() / 3.;
+            (0..3)
+                .map(|k| m * (theta - 2. * std::f64::consts::PI * k as f64 / 3.).cos() + shift)
+                .collect()
+        }
+    };
+    // Polish by Newton.
+    roots
+        .into_iter()
+        .map(|mut x| {
+            (0..3).for_each(|_| {
+                let f = c0 + x * (c1 + x * (c2 + x * c3));
+                let df = c1 + x * (2. * c2 + x * 3. * c3);
+                if df.abs() > eps {
+                    x -= f / df;
+                }
+            });
+            x
+        })
+        .collect()
+}
+
+impl<'a, P, T: BasicFloat> SacModelTorus<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero(), T::zero()),
+            data: None,
+            normals: vec![],
+        }
+    }
+
+    pub fn with_data(data: &'a [P]) -> Self {
+        Self {
+            coefficients: ([T::zero(); 3], [T::zero(); 3], T::zero(), T::zero()),
+            data: Some(data),
+            normals: vec![],
+        }
+    }
+
+    /// Normals indexed the same as data, like outputs of `NormalEstimation` of `f3l_features`.
+    pub fn set_normals<N: Into<[T; 3]> + Copy>(&mut self, normals: &[Option<N>]) {
+        self.normals = normals.iter().map(|n| n.map(|n| n.into())).collect();
+    }
+}
+
+impl<'a, P, T: BasicFloat> SacModel<'a, P, T> for SacModelTorus<'a, P, T>
+where
+    P: Into<[T; 3]> + Clone + Copy,
+{
+    type SampleIdxType = [usize; 4];
+
+    type CoefficientsType = ([T; 3], [T; 3], T, T);
+
+    /// 4 points with normals.
+    const NB_SAMPLE: usize = 4;
+
+    /// center XYZ, direction XYZ, major radius, minor radius.
+    const NB_COEFFICIENTS: usize = 8;
+
+    /// Distance to center circle of tube minus minor radius.
+    fn compute_point_to_model(p: P, coefficients: &Self::CoefficientsType) -> T {
+        let p: [T; 3] = p.into();
+        let (c, dir, major, minor) = coefficients;
+        let dir = dir.normalized();
+        let v = [p[0] - c[0], p[1] - c[1], p[2] - c[2]];
+        let h = v.dot(&dir);
+        let q = v.cross(&dir).len();
+        (((q - *major).powi(2) + h * h).sqrt() - *minor).abs()
+    }
+
+    fn set_data(&mut self, data: &'a [P]) {
+        self.data = Some(data);
+    }
+
+    fn set_coefficient(&mut self, factor: &Self::CoefficientsType) {
+        self.coefficients = *factor;
+    }
+
+    fn get_coefficient(&self) -> Self::CoefficientsType {
+        self.coefficients
+    }
+
+    fn samples(&self) -> &[P] {
+        self.data.unwrap()
+    }
+
+    fn get_random_samples<G: Rng + ?Sized>(&self, rng: &mut G) -> Self::SampleIdxType {
+        let sample = self.get_random_sample_id(rng);
+        [sample[0], sample[1], sample[2], sample[3]]
+    }
+
+    /// Points moved by minor radius `r` against normals, `c_i = p_i - r n_i`,
+    /// lie on the center circle of tube.
+    /// 1. `c_i` are coplanar, which is a cubic of `r`.
+    /// 2. For each root, circle through `c_1, c_2, c_3` is the center circle,
+    ///    the one `c_4` is closest to wins.
+    ///
+    /// Signs of normals could be inconsistent, so all combinations are tried.
+    fn compute_model_coefficients(
+        &self,
+        samples: &Self::SampleIdxType,
+    ) -> Result<Self::CoefficientsType, String> {
+        let points: [[f64; 3]; 4] = if let Some(data) = self.data {
+            samples.map(|i| {
+                let p: [T; 3] = data[i].into();
+                p.map(|v| v.to_f64().unwrap())
+            })
+        } else {
+            return Err("Data corrupted.".to_owned());
+        };
+        let mut normals = [[0f64; 3]; 4];
+        for (k, &i) in samples.iter().enumerate() {
+            match self.normals.get(i) {
+                Some(Some(n)) => normals[k] = n.map(|v| v.to_f64().unwrap()).normalized(),
+                _ => return Err("Normals missing.".to_owned()),
+            }
+        }
+        let sub = |a: &[f64; 3], b: &[f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+
+        let mut best: Option<(f64, Torus)> = None;
+        for signs in 0..8 {
+            let n = [0, 1, 2, 3].map(|k| {
+                let s = if k > 0 && signs & (1 << (k - 1)) != 0 {
+                    -1.
+                } else {
+                    1.
+                };
+                normals[k].map(|v| v * s)
+            });
+            // det[a_k - r b_k] of `c_k - c_0`.
+            let a = [1, 2, 3].map(|k| sub(&points[k], &points[0]));
+            let b = [1, 2, 3].map(|k| sub(&n[k], &n[0]));
+            let coefficients = [
+                det(&a[0], &a[1], &a[2]),
+                -(det(&b[0], &a[1], &a[2]) + det(&a[0], &b[1], &a[2]) + det(&a[0], &a[1], &b[2])),
+                det(&b[0], &b[1], &a[2]) + det(&b[0], &a[1], &b[2]) + det(&a[0], &b[1], &b[2]),
+                -det(&b[0], &b[1], &b[2]),
+            ];
+            for r in real_roots(coefficients) {
+                if !r.is_finite() || r == 0. {
+                    continue;
+                }
+                let c = [0, 1, 2, 3].map(|k| sub(&points[k], &n[k].map(|v| v * r)));
+                let (center, normal, major) = find_circle(&[c[0], c[1], c[2]]);
+                if normal.len() <= 1e-12 || !major.is_finite() {
+                    continue;
+                }
+                let normal = normal.normalized();
+                let v = sub(&c[3], &center);
+                let h = v.dot(&normal);
+                let residual = h.abs() + (v.cross(&normal).len() - major).abs();
+                if best.is_none_or(|(e, _)| residual < e) {
+                    best = Some((residual, (center, normal, major, r.abs())));
+                }
+            }
+        }
+        match best {
+            Some((_, (center, normal, major, minor))) => Ok((
+                center.map(|v| T::from(v).unwrap()),
+                normal.map(|v| T::from(v).unwrap()),
+                T::from(major).unwrap(),
+                T::from(minor).unwrap(),
+            )),
+            None => Err("Degenerate samples.".to_owned()),
+        }
+    }
+}
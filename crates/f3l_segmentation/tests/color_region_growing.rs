@@ -0,0 +1,88 @@
+use f3l_segmentation::*;
+
+/// Plane of `40 x 40`, left half red and right half blue in 0 ~ 255.
+/// Colors change slightly along `y`, and a few points are green.
+fn scene() -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+    (0..40)
+        .flat_map(|x| (0..40).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let p = [x as f32 * 0.01, y as f32 * 0.01, 0.];
+            let shade = (y % 4) as f32 * 6.;
+            let c = if (x, y) == (10, 10) || (x, y) == (30, 25) {
+                [0., 255., 0.]
+            } else if x < 20 {
+                [200. + shade, 20., 20.]
+            } else {
+                [20., 20., 200. + shade]
+            };
+            (p, c)
+        })
+        .unzip()
+}
+
+#[test]
+fn two_colors() {
+    let (data, colors) = scene();
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 0,
+        min_nb_data: 10,
+        max_nb_data: data.len(),
+        max_nb_cluster: 10,
+    };
+    // Shades of neighbor rows differ by 6 or 18, so each row grows alone.
+    let mut extractor = ColorRegionGrowing::new(parameter, 5f32, 20.);
+    extractor.set_colors(&colors);
+    let clusters = extractor.extract(&data);
+
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0].len() + clusters[1].len(), data.len());
+    let red = extractor.at(0).unwrap();
+    let blue = extractor.at(1).unwrap();
+    let (red, blue) = if red[0][0] < 0.1 {
+        (red, blue)
+    } else {
+        (blue, red)
+    };
+    // Green points are merged to the region around them.
+    assert!(red.iter().all(|p| p[0] < 0.195));
+    assert!(blue.iter().all(|p| p[0] > 0.195));
+}
+
+#[test]
+fn no_merge() {
+    let (data, colors) = scene();
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 0,
+        min_nb_data: 1,
+        max_nb_data: data.len(),
+        max_nb_cluster: 1000,
+    };
+    let mut extractor = ColorRegionGrowing::new(parameter, 5f32, 1.);
+    extractor.set_colors(&colors);
+    let clusters = extractor.extract(&data);
+    // Rows in each half, 2 green points, and 2 rows are split by green ones.
+    assert_eq!(clusters.len(), 40 * 2 + 2 + 2);
+
+    extractor.set_colors(&colors[..10]);
+    assert!(extractor.extract(&data).is_empty());
+}
+
+#[test]
+fn nan_colors() {
+    let (data, mut colors) = scene();
+    colors.iter_mut().step_by(7).for_each(|c| c[0] = f32::NAN);
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 0,
+        min_nb_data: 50,
+        max_nb_data: data.len(),
+        max_nb_cluster: 1000,
+    };
+    let mut extractor = ColorRegionGrowing::new(parameter, 5f32, 10.);
+    extractor.set_colors(&colors);
+    // Regions of NaN color are never merged by threshold, but must not panic.
+    let clusters = extractor.extract(&data);
+    assert!(clusters.iter().flatten().all(|&i| i < data.len()));
+}
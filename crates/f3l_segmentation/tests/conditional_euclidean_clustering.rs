@@ -0,0 +1,80 @@
+use f3l_segmentation::*;
+
+/// Two layers of `20 x 20` at `z = 0` and `z = 0.01`, and 3 points far away.
+fn layers() -> Vec<[f32; 3]> {
+    let layer = |z: f32| {
+        (0..20).flat_map(move |x| (0..20).map(move |y| [x as f32 * 0.01, y as f32 * 0.01, z]))
+    };
+    layer(0.)
+        .chain(layer(0.01))
+        .chain([[1., 1., 0.], [1.01, 1., 0.], [1.02, 1., 0.]])
+        .collect()
+}
+
+#[test]
+fn split_by_condition() {
+    let data = layers();
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 1,
+        min_nb_data: 10,
+        max_nb_data: 1000,
+        max_nb_cluster: 10,
+    };
+    let mut euclidean = EuclideanClusterExtractor::new(parameter);
+    assert_eq!(euclidean.extract(&data).len(), 1);
+
+    let mut extractor = ConditionalEuclideanClustering::new(parameter, |a: &[f32; 3], b, d| {
+        assert!(d <= 0.015);
+        (a[2] - b[2]).abs() < 0.005
+    });
+    let clusters = extractor.extract(&data);
+    assert_eq!(clusters.len(), 2);
+    assert!(clusters.iter().all(|c| c.len() == 400));
+    assert!(extractor.at(0).unwrap().iter().all(|p| p[2] == 0.));
+    assert!(extractor.at(1).unwrap().iter().all(|p| p[2] == 0.01));
+
+    let too_small = extractor.too_small_clusters();
+    assert_eq!(too_small, vec![vec![800, 801, 802]]);
+    assert!(extractor.too_large_clusters().is_empty());
+}
+
+#[test]
+fn too_large() {
+    let data = layers();
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 1,
+        min_nb_data: 1,
+        max_nb_data: 100,
+        max_nb_cluster: 10,
+    };
+    let mut extractor = ConditionalEuclideanClustering::new(parameter, |_: &[f32; 3], _, _| true);
+    let clusters = extractor.extract(&data);
+    assert_eq!(clusters, vec![vec![800, 801, 802]]);
+    let too_large = extractor.too_large_clusters();
+    assert_eq!(too_large.len(), 1);
+    assert_eq!(too_large[0].len(), 800);
+}
+
+#[test]
+fn excess() {
+    let data = layers();
+    let parameter = F3lClusterParameter {
+        tolerance: 0.015f32,
+        nb_in_tolerance: 1,
+        min_nb_data: 1,
+        max_nb_data: 1000,
+        max_nb_cluster: 1,
+    };
+    let mut extractor = ConditionalEuclideanClustering::new(parameter, |a: &[f32; 3], b, _| {
+        (a[2] - b[2]).abs() < 0.005
+    });
+    let clusters = extractor.extract(&data);
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].len(), 400);
+    let excess = extractor.excess_clusters();
+    assert_eq!(excess.len(), 2);
+    assert_eq!(excess[0].len(), 400);
+    assert_eq!(excess[1], vec![800, 801, 802]);
+}
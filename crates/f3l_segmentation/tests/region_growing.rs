@@ -0,0 +1,64 @@
+use f3l_segmentation::*;
+
+/// Floor of `2 x 2` at `z = 0`, wall of `2 x 1` at `x = 0` and a box of `0.5` on the floor.
+fn room() -> Vec<[f32; 3]> {
+    let step = 0.04f32;
+    let floor = (0..=50)
+        .flat_map(|x| (0..=50).map(move |y| [x as f32 * step, y as f32 * step, 0.]))
+        .filter(|p| !(p[0] > 1. && p[0] < 1.5 && p[1] > 1. && p[1] < 1.5));
+    let wall = (0..=50).flat_map(|y| (1..=25).map(move |z| [0., y as f32 * step, z as f32 * step]));
+    let top = (0..=12)
+        .flat_map(|x| (0..=12).map(move |y| [1.02 + x as f32 * step, 1.02 + y as f32 * step, 0.5]));
+    floor.chain(wall).chain(top).collect()
+}
+
+#[test]
+fn planes() {
+    let data = room();
+    let parameter = F3lClusterParameter {
+        tolerance: 0f32,
+        nb_in_tolerance: 10,
+        min_nb_data: 50,
+        max_nb_data: data.len(),
+        max_nb_cluster: 10,
+    };
+    let mut extractor = RegionGrowing::new(parameter);
+    extractor.set_angle_threshold(5f32.to_radians());
+    let clusters = extractor.extract(&data);
+
+    assert_eq!(clusters.len(), 3);
+    // Floor, wall then top of box, sorted by size.
+    let floor = extractor.at(0).unwrap();
+    assert!(floor.iter().all(|p| p[2] < 0.05));
+    let wall = extractor.at(1).unwrap();
+    assert!(wall.iter().all(|p| p[0] < 0.05));
+    let top = extractor.at(2).unwrap();
+    assert!(top.iter().all(|p| (p[2] - 0.5).abs() < 1e-5));
+    assert_eq!(top.len(), 13 * 13);
+    assert!(extractor.at(3).is_err());
+
+    let curvatures = extractor.curvatures();
+    assert_eq!(curvatures.len(), data.len());
+}
+
+#[test]
+fn given_normals() {
+    let data = room();
+    let parameter = F3lClusterParameter {
+        tolerance: 0f32,
+        nb_in_tolerance: 10,
+        min_nb_data: 50,
+        max_nb_data: data.len(),
+        max_nb_cluster: 10,
+    };
+    let mut extractor = RegionGrowing::new(parameter);
+    // All normals are the same, so only curvature stops growing.
+    extractor.set_normals(&vec![Some([0f32, 0., 1.]); data.len()]);
+    extractor.set_curvature_threshold(1.);
+    let clusters = extractor.extract(&data);
+    // Top of box is not connected to others.
+    assert_eq!(clusters.len(), 2);
+
+    extractor.set_normals(&[Some([0f32, 0., 1.])]);
+    assert!(extractor.extract(&data).is_empty());
+}
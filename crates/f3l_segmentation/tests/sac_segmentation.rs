@@ -50,6 +50,30 @@ mod sac_segmentation {
             assert!(((p[2] - distance) * 1000.).round() < f32::EPSILON);
         }
 
+        #[test]
+        fn sac_plane_degenerate_samples() {
+            // Most samples are collinear, which could not compute a plane.
+            let line = (0..200).map(|i| [i as f32, i as f32 * 2., i as f32 * 3.]);
+            let data = line
+                .chain([[5f32, 0., 0.], [6., 2., 3.]])
+                .collect::<Vec<_>>();
+            let parameter = SacAlgorithmParameter {
+                threshold: 0.01,
+                seed: Some(42),
+                max_iterations: 200,
+                ..Default::default()
+            };
+            let mut model = SacModelPlane::with_data(&data);
+            let mut algorithm = SacRansac::with_parameter(parameter);
+            assert!(algorithm.compute(&mut model));
+            assert_eq!(algorithm.get_inliers().len(), data.len());
+
+            // Normal of plane of `[1, 2, 3]` and `[1, 0, 0]`.
+            let [a, b, c, d] = model.get_coefficient();
+            let dot = (b * 3. - c * 2.) / 13f32.sqrt();
+            assert!(a.abs() < 1e-4 && dot.abs() > 0.9999 && d.abs() < 1e-3);
+        }
+
         #[test]
         fn sac_plane() {
             use std::path::Path;
@@ -62,11 +86,13 @@ mod sac_segmentation {
                 threshold: 0.02,
                 max_iterations: 200,
                 threads: 1,
+                ..Default::default()
             };
             let mut model = SacModelPlane::with_data(&vertices);
             let mut algorithm = SacRansac {
                 parameter,
                 inliers: vec![],
+                ..Default::default()
             };
             let result = algorithm.compute(&mut model);
             let _factor = model.get_coefficient();
@@ -86,11 +112,13 @@ mod sac_segmentation {
                 threshold: 0.1,
                 max_iterations: 200,
                 threads: 1,
+                ..Default::default()
             };
             let mut model = SacModelLine::with_data(&line);
             let mut algorithm = SacRansac {
                 parameter,
                 inliers: vec![],
+                ..Default::default()
             };
             let result = algorithm.compute(&mut model);
             let _factor = model.get_coefficient();
@@ -128,11 +156,13 @@ mod sac_segmentation {
                 threshold: 0.1,
                 max_iterations: 2000,
                 threads: 1,
+                ..Default::default()
             };
             let mut model = SacModelLine::with_data(&line);
             let mut algorithm = SacRansac {
                 parameter,
                 inliers: vec![],
+                ..Default::default()
             };
             let result = algorithm.compute(&mut model);
             let _factor = model.get_coefficient();
@@ -303,6 +333,7 @@ mod sac_segmentation {
             let mut algorithm = SacRansac {
                 parameter,
                 inliers: vec![],
+                ..Default::default()
             };
             let ok = algorithm.compute(&mut model);
 
@@ -313,4 +344,810 @@ mod sac_segmentation {
             assert_eq!(center_slice, round_slice_n(center, 3));
         }
     }
+    mod model_2d {
+        use super::*;
+        use f3l_segmentation::SacSegment;
+
+        #[test]
+        fn sac_line2d() {
+            // `y = 0.5 x + 1` with random `Z`, and outliers.
+            let data = (0..50)
+                .map(|i| [i as f32 * 0.2, i as f32 * 0.1 + 1., (i % 7) as f32])
+                .chain((0..10).map(|i| [i as f32, 5. + i as f32 * 0.3, 0.]))
+                .collect::<Vec<_>>();
+            let mut sac = SacSegment {
+                model: Line2dCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert_eq!(sac.inliers, (0..50).collect::<Vec<_>>());
+            let (_, dir) = sac.model.coefficients;
+            assert!((dir[1] / dir[0] - 0.5).abs() < 1e-4);
+        }
+
+        #[test]
+        fn sac_circle2d() {
+            let data = (0..100)
+                .map(|i| {
+                    let t = (i as f32 * 3.6).to_radians();
+                    [2. + 3. * t.cos(), -1. + 3. * t.sin(), 0.]
+                })
+                .chain((0..20).map(|i| [i as f32 * 0.1, i as f32 * 0.05, 1.]))
+                .collect::<Vec<_>>();
+            let mut sac = SacSegment {
+                model: Circle2dCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert!(sac.inliers.len() >= 100);
+            let (center, radius) = sac.model.coefficients;
+            assert!((center[0] - 2.).abs() < 1e-3 && (center[1] + 1.).abs() < 1e-3);
+            assert!((radius - 3.).abs() < 1e-3);
+        }
+
+        fn ellipse() -> Vec<[f64; 3]> {
+            let (a, b, angle) = (4f64, 2f64, 0.5f64);
+            (0..100)
+                .map(|i| {
+                    let t = (i as f64 * 3.6).to_radians();
+                    let (x, y) = (a * t.cos(), b * t.sin());
+                    [
+                        1. + x * angle.cos() - y * angle.sin(),
+                        2. + x * angle.sin() + y * angle.cos(),
+                        0.,
+                    ]
+                })
+                .collect()
+        }
+
+        #[test]
+        fn ellipse2d_coefficients() {
+            let data = ellipse();
+            let model = SacModelEllipse2d::with_data(&data);
+            let (center, axes, angle) = model
+                .compute_model_coefficients(&[0, 17, 33, 51, 80])
+                .unwrap();
+            assert!((center[0] - 1.).abs() < 1e-9 && (center[1] - 2.).abs() < 1e-9);
+            assert!((axes[0] - 4.).abs() < 1e-9 && (axes[1] - 2.).abs() < 1e-9);
+            assert!((angle - 0.5).abs() < 1e-9);
+
+            let coefficients = (center, axes, angle);
+            assert!(data
+                .iter()
+                .all(|&p| SacModelEllipse2d::compute_point_to_model(p, &coefficients) < 1e-9));
+            // Outside of major axis, and center.
+            let tip = [1. + 5. * 0.5f64.cos(), 2. + 5. * 0.5f64.sin(), 0.];
+            let d = SacModelEllipse2d::compute_point_to_model(tip, &coefficients);
+            assert!((d - 1.).abs() < 1e-9);
+            let d = SacModelEllipse2d::compute_point_to_model([1., 2., 0.], &coefficients);
+            assert!((d - 2.).abs() < 1e-9);
+        }
+
+        #[test]
+        fn sac_ellipse2d() {
+            let data = ellipse()
+                .into_iter()
+                .chain((0..20).map(|i| [i as f64 * 0.1, i as f64 * 0.05, 0.]))
+                .collect::<Vec<_>>();
+            let mut sac = SacSegment {
+                model: Ellipse2dCoefficient::<f64>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    max_iterations: 5000,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert!(sac.inliers.len() >= 100);
+            let (_, axes, _) = sac.model.coefficients;
+            assert!((axes[0] - 4.).abs() < 1e-3 && (axes[1] - 2.).abs() < 1e-3);
+        }
+    }
+
+    mod model_normals {
+        use super::*;
+        use f3l_core::SimpleSliceMath;
+        use f3l_segmentation::SacSegment;
+
+        /// Flip some normals, like outputs of normal estimation.
+        fn flip(i: usize, n: [f32; 3]) -> Option<[f32; 3]> {
+            if i.is_multiple_of(3) {
+                Some([-n[0], -n[1], -n[2]])
+            } else {
+                Some(n)
+            }
+        }
+
+        #[test]
+        fn sac_cylinder() {
+            // Axis through `(1, 2, 3)` along `(1, 1, 0)`, radius 0.5.
+            let dir = [1f32, 1., 0.].normalized();
+            let (u, v) = ([0f32, 0., 1.], [1f32, -1., 0.].normalized());
+            let (data, normals): (Vec<_>, Vec<_>) = (0..200)
+                .map(|i| {
+                    let (h, t) = ((i / 20) as f32 * 0.3, (i % 20) as f32 * 0.314);
+                    let n = [0, 1, 2].map(|k| u[k] * t.cos() + v[k] * t.sin());
+                    let p = [0, 1, 2].map(|k| [1f32, 2., 3.][k] + dir[k] * h + n[k] * 0.5);
+                    (p, flip(i, n))
+                })
+                .chain((0..20).map(|i| ([i as f32 * 0.1, 0., 0.], Some([0f32, 0., 1.]))))
+                .unzip();
+            let mut sac = SacSegment {
+                model: CylinderCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data, &normals));
+            assert_eq!(sac.inliers, (0..200).collect::<Vec<_>>());
+            let (point, axis, radius) = sac.model.coefficients;
+            assert!((radius - 0.5).abs() < 1e-3);
+            assert!(axis.dot(&dir).abs() > 0.9999);
+            let to_axis = [point[0] - 1., point[1] - 2., point[2] - 3.];
+            assert!(to_axis.cross(&dir).len() < 1e-3);
+
+            assert!(!sac.compute(&data, &normals[..10]));
+        }
+
+        #[test]
+        fn sac_cone() {
+            // Apex `(0, 0, 1)`, axis `-Z`, half angle 30 degrees.
+            let alpha = 30f32.to_radians();
+            let (data, normals): (Vec<_>, Vec<_>) = (0..200)
+                .map(|i| {
+                    let (h, t) = (0.2 + (i / 20) as f32 * 0.2, (i % 20) as f32 * 0.314);
+                    let u = [t.cos(), t.sin(), 0.];
+                    let p = [
+                        h * alpha.sin() * u[0],
+                        h * alpha.sin() * u[1],
+                        1. - h * alpha.cos(),
+                    ];
+                    let n = [alpha.cos() * u[0], alpha.cos() * u[1], alpha.sin()];
+                    (p, flip(i, n))
+                })
+                .chain((0..20).map(|i| ([i as f32 * 0.1, 3., 0.], Some([0f32, 0., 1.]))))
+                .unzip();
+            let mut sac = SacSegment {
+                model: ConeCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data, &normals));
+            assert_eq!(sac.inliers, (0..200).collect::<Vec<_>>());
+            let (apex, axis, angle) = sac.model.coefficients;
+            assert!(apex.distance_between(&[0., 0., 1.]) < 1e-3);
+            assert!(axis[2] < -0.9999);
+            assert!((angle - alpha).abs() < 1e-3);
+            // Behind apex, the closest is apex.
+            let d = SacModelCone::compute_point_to_model([0f32, 0., 2.], &(apex, axis, angle));
+            assert!((d - 1.).abs() < 1e-3);
+        }
+
+        #[test]
+        fn sac_torus() {
+            // Center `(1, 0, 0)`, axis `Z`, radii 2 and 0.5.
+            let (data, normals): (Vec<_>, Vec<_>) = (0..400)
+                .map(|i| {
+                    let (phi, theta) = ((i / 20) as f32 * 0.314, (i % 20) as f32 * 0.314);
+                    let u = [phi.cos(), phi.sin(), 0.];
+                    let n = [theta.cos() * u[0], theta.cos() * u[1], theta.sin()];
+                    let p = [
+                        1. + 2. * u[0] + 0.5 * n[0],
+                        2. * u[1] + 0.5 * n[1],
+                        0.5 * n[2],
+                    ];
+                    (p, flip(i, n))
+                })
+                .chain((0..20).map(|i| ([i as f32 * 0.1, 0., 3.], Some([0f32, 0., 1.]))))
+                .unzip();
+            let mut sac = SacSegment {
+                model: TorusCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data, &normals));
+            // Model from 4 samples in `f32`, a few of points could be just out of threshold.
+            assert!(sac.inliers.len() >= 380 && sac.inliers.iter().all(|&i| i < 400));
+            let (center, axis, major, minor) = sac.model.coefficients;
+            assert!(center.distance_between(&[1., 0., 0.]) < 1e-3);
+            assert!(axis[2].abs() > 0.9999);
+            assert!((major - 2.).abs() < 1e-3);
+            assert!((minor - 0.5).abs() < 1e-3);
+        }
+    }
+    mod constrained_plane {
+        use super::*;
+        use f3l_segmentation::SacSegment;
+
+        /// Floor `z = 0` with 100 points, and wall `x = 0` with 200 points.
+        fn room() -> Vec<[f32; 3]> {
+            (0..100)
+                .map(|i| [1. + (i / 10) as f32 * 0.1, (i % 10) as f32 * 0.1, 0.])
+                .chain((0..200).map(|i| [0., (i / 20) as f32 * 0.1, 0.5 + (i % 20) as f32 * 0.1]))
+                .collect()
+        }
+
+        #[test]
+        fn sac_perpendicular_plane() {
+            let data = room();
+            let mut sac = SacSegment {
+                model: PerpendicularPlaneCoefficient {
+                    axis: [0f32, 0., 1.],
+                    eps_angle: 5f32.to_radians(),
+                    ..Default::default()
+                },
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert_eq!(sac.inliers, (0..100).collect::<Vec<_>>());
+            assert!(sac.model.coefficients[2].abs() > 0.9999);
+        }
+
+        #[test]
+        fn sac_parallel_plane() {
+            let data = room();
+            let mut sac = SacSegment {
+                model: ParallelPlaneCoefficient {
+                    axis: [0f32, 0., 1.],
+                    eps_angle: 5f32.to_radians(),
+                    ..Default::default()
+                },
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert_eq!(sac.inliers, (100..300).collect::<Vec<_>>());
+            assert!(sac.model.coefficients[0].abs() > 0.9999);
+        }
+
+        #[test]
+        fn reject_by_axis() {
+            let data = room();
+            let mut model = SacModelPerpendicularPlane::with_data(&data);
+            model.set_axis([0f32, 0., 1.]);
+            model.set_eps_angle(5f32.to_radians());
+            assert!(model.compute_model_coefficients(&[0, 15, 42]).is_ok());
+            assert!(model.compute_model_coefficients(&[100, 115, 142]).is_err());
+
+            let mut model = SacModelParallelPlane::with_data(&data);
+            model.set_axis([0f32, 0., 1.]);
+            model.set_eps_angle(5f32.to_radians());
+            assert!(model.compute_model_coefficients(&[0, 15, 42]).is_err());
+            assert!(model.compute_model_coefficients(&[100, 115, 142]).is_ok());
+
+            // No constraint without angle.
+            model.set_eps_angle(0.);
+            assert!(model.compute_model_coefficients(&[0, 15, 42]).is_ok());
+        }
+
+        #[test]
+        fn sac_normal_plane() {
+            // Floor `z = 0` facing up, and points just above it facing sideways.
+            let (data, normals): (Vec<_>, Vec<_>) = (0..100)
+                .map(|i| {
+                    (
+                        [(i / 10) as f32 * 0.1, (i % 10) as f32 * 0.1, 0.],
+                        Some([0f32, 0., 1.]),
+                    )
+                })
+                .chain((0..50).map(|i| {
+                    (
+                        [(i / 10) as f32 * 0.1 + 0.05, (i % 10) as f32 * 0.1, 0.005],
+                        Some([1f32, 0., 0.]),
+                    )
+                }))
+                .unzip();
+            let parameter = SacAlgorithmParameter {
+                threshold: 0.01,
+                seed: Some(42),
+                ..Default::default()
+            };
+
+            let mut sac = SacSegment {
+                model: NormalPlaneCoefficient::<f32>::default(),
+                algorithm_parameter: parameter,
+                ..Default::default()
+            };
+            assert!(sac.compute(&data, &normals));
+            assert_eq!(sac.inliers.len(), 150);
+
+            let mut sac = SacSegment {
+                model: NormalPlaneCoefficient {
+                    normal_distance_weight: 0.5f32,
+                    ..Default::default()
+                },
+                algorithm_parameter: parameter,
+                ..Default::default()
+            };
+            assert!(sac.compute(&data, &normals));
+            assert_eq!(sac.inliers, (0..100).collect::<Vec<_>>());
+        }
+    }
+    mod algorithms {
+        use super::*;
+        use f3l_segmentation::SacSegment;
+
+        /// 200 points of noisy plane `z = 0` in `0.002`, then 100 outliers above it.
+        fn noisy_plane() -> Vec<[f32; 3]> {
+            (0..200)
+                .map(|i| {
+                    [
+                        (i / 20) as f32 * 0.1,
+                        (i % 20) as f32 * 0.05,
+                        ((i * 7919) % 41) as f32 * 1e-4 - 0.002,
+                    ]
+                })
+                .chain((0..100).map(|i| {
+                    [
+                        ((i * 37) % 100) as f32 * 0.01,
+                        ((i * 53) % 100) as f32 * 0.01,
+                        0.1 + ((i * 71) % 90) as f32 * 0.01,
+                    ]
+                }))
+                .collect()
+        }
+
+        fn segment(
+            algorithm: SacAlgorithmType,
+            data: &[[f32; 3]],
+        ) -> SacSegment<PlaneCoefficient<f32>> {
+            let mut sac = SacSegment {
+                model: PlaneCoefficient::<f32>::default(),
+                algorithm,
+                algorithm_parameter: SacAlgorithmParameter {
+                    probability: 0.999,
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(data));
+            sac
+        }
+
+        fn check_plane(sac: &SacSegment<PlaneCoefficient<f32>>, min_inliers: usize) {
+            assert!(sac.model.coefficients[2].abs() > 0.999);
+            assert!(sac.model.coefficients[3].abs() < 0.01);
+            assert!(sac.inliers.len() >= min_inliers);
+            assert!(sac.inliers.iter().all(|&i| i < 200));
+            assert!(sac.iterations > 0);
+        }
+
+        fn seeded(
+            algorithm: SacAlgorithmType,
+            data: &[[f32; 3]],
+            threads: usize,
+        ) -> SacSegment<PlaneCoefficient<f32>> {
+            let mut sac = SacSegment {
+                model: PlaneCoefficient::<f32>::default(),
+                algorithm,
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    threads,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(data));
+            sac
+        }
+
+        #[test]
+        fn seed_reproducible() {
+            let data = noisy_plane();
+            for algorithm in [
+                SacAlgorithmType::RANSAC,
+                SacAlgorithmType::MSAC,
+                SacAlgorithmType::MLESAC,
+                SacAlgorithmType::PROSAC,
+                SacAlgorithmType::LMEDS,
+                SacAlgorithmType::LORANSAC,
+            ] {
+                let a = seeded(algorithm, &data, 1);
+                let b = seeded(algorithm, &data, 1);
+                assert_eq!(a.model.coefficients, b.model.coefficients);
+                assert_eq!(a.inliers, b.inliers);
+                assert_eq!(a.iterations, b.iterations);
+                assert_eq!(a.score, b.score);
+            }
+        }
+
+        #[test]
+        fn seed_reproducible_threads() {
+            let data = noisy_plane();
+            let a = seeded(SacAlgorithmType::RANSAC, &data, 4);
+            check_plane(&a, 190);
+            for _ in 0..5 {
+                let b = seeded(SacAlgorithmType::RANSAC, &data, 4);
+                assert_eq!(a.model.coefficients, b.model.coefficients);
+                assert_eq!(a.inliers, b.inliers);
+                assert_eq!(a.iterations, b.iterations);
+            }
+        }
+
+        #[test]
+        fn pluggable_rng() {
+            use f3l_core::seeded_rng;
+            use rand::{rngs::StdRng, SeedableRng};
+
+            let data = noisy_plane();
+            let parameter = SacAlgorithmParameter {
+                threshold: 0.01,
+                threads: 2,
+                seed: Some(42),
+                ..Default::default()
+            };
+            let run = |rng: Option<&mut StdRng>| {
+                let mut model = SacModelPlane::with_data(&data);
+                let mut algorithm = SacMsac::with_parameter(parameter);
+                let mut ransac = SacRansac::with_parameter(parameter);
+                let mut ransac_model = SacModelPlane::with_data(&data);
+                match rng {
+                    Some(rng) => {
+                        assert!(algorithm.compute_with_rng(&mut model, rng));
+                        assert!(ransac.compute_with_rng(&mut ransac_model, rng));
+                    }
+                    None => {
+                        assert!(algorithm.compute(&mut model));
+                        assert!(ransac.compute(&mut ransac_model));
+                    }
+                }
+                (model.coefficients, ransac_model.coefficients)
+            };
+            // Any generator, same state gives same result.
+            let a = run(Some(&mut StdRng::seed_from_u64(7)));
+            let b = run(Some(&mut StdRng::seed_from_u64(7)));
+            assert_eq!(a, b);
+            assert!(a.0[2].abs() > 0.999 && a.1[2].abs() > 0.999);
+
+            // `seed` is the same as generator of `seeded_rng`.
+            let mut model = SacModelPlane::with_data(&data);
+            let mut algorithm = SacMsac::with_parameter(parameter);
+            assert!(algorithm.compute_with_rng(&mut model, &mut seeded_rng(Some(42))));
+            assert_eq!(model.coefficients, run(None).0);
+        }
+
+        #[test]
+        fn ransac_reports() {
+            let data = noisy_plane();
+            let sac = segment(SacAlgorithmType::RANSAC, &data);
+            check_plane(&sac, 190);
+            assert_eq!(sac.score, sac.inliers.len() as f32);
+        }
+
+        #[test]
+        fn ransac_threads_share_iterations() {
+            let data = noisy_plane();
+            let parameter = SacAlgorithmParameter {
+                // Never stop early.
+                probability: 1.,
+                threshold: 0.01,
+                max_iterations: 100,
+                threads: 3,
+                ..Default::default()
+            };
+            let mut model = SacModelPlane::with_data(&data);
+            let mut algorithm = SacRansac::with_parameter(parameter);
+            assert!(algorithm.compute(&mut model));
+            // Shares of 34, 33 and 33, not over `max_iterations`.
+            assert_eq!(algorithm.iterations, 100);
+            assert!(model.coefficients[2].abs() > 0.999);
+            assert!(algorithm.inliers.len() >= 190);
+            assert_eq!(algorithm.score, algorithm.inliers.len() as f32);
+        }
+
+        #[test]
+        fn msac() {
+            let data = noisy_plane();
+            let sac = segment(SacAlgorithmType::MSAC, &data);
+            check_plane(&sac, 190);
+            // Outliers cost `threshold^2` each, inliers less.
+            assert!(sac.score < 0.01f32.powi(2) * 300.);
+            assert!(sac.score >= 0.01f32.powi(2) * 100. * 0.999);
+        }
+
+        #[test]
+        fn mlesac() {
+            let data = noisy_plane();
+            let sac = segment(SacAlgorithmType::MLESAC, &data);
+            check_plane(&sac, 190);
+            assert!(sac.score.is_finite());
+        }
+
+        #[test]
+        fn prosac() {
+            // Plane points are in good quality.
+            let mut data = noisy_plane();
+            data.reverse();
+            let ordering = (0..300).rev().collect::<Vec<_>>();
+            let mut sac = SacSegment {
+                model: PlaneCoefficient::<f32>::default(),
+                algorithm: SacAlgorithmType::PROSAC,
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ordering,
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            // Early samples from a few of top points, stop with fewer inliers is possible.
+            assert!(sac.inliers.len() >= 150 && sac.inliers.iter().all(|&i| i >= 100));
+            assert_eq!(sac.score, sac.inliers.len() as f32);
+
+            sac.ordering = vec![0, 1, 2];
+            assert!(!sac.compute(&data));
+        }
+
+        #[test]
+        fn lmeds() {
+            let data = noisy_plane();
+            let mut sac = SacSegment {
+                model: PlaneCoefficient::<f32>::default(),
+                algorithm: SacAlgorithmType::LMEDS,
+                algorithm_parameter: SacAlgorithmParameter {
+                    // Not used.
+                    threshold: 100.,
+                    max_iterations: 300,
+                    seed: Some(42),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            check_plane(&sac, 180);
+            assert_eq!(sac.iterations, 300);
+            assert!(sac.score < 0.002f32.powi(2));
+        }
+
+        #[test]
+        fn lo_ransac() {
+            let data = noisy_plane();
+            let sac = segment(SacAlgorithmType::LORANSAC, &data);
+            check_plane(&sac, 190);
+            assert_eq!(sac.score, sac.inliers.len() as f32);
+        }
+
+        #[test]
+        fn algorithm_trait() {
+            let data = noisy_plane();
+            let parameter = SacAlgorithmParameter {
+                probability: 0.999,
+                threshold: 0.01,
+                seed: Some(42),
+                ..Default::default()
+            };
+            let mut model = SacModelPlane::with_data(&data);
+            let mut algorithm = SacMsac::with_parameter(parameter);
+            assert!(algorithm.compute(&mut model));
+            assert!(algorithm.get_inliers().len() >= 190);
+            assert!(algorithm.get_iterations() > 0);
+
+            let mut algorithm = SacLoRansac {
+                parameter,
+                local_iterations: 0,
+                ..Default::default()
+            };
+            assert!(algorithm.compute(&mut model));
+            // Without local optimization, it is plain Ransac.
+            assert!(algorithm.get_inliers().iter().all(|&i| i < 200));
+            assert_eq!(algorithm.get_score(), algorithm.get_inliers().len() as f32);
+        }
+    }
+    mod refinement {
+        use super::*;
+        use f3l_core::SimpleSliceMath;
+        use f3l_segmentation::SacSegment;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        fn noise(rng: &mut StdRng, amount: f64) -> f64 {
+            rng.gen_range(-amount..amount)
+        }
+
+        #[test]
+        fn refine_plane() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let data = (0..400)
+                .map(|i| {
+                    [
+                        (i / 20) as f64 * 0.05,
+                        (i % 20) as f64 * 0.05,
+                        1. + noise(&mut rng, 0.005),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let model = SacModelPlane::with_data(&data);
+            // Coarse plane, tilted.
+            let coarse = [0.002, 0., 1., -1.].normalized();
+            let inliers = model.select_indices_within_tolerance(&coarse, 0.01);
+            let refined = model.refine_model_coefficients(&inliers, &coarse).unwrap();
+            assert!(refined[2] > 0.99999);
+            assert!((refined[3] + 1.).abs() < 1e-3);
+        }
+
+        #[test]
+        fn refine_line() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let dir = [1f64, 2., 2.].normalized();
+            let data = (0..200)
+                .map(|i| {
+                    let t = i as f64 * 0.01;
+                    [
+                        1. + t * dir[0] + noise(&mut rng, 0.002),
+                        t * dir[1] + noise(&mut rng, 0.002),
+                        t * dir[2] + noise(&mut rng, 0.002),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let model = SacModelLine::with_data(&data);
+            let coarse = ([1f64, 0., 0.], [1.02, 2., 1.98].normalized());
+            let inliers = (0..200).collect::<Vec<_>>();
+            let (point, refined) = model.refine_model_coefficients(&inliers, &coarse).unwrap();
+            assert!(refined.dot(&dir) > 0.99999);
+            assert!([point[0] - 1., point[1], point[2]].cross(&dir).len() < 1e-3);
+        }
+
+        #[test]
+        fn refine_sphere_and_circles() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let sphere = (0..500)
+                .map(|i| {
+                    let (theta, phi) = (i as f64 * 0.37, (i % 50) as f64 * 0.06);
+                    let r = 2. + noise(&mut rng, 0.002);
+                    [
+                        1. + r * phi.sin() * theta.cos(),
+                        -1. + r * phi.sin() * theta.sin(),
+                        0.5 + r * phi.cos(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let inliers = (0..500).collect::<Vec<_>>();
+            let model = SacModelSphere::with_data(&sphere);
+            let (center, radius) = model
+                .refine_model_coefficients(&inliers, &([1.05, -1., 0.5], 1.9))
+                .unwrap();
+            assert!(center.distance_between(&[1., -1., 0.5]) < 1e-3);
+            assert!((radius - 2.).abs() < 1e-3);
+
+            // Circle on plane `z = x`.
+            let axis = [-1f64, 0., 1.].normalized();
+            let (u, v) = ([1f64, 0., 1.].normalized(), [0f64, 1., 0.]);
+            let circle = (0..200)
+                .map(|i| {
+                    let t = i as f64 * 0.1;
+                    let r = 3. + noise(&mut rng, 0.002);
+                    [0, 1, 2].map(|k| [1., 2., 1.][k] + r * (t.cos() * u[k] + t.sin() * v[k]))
+                })
+                .collect::<Vec<_>>();
+            let inliers = (0..200).collect::<Vec<_>>();
+            let model = SacModelCircle3d::with_data(&circle);
+            let (center, normal, radius) = model
+                .refine_model_coefficients(&inliers, &([1., 2.1, 1.], axis, 3.1))
+                .unwrap();
+            assert!(center.distance_between(&[1., 2., 1.]) < 1e-3);
+            assert!(normal.dot(&axis) > 0.99999);
+            assert!((radius - 3.).abs() < 1e-3);
+
+            let model = SacModelCircle2d::with_data(&circle);
+            let projected = (0..200)
+                .map(|i| {
+                    let p = circle[i];
+                    [p[0], p[1] * 0.5, 0.]
+                })
+                .collect::<Vec<_>>();
+            assert!(model
+                .refine_model_coefficients(&inliers, &([0., 0.], 1.))
+                .is_some());
+            let model = SacModelCircle2d::with_data(&projected);
+            // `XY` of circle is an ellipse, fit is still a circle.
+            assert!(model
+                .refine_model_coefficients(&inliers, &([0., 0.], 1.))
+                .is_some());
+        }
+
+        #[test]
+        fn refine_cylinder() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let dir = [0f64, 1., 1.].normalized();
+            let (u, v) = ([1f64, 0., 0.], [0f64, 1., -1.].normalized());
+            let data = (0..400)
+                .map(|i| {
+                    let (h, t) = ((i / 20) as f64 * 0.1, (i % 20) as f64 * 0.314);
+                    let r = 0.5 + noise(&mut rng, 0.001);
+                    [0, 1, 2].map(|k| dir[k] * h + r * (t.cos() * u[k] + t.sin() * v[k]))
+                })
+                .collect::<Vec<_>>();
+            let inliers = (0..400).collect::<Vec<_>>();
+            let model = SacModelCylinder::with_data(&data);
+            let coarse = ([0.02, 0., 0.], [0.05, 1., 1.].normalized(), 0.45);
+            let (point, axis, radius) = model.refine_model_coefficients(&inliers, &coarse).unwrap();
+            assert!(axis.dot(&dir).abs() > 0.99999);
+            assert!(point.cross(&dir).len() < 1e-3);
+            assert!((radius - 0.5).abs() < 1e-3);
+        }
+
+        #[test]
+        fn refine_in_segment() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let data = (0..400)
+                .map(|i| {
+                    [
+                        (i / 20) as f32 * 0.05,
+                        (i % 20) as f32 * 0.05,
+                        noise(&mut rng, 0.005) as f32,
+                    ]
+                })
+                .chain((0..100).map(|i| [(i % 10) as f32 * 0.1, (i / 10) as f32 * 0.1, 0.5]))
+                .collect::<Vec<_>>();
+            let mut sac = SacSegment {
+                model: PlaneCoefficient::<f32>::default(),
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    refine_iterations: 10,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            assert_eq!(sac.inliers, (0..400).collect::<Vec<_>>());
+            let coefficients = sac.model.coefficients;
+            assert!(coefficients[2].abs() > 0.99999);
+            assert!(coefficients[3].abs() < 1e-3);
+
+            // Perpendicular plane keeps constraint.
+            let mut sac = SacSegment {
+                model: PerpendicularPlaneCoefficient {
+                    axis: [0f32, 0., 1.],
+                    eps_angle: 0.1f32.to_radians(),
+                    ..Default::default()
+                },
+                algorithm_parameter: SacAlgorithmParameter {
+                    threshold: 0.01,
+                    seed: Some(42),
+                    refine_iterations: 10,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert!(sac.compute(&data));
+            let coefficients = sac.model.coefficients;
+            assert!(coefficients[2].abs() > 0.1f32.to_radians().cos());
+        }
+    }
 }
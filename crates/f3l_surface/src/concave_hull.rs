@@ -100,6 +100,7 @@ impl ConcaveHull<3> {
         let mut algorithm = f3l_segmentation::sac_algorithm::SacRansac {
             parameter,
             inliers: vec![],
+            ..Default::default()
         };
         algorithm.compute(&mut model);
         let mut coefficients = model.get_coefficient();
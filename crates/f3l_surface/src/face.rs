@@ -54,3 +54,9 @@ impl<P: Copy> From<FaceInstanceType<P>> for Face<P> {
         }
     }
 }
+
+impl From<FaceIdType> for [usize; 3] {
+    fn from(value: FaceIdType) -> Self {
+        value.point
+    }
+}
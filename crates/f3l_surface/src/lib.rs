@@ -1,9 +1,11 @@
 mod concave_hull;
 mod convex_hull;
 mod face;
+mod moving_least_squares;
 mod triangulation;
 
 pub use concave_hull::*;
 pub use convex_hull::*;
 pub use face::*;
+pub use moving_least_squares::*;
 pub use triangulation::*;
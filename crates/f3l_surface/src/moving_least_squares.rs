@@ -0,0 +1,583 @@
+use std::{collections::HashSet, ops::Index};
+
+use f3l_core::{
+    jacobi_eigen_square_n, pivoted_solve,
+    rayon::prelude::*,
+    seeded_rng,
+    serde::{self, Deserialize, Serialize},
+    BasicFloat, EigenSet,
+};
+use f3l_search_tree::{KdTree, TreeSearch};
+use rand::Rng;
+
+/// Upsampling methods of [`MovingLeastSquares`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub enum MlsUpsampling<T: BasicFloat> {
+    /// Project each point to its surface only.
+    None,
+    /// Grid of `step` inside `radius` on local plane of each point, projected to surface.
+    SampleLocalPlane { radius: T, step: T },
+    /// Add random points in search radius of each point, until `density` points in it.
+    RandomUniformDensity { density: usize },
+    /// Voxelize projected points, dilate voxels `iterations` times,
+    /// then project centers of voxels to surface of nearest point.
+    VoxelGridDilation { voxel: T, iterations: usize },
+}
+
+/// Local polynomial surface of a point.
+///
+/// Height along `normal` is a polynomial of `(x, y)` on plane of `u`, `v` through `origin`.
+#[derive(Debug, Clone)]
+struct LocalSurface<T: BasicFloat> {
+    origin: [T; 3],
+    u: [T; 3],
+    v: [T; 3],
+    normal: [T; 3],
+    /// Local coordinates are divided by it before evaluating monomials.
+    scale: T,
+    /// Coefficients of monomials `x^i y^j`, `i + j <= order`. Empty as plane.
+    coefficients: Vec<T>,
+    nb_neighbors: usize,
+}
+
+/// Moving Least Squares smoothing and upsampling.
+///
+/// Ref: Alexa et al., Computing and Rendering Point Set Surfaces.
+///
+/// 1. Search neighbors in `radius` of each point by [`KdTree`].
+/// 2. Fit a plane by weighted covariance, weights are gaussian of distance.
+/// 3. Fit a polynomial of `order` as height on the plane by weighted least squares.
+/// 4. Project point (or samples of [`MlsUpsampling`]) to the polynomial surface.
+///
+/// Points with less than 3 neighbors are dropped, and a polynomial falls back to plane
+/// if neighbors are not enough for it. Source point of each output is kept in `sources`.
+///
+/// # Examples
+/// ```
+/// let vertices = load_ply("../../data/table_scene_lms400.ply");
+///
+/// let mut mls = MovingLeastSquares::new(0.03f32);
+/// mls.set_compute_normals(true);
+/// mls.compute(&vertices);
+/// let smoothed: Vec<[f32; 3]> = mls.points();
+/// let normals = mls.normals();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct MovingLeastSquares<T: BasicFloat> {
+    /// Radius to search neighbors.
+    pub radius: T,
+    /// Order of polynomial, `0` to project to plane only. Default: 2.
+    pub order: usize,
+    /// Square of gaussian parameter of weights, `None` for square of `radius`.
+    pub sqr_gauss: Option<T>,
+    pub compute_normals: bool,
+    pub upsampling: MlsUpsampling<T>,
+    /// Seed of [`MlsUpsampling::RandomUniformDensity`], random every call if `None`.
+    pub seed: Option<u64>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    points: Vec<[T; 3]>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing, default = "Vec::new")]
+    normals: Vec<[T; 3]>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    sources: Vec<usize>,
+}
+
+#[inline]
+fn dot<T: BasicFloat>(a: &[T; 3], b: &[T; 3]) -> T {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+fn cross<T: BasicFloat>(a: &[T; 3], b: &[T; 3]) -> [T; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[inline]
+fn normalized<T: BasicFloat>(a: [T; 3]) -> [T; 3] {
+    let len = dot(&a, &a).sqrt();
+    [a[0] / len, a[1] / len, a[2] / len]
+}
+
+impl<T: BasicFloat> LocalSurface<T> {
+    /// Exponents of monomials.
+    fn monomials(order: usize) -> impl Iterator<Item = (i32, i32)> {
+        (0..=order as i32).flat_map(move |i| (0..=order as i32 - i).map(move |j| (i, j)))
+    }
+
+    /// Point and normal on surface of local coordinate `(x, y)`.
+    fn project(&self, x: T, y: T, order: usize) -> ([T; 3], [T; 3]) {
+        let (mut height, mut dx, mut dy) = (T::zero(), T::zero(), T::zero());
+        let (sx, sy) = (x / self.scale, y / self.scale);
+        Self::monomials(order)
+            .zip(&self.coefficients)
+            .for_each(|((i, j), &c)| {
+                height += c * sx.powi(i) * sy.powi(j);
+                if i > 0 {
+                    dx += c * T::from(i).unwrap() * sx.powi(i - 1) * sy.powi(j) / self.scale;
+                }
+                if j > 0 {
+                    dy += c * T::from(j).unwrap() * sx.powi(i) * sy.powi(j - 1) / self.scale;
+                }
+            });
+        let mut point = [T::zero(); 3];
+        let mut normal = [T::zero(); 3];
+        (0..3).for_each(|k| {
+            point[k] = self.origin[k] + x * self.u[k] + y * self.v[k] + height * self.normal[k];
+            normal[k] = self.normal[k] - dx * self.u[k] - dy * self.v[k];
+        });
+        (point, normalized(normal))
+    }
+
+    /// Local coordinate of `p` on plane.
+    fn local(&self, p: &[T; 3]) -> (T, T) {
+        let d = [
+            p[0] - self.origin[0],
+            p[1] - self.origin[1],
+            p[2] - self.origin[2],
+        ];
+        (dot(&d, &self.u), dot(&d, &self.v))
+    }
+}
+
+impl<T: BasicFloat> MovingLeastSquares<T> {
+    pub fn new(radius: T) -> Self {
+        Self {
+            radius,
+            order: 2,
+            sqr_gauss: None,
+            compute_normals: false,
+            upsampling: MlsUpsampling::None,
+            seed: None,
+            points: vec![],
+            normals: vec![],
+            sources: vec![],
+        }
+    }
+
+    pub fn set_order(&mut self, order: usize) {
+        self.order = order;
+    }
+
+    pub fn set_compute_normals(&mut self, compute_normals: bool) {
+        self.compute_normals = compute_normals;
+    }
+
+    pub fn set_upsampling(&mut self, upsampling: MlsUpsampling<T>) {
+        self.upsampling = upsampling;
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn points<P>(&self) -> Vec<P>
+    where
+        [T; 3]: Into<P>,
+    {
+        self.points.iter().map(|&p| p.into()).collect()
+    }
+
+    /// Normals of `points`, empty if `compute_normals` is false.
+    pub fn normals(&self) -> Vec<[T; 3]> {
+        self.normals.clone()
+    }
+
+    /// Index of data, which surface each of `points` is projected to.
+    pub fn sources(&self) -> Vec<usize> {
+        self.sources.clone()
+    }
+
+    fn fit(&self, p: [T; 3], neighbors: &[[T; 3]]) -> Option<LocalSurface<T>> {
+        if neighbors.len() < 3 {
+            return None;
+        }
+        let sqr_gauss = self.sqr_gauss.unwrap_or(self.radius * self.radius);
+        let weights = neighbors
+            .iter()
+            .map(|q| {
+                let d = (0..3).fold(T::zero(), |acc, k| acc + (q[k] - p[k]).powi(2));
+                (-d / sqr_gauss).exp()
+            })
+            .collect::<Vec<_>>();
+        let sum = weights.iter().fold(T::zero(), |acc, &w| acc + w);
+
+        let mut centroid = [T::zero(); 3];
+        neighbors.iter().zip(&weights).for_each(|(q, &w)| {
+            (0..3).for_each(|k| centroid[k] += q[k] * w / sum);
+        });
+        let mut cov = [[T::zero(); 3]; 3];
+        neighbors.iter().zip(&weights).for_each(|(q, &w)| {
+            (0..3).for_each(|r| {
+                (0..3).for_each(|c| {
+                    cov[r][c] += w * (q[r] - centroid[r]) * (q[c] - centroid[c]);
+                })
+            });
+        });
+        if cov.iter().flatten().any(|v| !v.is_finite()) {
+            return None;
+        }
+        let eigen = EigenSet(jacobi_eigen_square_n(cov));
+        let normal = normalized(eigen.minimal().eigenvector);
+        if normal.iter().any(|v| !v.is_finite()) {
+            return None;
+        }
+
+        let offset = (0..3).fold(T::zero(), |acc, k| acc + (p[k] - centroid[k]) * normal[k]);
+        let origin = [
+            p[0] - offset * normal[0],
+            p[1] - offset * normal[1],
+            p[2] - offset * normal[2],
+        ];
+        // Axis least aligned with normal.
+        let axis = (0..3)
+            .min_by(|&a, &b| {
+                normal[a]
+                    .abs()
+                    .to_f64()
+                    .unwrap()
+                    .total_cmp(&normal[b].abs().to_f64().unwrap())
+            })
+            .unwrap();
+        let mut e = [T::zero(); 3];
+        e[axis] = T::one();
+        let u = normalized(cross(&normal, &e));
+        let v = cross(&normal, &u);
+
+        let mut surface = LocalSurface {
+            origin,
+            u,
+            v,
+            normal,
+            // Keep monomials near unit for small radius.
+            scale: self.radius,
+            coefficients: vec![],
+            nb_neighbors: neighbors.len(),
+        };
+
+        let nb_coefficients = (self.order + 1) * (self.order + 2) / 2;
+        if self.order > 0 && neighbors.len() >= nb_coefficients {
+            let mut a = vec![vec![T::zero(); nb_coefficients]; nb_coefficients];
+            let mut b = vec![T::zero(); nb_coefficients];
+            neighbors.iter().zip(&weights).for_each(|(q, &w)| {
+                let (x, y) = surface.local(q);
+                let (x, y) = (x / surface.scale, y / surface.scale);
+                let d = [q[0] - origin[0], q[1] - origin[1], q[2] - origin[2]];
+                let height = dot(&d, &normal);
+                let m = LocalSurface::<T>::monomials(self.order)
+                    .map(|(i, j)| x.powi(i) * y.powi(j))
+                    .collect::<Vec<_>>();
+                (0..nb_coefficients).for_each(|r| {
+                    (0..nb_coefficients).for_each(|c| a[r][c] += w * m[r] * m[c]);
+                    b[r] += w * m[r] * height;
+                });
+            });
+            // Pivot is singular when relatively small to the largest entry of `a`.
+            let tolerance = a
+                .iter()
+                .flatten()
+                .fold(T::zero(), |acc, v| acc.max(v.abs()))
+                * T::epsilon();
+            if let Some(coefficients) = pivoted_solve(&a, &b, tolerance) {
+                surface.coefficients = coefficients;
+            }
+        }
+        Some(surface)
+    }
+
+    pub fn compute<P>(&mut self, data: &[P]) -> bool
+    where
+        P: Into<[T; 3]> + Clone + Copy + Send + Sync + Index<usize, Output = T>,
+        [T; 3]: Into<P>,
+    {
+        self.points.clear();
+        self.normals.clear();
+        self.sources.clear();
+        if data.is_empty() || self.radius <= T::zero() {
+            return false;
+        }
+
+        let mut tree = KdTree::<T, P>::new(3);
+        tree.set_data(data);
+        tree.build();
+
+        let surfaces = data
+            .par_iter()
+            .map(|p| {
+                let neighbors = tree
+                    .search_radius(p, self.radius)
+                    .into_iter()
+                    .map(|q| q.into())
+                    .collect::<Vec<[T; 3]>>();
+                self.fit((*p).into(), &neighbors)
+            })
+            .collect::<Vec<_>>();
+
+        let order = self.order;
+        let outputs: Vec<(usize, [T; 3], [T; 3])> = match self.upsampling {
+            MlsUpsampling::None => surfaces
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, s)| {
+                    s.as_ref().map(|s| {
+                        let (p, n) = s.project(T::zero(), T::zero(), order);
+                        (i, p, n)
+                    })
+                })
+                .collect(),
+            MlsUpsampling::SampleLocalPlane { radius, step } => {
+                if step <= T::zero() {
+                    return false;
+                }
+                let steps = (radius / step).floor().to_i64().unwrap_or(0);
+                surfaces
+                    .par_iter()
+                    .enumerate()
+                    .flat_map_iter(|(i, s)| {
+                        (-steps..=steps)
+                            .flat_map(move |x| (-steps..=steps).map(move |y| (x, y)))
+                            .filter_map(move |(x, y)| {
+                                let s = s.as_ref()?;
+                                let x = T::from(x).unwrap() * step;
+                                let y = T::from(y).unwrap() * step;
+                                if x * x + y * y > radius * radius {
+                                    return None;
+                                }
+                                let (p, n) = s.project(x, y, order);
+                                Some((i, p, n))
+                            })
+                    })
+                    .collect()
+            }
+            MlsUpsampling::RandomUniformDensity { density } => {
+                let mut rng = seeded_rng(self.seed);
+                let mut outputs = vec![];
+                surfaces.iter().enumerate().for_each(|(i, s)| {
+                    let s = match s {
+                        Some(s) => s,
+                        None => return,
+                    };
+                    let (p, n) = s.project(T::zero(), T::zero(), order);
+                    outputs.push((i, p, n));
+                    (s.nb_neighbors..density).for_each(|_| {
+                        // Uniform in disc of search radius.
+                        let r = self.radius * T::from(rng.gen::<f64>().sqrt()).unwrap();
+                        let theta = T::from(rng.gen::<f64>() * std::f64::consts::TAU).unwrap();
+                        let (p, n) = s.project(r * theta.cos(), r * theta.sin(), order);
+                        outputs.push((i, p, n));
+                    });
+                });
+                outputs
+            }
+            MlsUpsampling::VoxelGridDilation { voxel, iterations } => {
+                if voxel <= T::zero() {
+                    return false;
+                }
+                // None on non-finite or out of range point, which is skipped.
+                let key = |p: &[T; 3]| -> Option<[i64; 3]> {
+                    let mut k = [0i64; 3];
+                    for (k, v) in k.iter_mut().zip(p) {
+                        *k = (*v / voxel).floor().to_i64()?;
+                    }
+                    Some(k)
+                };
+                let mut voxels = surfaces
+                    .iter()
+                    .flatten()
+                    .filter_map(|s| key(&s.project(T::zero(), T::zero(), order).0))
+                    .collect::<HashSet<_>>();
+                (0..iterations).for_each(|_| {
+                    voxels = voxels
+                        .iter()
+                        .flat_map(|k| {
+                            (-1..=1).flat_map(move |x| {
+                                (-1..=1).flat_map(move |y| {
+                                    (-1..=1).map(move |z| {
+                                        [
+                                            k[0].saturating_add(x),
+                                            k[1].saturating_add(y),
+                                            k[2].saturating_add(z),
+                                        ]
+                                    })
+                                })
+                            })
+                        })
+                        .collect();
+                });
+                let mut voxels = voxels.into_iter().collect::<Vec<_>>();
+                voxels.sort();
+
+                let half = T::from(0.5).unwrap();
+                voxels
+                    .par_iter()
+                    .filter_map(|k| {
+                        let center = k.map(|v| (T::from(v).unwrap() + half) * voxel);
+                        let nearest = *tree.search_knn_ids(&center.into(), 1).first()?;
+                        let s = surfaces[nearest].as_ref()?;
+                        let (x, y) = s.local(&center);
+                        let (p, n) = s.project(x, y, order);
+                        Some((nearest, p, n))
+                    })
+                    .collect()
+            }
+        };
+
+        self.sources = outputs.iter().map(|o| o.0).collect();
+        self.points = outputs.iter().map(|o| o.1).collect();
+        if self.compute_normals {
+            self.normals = outputs.iter().map(|o| o.2).collect();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+fn noisy_plane(nb: usize) -> Vec<[f32; 3]> {
+    let mut rng = seeded_rng(Some(0));
+    (0..nb)
+        .flat_map(|x| (0..nb).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            [
+                x as f32 * 0.1,
+                y as f32 * 0.1,
+                0.3 + rng.gen_range(-0.01f32..0.01),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn smoothing() {
+    let data = noisy_plane(20);
+    let mut mls = MovingLeastSquares::new(0.25f32);
+    mls.set_compute_normals(true);
+    assert!(mls.compute(&data));
+
+    let points: Vec<[f32; 3]> = mls.points();
+    assert_eq!(points.len(), data.len());
+    assert_eq!(mls.sources(), (0..data.len()).collect::<Vec<_>>());
+    let error = |cloud: &[[f32; 3]]| cloud.iter().map(|p| (p[2] - 0.3).abs()).sum::<f32>();
+    assert!(error(&points) < error(&data) * 0.5);
+    assert!(mls.normals().iter().all(|n| n[2].abs() > 0.95));
+}
+
+#[test]
+fn curved() {
+    // Paraboloid z = x^2 + y^2, order 2 recovers it exactly.
+    let data = (-10..=10)
+        .flat_map(|x| (-10..=10).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let (x, y) = (x as f64 * 0.05, y as f64 * 0.05);
+            [x, y, x * x + y * y]
+        })
+        .collect::<Vec<_>>();
+    let mut mls = MovingLeastSquares::new(0.16f64);
+    mls.compute(&data);
+    let points: Vec<[f64; 3]> = mls.points();
+    assert!(points
+        .iter()
+        .all(|p| (p[2] - p[0] * p[0] - p[1] * p[1]).abs() < 1e-3));
+}
+
+#[test]
+fn curved_small_radius() {
+    // Paraboloid z = 100 (x^2 + y^2) at millimeter scale.
+    let data = (-10..=10)
+        .flat_map(|x| (-10..=10).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let (x, y) = (x as f32 * 5e-4, y as f32 * 5e-4);
+            [x, y, 100. * (x * x + y * y)]
+        })
+        .collect::<Vec<_>>();
+    let mut mls = MovingLeastSquares::new(1.6e-3f32);
+    mls.compute(&data);
+    let points: Vec<[f32; 3]> = mls.points();
+    assert_eq!(points.len(), data.len());
+    assert!(points
+        .iter()
+        .all(|p| (p[2] - 100. * (p[0] * p[0] + p[1] * p[1])).abs() < 2e-5));
+}
+
+#[test]
+fn non_finite_dilation() {
+    // Plane of 5 x 5 voxels, centered in voxels to avoid rounding of keys.
+    let mut data = (0..5)
+        .flat_map(|x| (0..5).map(move |y| [x as f32 * 0.1 + 0.05, y as f32 * 0.1 + 0.05, 0.35]))
+        .collect::<Vec<_>>();
+    data.push([f32::INFINITY, 0.05, 0.35]);
+    // Surface of far points, whose voxel key is out of range.
+    data.extend([[1e20, 0.05, 0.35]; 3]);
+    let mut mls = MovingLeastSquares::new(0.25f32);
+    mls.set_upsampling(MlsUpsampling::VoxelGridDilation {
+        voxel: 0.1,
+        iterations: 1,
+    });
+    assert!(mls.compute(&data));
+    let points: Vec<[f32; 3]> = mls.points();
+    // One point per voxel of 7 x 7 x 3 dilated voxels, others are skipped.
+    assert_eq!(points.len(), 7 * 7 * 3);
+    assert!(points.iter().all(|p| p.iter().all(|v| v.is_finite())));
+}
+
+#[test]
+fn upsampling() {
+    let data = noisy_plane(10);
+
+    let mut mls = MovingLeastSquares::new(0.25f32);
+    mls.set_upsampling(MlsUpsampling::SampleLocalPlane {
+        radius: 0.05,
+        step: 0.05,
+    });
+    mls.compute(&data);
+    // Center and 4 neighbors of grid.
+    assert_eq!(mls.points::<[f32; 3]>().len(), data.len() * 5);
+
+    mls.set_upsampling(MlsUpsampling::RandomUniformDensity { density: 30 });
+    mls.set_seed(Some(42));
+    mls.compute(&data);
+    let first: Vec<[f32; 3]> = mls.points();
+    assert!(first.len() > data.len());
+    mls.compute(&data);
+    assert_eq!(first, mls.points::<[f32; 3]>());
+
+    mls.set_upsampling(MlsUpsampling::VoxelGridDilation {
+        voxel: 0.05,
+        iterations: 1,
+    });
+    mls.compute(&data);
+    let points: Vec<[f32; 3]> = mls.points();
+    assert!(points.len() > data.len());
+    assert!(points.iter().all(|p| (p[2] - 0.3).abs() < 0.05));
+}
+
+#[test]
+fn serde() {
+    let mut model = MovingLeastSquares::new(0.03f32);
+    model.set_upsampling(MlsUpsampling::SampleLocalPlane {
+        radius: 0.01,
+        step: 0.005,
+    });
+    let content = serde_json::to_string(&model).unwrap();
+    println!("{}", content);
+
+    let text = r#"{
+        "radius":0.03,
+        "order":2,
+        "sqr_gauss":null,
+        "compute_normals":false,
+        "upsampling":{"SampleLocalPlane":{"radius":0.01,"step":0.005}},
+        "seed":null
+    }"#;
+    let model_de: MovingLeastSquares<f32> = serde_json::from_str(text).unwrap();
+    assert_eq!(model.radius, model_de.radius);
+    assert_eq!(model.order, model_de.order);
+    assert_eq!(model.upsampling, model_de.upsampling);
+}